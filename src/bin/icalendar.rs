@@ -0,0 +1,375 @@
+//! Command-line tool for validating, formatting and converting `.ics` files.
+//!
+//! Run `icalendar help` for the list of commands.
+use std::{
+    error::Error,
+    fs,
+    io::{self, Read, Write},
+    path::Path,
+    process::ExitCode,
+};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use icalendar::{
+    Calendar, CalendarComponent, Component, DatePerhapsTime, MergeOutcome, MergeStrategy,
+    Normalization, Severity, TimeRange, jcal, parser,
+    vdir::{Vdir, VdirError},
+};
+use uuid::Uuid;
+
+const USAGE: &str = "\
+Usage: icalendar <command> [options] [files...]
+
+Reads the given files, or stdin if there are none or a file is `-`.
+
+Commands:
+    validate [files...]               report RFC 5545 violations, fails on errors
+    fmt [-w] [files...]               normalize and refold, -w rewrites the files
+    to-json [file]                    convert to jCal (RFC 7265)
+    from-json [file]                  convert from jCal
+    expand --from T --to T [file]     list the occurrences within a time range
+    merge [--strategy S] [files...]   merge into the first calendar, S is newest, ours or theirs
+    split --dir DIR [file]            write one file per UID into DIR
+    query [--uid UID] [--from T] [--to T] [file]
+                                      print the components with the UID or within the time range
+    help                              print this message
+
+Times are dates like 20240301, UTC date-times like 20240301T100000Z or RFC 3339.";
+
+type Result<T, E = Box<dyn Error>> = std::result::Result<T, E>;
+
+/// Command-line arguments, split into options and positional files.
+#[derive(Debug, Default)]
+struct Args {
+    options: Vec<(String, Option<String>)>,
+    files: Vec<String>,
+}
+
+impl Args {
+    /// Splits `args`, options in `with_value` consume the following argument.
+    fn parse(args: impl IntoIterator<Item = String>, with_value: &[&str]) -> Result<Self> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg.starts_with('-') && arg != "-" {
+                let value = if with_value.contains(&arg.as_str()) {
+                    Some(args.next().ok_or(format!("{arg} needs a value"))?)
+                } else {
+                    None
+                };
+                parsed.options.push((arg, value));
+            } else {
+                parsed.files.push(arg);
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(option, _)| option == name)
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.options.iter().any(|(option, _)| option == name)
+    }
+
+    /// Fails on options the command doesn't know.
+    fn only(&self, known: &[&str]) -> Result<()> {
+        match self
+            .options
+            .iter()
+            .find(|(option, _)| !known.contains(&option.as_str()))
+        {
+            Some((option, _)) => Err(format!("unknown option {option}").into()),
+            None => Ok(()),
+        }
+    }
+
+    /// The files, `-` for stdin if there are none.
+    fn inputs(&self) -> Vec<&str> {
+        if self.files.is_empty() {
+            vec!["-"]
+        } else {
+            self.files.iter().map(String::as_str).collect()
+        }
+    }
+
+    /// The only file, `-` for stdin if there is none.
+    fn input(&self) -> Result<&str> {
+        match self.files.as_slice() {
+            [] => Ok("-"),
+            [file] => Ok(file),
+            _ => Err("expected at most one file".into()),
+        }
+    }
+
+    fn range(&self) -> Result<Option<TimeRange>> {
+        let from = self.value("--from").map(parse_time).transpose()?;
+        let to = self.value("--to").map(parse_time).transpose()?;
+        Ok(match (from, to) {
+            (Some(from), Some(to)) => Some(TimeRange::new(from, to)),
+            (Some(from), None) => Some(TimeRange::starting_at(from)),
+            (None, Some(to)) => Some(TimeRange::ending_at(to)),
+            (None, None) => None,
+        })
+    }
+}
+
+fn parse_time(time: &str) -> Result<DateTime<Utc>> {
+    if let Ok(date) = NaiveDate::parse_from_str(time, "%Y%m%d") {
+        return Ok(date.and_time(Default::default()).and_utc());
+    }
+    if let Ok(date_time) = NaiveDateTime::parse_from_str(time, "%Y%m%dT%H%M%SZ") {
+        return Ok(date_time.and_utc());
+    }
+    DateTime::parse_from_rfc3339(time)
+        .map(|date_time| date_time.to_utc())
+        .map_err(|_| format!("invalid time {time:?}").into())
+}
+
+/// A time the way a content line holds it, with its `TZID` if it has one, e.g. `TZID=Europe/Berlin:20240301T100000`.
+fn format_time(time: Option<DatePerhapsTime>) -> String {
+    let Some(time) = time else {
+        return "-".to_owned();
+    };
+    let property = time.to_property("DTSTART");
+    match property.params().get("TZID") {
+        Some(tzid) => format!("TZID={}:{}", tzid.value(), property.value()),
+        None => property.value().to_owned(),
+    }
+}
+
+fn read(file: &str) -> Result<String> {
+    let mut content = String::new();
+    if file == "-" {
+        io::stdin().read_to_string(&mut content)?;
+    } else {
+        content = fs::read_to_string(file).map_err(|error| format!("{file}: {error}"))?;
+    }
+    Ok(content)
+}
+
+fn parse(file: &str) -> Result<Calendar> {
+    read(file)?
+        .parse()
+        .map_err(|error| format!("{file}: {error}").into())
+}
+
+fn write(calendar: &Calendar) -> Result<()> {
+    let mut out = io::stdout().lock();
+    calendar.write_to(&mut out)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// A calendar with the properties and time zones of `calendar` and the given components.
+fn subset<'a>(
+    calendar: &Calendar,
+    components: impl IntoIterator<Item = &'a CalendarComponent>,
+) -> Calendar {
+    let mut subset = Calendar {
+        properties: calendar.properties.clone(),
+        components: Vec::new(),
+    };
+    subset.components.extend(
+        calendar
+            .components
+            .iter()
+            .filter(|component| component.component_kind() == "VTIMEZONE")
+            .cloned(),
+    );
+    subset.components.extend(components.into_iter().cloned());
+    subset
+}
+
+fn validate(args: &Args) -> Result<bool> {
+    args.only(&[])?;
+    let mut valid = true;
+    for file in args.inputs() {
+        for violation in parse(file)?.validate() {
+            println!("{file}: {}: {violation}", violation.severity);
+            valid &= violation.severity != Severity::Error;
+        }
+    }
+    Ok(valid)
+}
+
+fn fmt(args: &Args) -> Result<bool> {
+    args.only(&["-w", "--write"])?;
+    let in_place = args.flag("-w") || args.flag("--write");
+    for file in args.inputs() {
+        let mut calendar = parse(file)?;
+        calendar.normalize_with(&Normalization::new().stable());
+        // unlike `Calendar`, the parsed form is written without generating a missing `UID` or `DTSTAMP`
+        let formatted = parser::Calendar::from(calendar).to_string();
+        if in_place && file != "-" {
+            replace(Path::new(file), &formatted).map_err(|error| format!("{file}: {error}"))?;
+        } else {
+            let mut out = io::stdout().lock();
+            out.write_all(formatted.as_bytes())?;
+            out.flush()?;
+        }
+    }
+    Ok(true)
+}
+
+/// Replaces the file at `path` by renaming a temporary file onto it, so it is never left half written.
+fn replace(path: &Path, content: &str) -> io::Result<()> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temporary = path.with_file_name(format!(".{name}.{}.tmp", Uuid::new_v4()));
+    let written = (|| {
+        let mut file = fs::File::create_new(&temporary)?;
+        file.write_all(content.as_bytes())?;
+        file.flush()?;
+        file.sync_all()?;
+        fs::rename(&temporary, path)
+    })();
+    if written.is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+    written
+}
+
+fn to_json(args: &Args) -> Result<bool> {
+    args.only(&[])?;
+    let calendar = parse(args.input()?)?;
+    println!("{}", jcal::to_string(&calendar));
+    Ok(true)
+}
+
+fn from_json(args: &Args) -> Result<bool> {
+    args.only(&[])?;
+    let file = args.input()?;
+    let calendar = jcal::from_str(&read(file)?).map_err(|error| format!("{file}: {error}"))?;
+    write(&calendar)?;
+    Ok(true)
+}
+
+fn expand(args: &Args) -> Result<bool> {
+    args.only(&["--from", "--to"])?;
+    let calendar = parse(args.input()?)?;
+    let (Some(from), Some(to)) = (args.value("--from"), args.value("--to")) else {
+        return Err("expand needs --from and --to".into());
+    };
+    let range = TimeRange::new(parse_time(from)?, parse_time(to)?);
+    for occurrence in calendar.occurrences_in_range(&range) {
+        println!(
+            "{}\t{}\t{}\t{}",
+            format_time(occurrence.start),
+            format_time(occurrence.end),
+            occurrence.component.get_uid().unwrap_or("-"),
+            occurrence.component.get_summary().unwrap_or_default()
+        );
+    }
+    Ok(true)
+}
+
+fn merge(args: &Args) -> Result<bool> {
+    args.only(&["--strategy"])?;
+    let strategy = match args.value("--strategy").unwrap_or("newest") {
+        "newest" => MergeStrategy::Newest,
+        "ours" => MergeStrategy::Ours,
+        "theirs" => MergeStrategy::Theirs,
+        other => return Err(format!("unknown strategy {other:?}").into()),
+    };
+    let mut files = args.inputs().into_iter();
+    let mut calendar = parse(files.next().unwrap_or("-"))?;
+    for file in files {
+        for merged in calendar.merge(parse(file)?, &strategy) {
+            if merged.outcome != MergeOutcome::Unchanged {
+                eprintln!("{file}: {merged}");
+            }
+        }
+    }
+    write(&calendar)?;
+    Ok(true)
+}
+
+fn split(args: &Args) -> Result<bool> {
+    args.only(&["--dir"])?;
+    let dir = args.value("--dir").ok_or("split needs --dir")?;
+    let calendar = parse(args.input()?)?;
+    let vdir = Vdir::create(dir)?;
+    let mut complete = true;
+    for resource in calendar.split_by_uid() {
+        let stored = match vdir.create_item(&resource) {
+            Err(VdirError::Exists(uid)) => {
+                let (existing, _) = vdir.get(&uid)?.ok_or(VdirError::NotFound(uid.clone()))?;
+                vdir.update(&uid, &resource, &existing.etag)
+            }
+            stored => stored,
+        };
+        match stored {
+            Ok(item) => println!("{}", vdir.path().join(item.href).display()),
+            Err(VdirError::MissingUid) => {
+                let kind = resource
+                    .components
+                    .last()
+                    .map(Component::component_kind)
+                    .unwrap_or_default();
+                eprintln!("skipping {kind} without UID");
+                complete = false;
+            }
+            Err(error) => return Err(error.into()),
+        }
+    }
+    Ok(complete)
+}
+
+fn query(args: &Args) -> Result<bool> {
+    args.only(&["--uid", "--from", "--to"])?;
+    let calendar = parse(args.input()?)?;
+    let uid = args.value("--uid");
+    let in_range = args
+        .range()?
+        .map(|range| calendar.components_in_range(&range));
+    let matches = calendar.components.iter().filter(|component| {
+        component.component_kind() != "VTIMEZONE"
+            && uid.is_none_or(|uid| component.get_uid() == Some(uid))
+            && in_range.as_ref().is_none_or(|in_range| {
+                in_range
+                    .iter()
+                    .any(|matching| std::ptr::eq(*matching, *component))
+            })
+    });
+    write(&subset(&calendar, matches))?;
+    Ok(true)
+}
+
+fn run(command: &str, args: impl IntoIterator<Item = String>) -> Result<bool> {
+    match command {
+        "validate" => validate(&Args::parse(args, &[])?),
+        "fmt" => fmt(&Args::parse(args, &[])?),
+        "to-json" => to_json(&Args::parse(args, &[])?),
+        "from-json" => from_json(&Args::parse(args, &[])?),
+        "expand" => expand(&Args::parse(args, &["--from", "--to"])?),
+        "merge" => merge(&Args::parse(args, &["--strategy"])?),
+        "split" => split(&Args::parse(args, &["--dir"])?),
+        "query" => query(&Args::parse(args, &["--uid", "--from", "--to"])?),
+        "help" | "-h" | "--help" => {
+            println!("{USAGE}");
+            Ok(true)
+        }
+        other => Err(format!("unknown command {other:?}\n\n{USAGE}").into()),
+    }
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let Some(command) = args.next() else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    match run(&command, args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("icalendar: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
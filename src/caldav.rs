@@ -0,0 +1,553 @@
+//! Evaluation of `CalDAV` `calendar-query` reports ([RFC 4791, Section 7.8](https://datatracker.ietf.org/doc/html/rfc4791#section-7.8)).
+//!
+//! This module models the `CALDAV:filter` element ([`CompFilter`], [`PropFilter`], [`ParamFilter`], [`TextMatch`])
+//! and the `CALDAV:calendar-data` element ([`CompSelector`]) and evaluates them against parsed calendar object resources.
+//! Reading the XML request body is left to the server.
+//!
+//! ```
+//! # use icalendar::{caldav::*, parser::read_calendar, TimeRange};
+//! # use chrono::*;
+//! let resource = read_calendar(
+//!     "BEGIN:VCALENDAR\n\
+//!      BEGIN:VEVENT\n\
+//!      UID:standup\n\
+//!      SUMMARY:Daily Standup\n\
+//!      DTSTART:20260302T090000Z\n\
+//!      DTEND:20260302T091500Z\n\
+//!      END:VEVENT\n\
+//!      END:VCALENDAR\n",
+//! )
+//! .unwrap();
+//!
+//! let filter = CompFilter::new("VCALENDAR").comp_filter(
+//!     CompFilter::new("VEVENT")
+//!         .time_range(TimeRange::starting_at(Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap()))
+//!         .prop_filter(PropFilter::new("SUMMARY").text_match(TextMatch::new("standup"))),
+//! );
+//! assert!(filter.matches(&resource));
+//!
+//! let data = CompSelector::new("VCALENDAR")
+//!     .comp(CompSelector::new("VEVENT").prop("UID").prop("DTSTART"));
+//! let partial = data.select(&resource);
+//! assert_eq!(partial.components[0].properties.len(), 2);
+//! ```
+use std::{borrow::Cow, str::FromStr};
+
+use crate::{
+    TimeRange,
+    components::{Other, date_time::DatePerhapsTime},
+    parser::{Calendar, Component, Parameter, Property, components::LikeComponent},
+};
+
+/// Errors that can occur when building a filter.
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum FilterError {
+    /// The requested collation is not one of the collations `CalDAV` servers are required to support.
+    ///
+    /// Servers should answer this with the `CALDAV:supported-collation` precondition.
+    #[error("unsupported collation: {0}")]
+    UnsupportedCollation(String),
+}
+
+/// Collation used to compare text in a [`TextMatch`].
+///
+/// See [RFC 4790](https://datatracker.ietf.org/doc/html/rfc4790) for the collation registry.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Collation {
+    /// `i;octet`, compares the exact bytes.
+    Octet,
+    /// `i;ascii-casemap`, ignores the case of ASCII letters.
+    #[default]
+    AsciiCasemap,
+    /// `i;unicode-casemap` ([RFC 5051](https://datatracker.ietf.org/doc/html/rfc5051)), ignores the case of all letters.
+    ///
+    /// Characters are case folded but not decomposed, so compatibility variants of a character are not equal.
+    UnicodeCasemap,
+}
+
+impl Collation {
+    /// The identifier of the collation as used in the `collation` attribute.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Collation::Octet => "i;octet",
+            Collation::AsciiCasemap => "i;ascii-casemap",
+            Collation::UnicodeCasemap => "i;unicode-casemap",
+        }
+    }
+
+    /// Returns `true` if `needle` occurs anywhere in `haystack` under this collation.
+    pub fn contains(&self, haystack: &str, needle: &str) -> bool {
+        self.fold(haystack).contains(self.fold(needle).as_ref())
+    }
+
+    fn fold<'s>(&self, text: &'s str) -> Cow<'s, str> {
+        match self {
+            Collation::Octet => Cow::Borrowed(text),
+            Collation::AsciiCasemap => Cow::Owned(text.to_ascii_lowercase()),
+            // uppercasing first turns characters like `ß` into `SS` so they fold like their expansion
+            Collation::UnicodeCasemap => Cow::Owned(text.to_uppercase().to_lowercase()),
+        }
+    }
+}
+
+impl FromStr for Collation {
+    type Err = FilterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "i;octet" => Ok(Collation::Octet),
+            "i;ascii-casemap" => Ok(Collation::AsciiCasemap),
+            "i;unicode-casemap" => Ok(Collation::UnicodeCasemap),
+            other => Err(FilterError::UnsupportedCollation(other.to_owned())),
+        }
+    }
+}
+
+/// The `CALDAV:text-match` element, a substring search in property or parameter values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextMatch {
+    value: String,
+    collation: Collation,
+    negate: bool,
+}
+
+impl TextMatch {
+    /// Matches values containing `value`, compared with [`Collation::AsciiCasemap`].
+    pub fn new(value: impl Into<String>) -> Self {
+        TextMatch {
+            value: value.into(),
+            collation: Collation::default(),
+            negate: false,
+        }
+    }
+
+    /// Sets the collation used for comparing.
+    pub fn collation(mut self, collation: Collation) -> Self {
+        self.collation = collation;
+        self
+    }
+
+    /// Inverts the match, like `negate-condition="yes"`.
+    pub fn negate(mut self) -> Self {
+        self.negate = true;
+        self
+    }
+
+    /// Returns `true` if `text` satisfies this condition.
+    pub fn matches(&self, text: &str) -> bool {
+        self.collation.contains(text, &self.value) != self.negate
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ParamTest {
+    IsNotDefined,
+    TextMatch(TextMatch),
+}
+
+/// The `CALDAV:param-filter` element.
+///
+/// Without further conditions it matches if the property has the parameter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParamFilter {
+    name: String,
+    test: Option<ParamTest>,
+}
+
+impl ParamFilter {
+    /// Matches properties that have a parameter called `name`.
+    pub fn new(name: impl Into<String>) -> Self {
+        ParamFilter {
+            name: name.into(),
+            test: None,
+        }
+    }
+
+    /// Matches properties that do not have the parameter instead.
+    pub fn is_not_defined(mut self) -> Self {
+        self.test = Some(ParamTest::IsNotDefined);
+        self
+    }
+
+    /// Only matches if the value of the parameter satisfies `text_match`.
+    pub fn text_match(mut self, text_match: TextMatch) -> Self {
+        self.test = Some(ParamTest::TextMatch(text_match));
+        self
+    }
+
+    /// The name of the parameter this filter applies to.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns `true` if `property` satisfies this filter.
+    pub fn matches(&self, property: &Property<'_>) -> bool {
+        let mut named = property
+            .params
+            .iter()
+            .filter(|param| param.key.as_str().eq_ignore_ascii_case(&self.name));
+        match &self.test {
+            None => named.next().is_some(),
+            Some(ParamTest::IsNotDefined) => named.next().is_none(),
+            Some(ParamTest::TextMatch(text_match)) => named.any(|Parameter { val, .. }| {
+                text_match.matches(val.as_ref().map_or("", |val| val.as_str()))
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ValueTest {
+    TimeRange(TimeRange),
+    TextMatch(TextMatch),
+}
+
+/// The `CALDAV:prop-filter` element.
+///
+/// Without further conditions it matches if the component has the property.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PropFilter {
+    name: String,
+    is_not_defined: bool,
+    test: Option<ValueTest>,
+    param_filters: Vec<ParamFilter>,
+}
+
+impl PropFilter {
+    /// Matches components that have a property called `name`.
+    pub fn new(name: impl Into<String>) -> Self {
+        PropFilter {
+            name: name.into(),
+            is_not_defined: false,
+            test: None,
+            param_filters: Vec::new(),
+        }
+    }
+
+    /// Matches components that do not have the property instead.
+    ///
+    /// All other conditions are ignored.
+    pub fn is_not_defined(mut self) -> Self {
+        self.is_not_defined = true;
+        self
+    }
+
+    /// Only matches if the value of the property, a date or date-time, lies within `range`.
+    ///
+    /// Replaces a previous [`PropFilter::text_match`].
+    pub fn time_range(mut self, range: TimeRange) -> Self {
+        self.test = Some(ValueTest::TimeRange(range));
+        self
+    }
+
+    /// Only matches if the value of the property satisfies `text_match`.
+    ///
+    /// Replaces a previous [`PropFilter::time_range`].
+    pub fn text_match(mut self, text_match: TextMatch) -> Self {
+        self.test = Some(ValueTest::TextMatch(text_match));
+        self
+    }
+
+    /// Only matches if the property also satisfies `filter`.
+    pub fn param_filter(mut self, filter: ParamFilter) -> Self {
+        self.param_filters.push(filter);
+        self
+    }
+
+    /// The name of the property this filter applies to.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns `true` if `component` satisfies this filter.
+    pub fn matches(&self, component: &Component<'_>) -> bool {
+        self.matches_within(&component.properties, None)
+    }
+
+    fn matches_within(&self, properties: &[Property<'_>], timezone: Option<&str>) -> bool {
+        let mut named = properties
+            .iter()
+            .filter(|property| property.name.as_str().eq_ignore_ascii_case(&self.name));
+        if self.is_not_defined {
+            return named.next().is_none();
+        }
+        named.any(|property| self.test(property, timezone))
+    }
+
+    fn test(&self, property: &Property<'_>, timezone: Option<&str>) -> bool {
+        let value_matches = match &self.test {
+            None => true,
+            Some(ValueTest::TextMatch(text_match)) => text_match.matches(property.val.as_str()),
+            Some(ValueTest::TimeRange(range)) => DatePerhapsTime::try_from(property)
+                .is_ok_and(|value| range.or_timezone(timezone).contains_value(&value)),
+        };
+        value_matches
+            && self
+                .param_filters
+                .iter()
+                .all(|filter| filter.matches(property))
+    }
+}
+
+/// The `CALDAV:comp-filter` element.
+///
+/// Without further conditions it matches if a component with the given name exists.
+/// Nested filters must all be satisfied by the same component.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompFilter {
+    name: String,
+    is_not_defined: bool,
+    time_range: Option<TimeRange>,
+    prop_filters: Vec<PropFilter>,
+    comp_filters: Vec<CompFilter>,
+}
+
+impl CompFilter {
+    /// Matches components called `name`.
+    ///
+    /// The outermost filter of a query must be called `VCALENDAR`.
+    pub fn new(name: impl Into<String>) -> Self {
+        CompFilter {
+            name: name.into(),
+            is_not_defined: false,
+            time_range: None,
+            prop_filters: Vec::new(),
+            comp_filters: Vec::new(),
+        }
+    }
+
+    /// Matches if no such component exists instead.
+    ///
+    /// All other conditions are ignored.
+    pub fn is_not_defined(mut self) -> Self {
+        self.is_not_defined = true;
+        self
+    }
+
+    /// Only matches components that overlap `range`, see [`TimeRange::matches`].
+    ///
+    /// Floating times are evaluated in the range's timezone or else the calendar's `X-WR-TIMEZONE`.
+    pub fn time_range(mut self, range: TimeRange) -> Self {
+        self.time_range = Some(range);
+        self
+    }
+
+    /// Only matches components that also satisfy `filter`.
+    pub fn prop_filter(mut self, filter: PropFilter) -> Self {
+        self.prop_filters.push(filter);
+        self
+    }
+
+    /// Only matches components with a sub-component that satisfies `filter`.
+    pub fn comp_filter(mut self, filter: CompFilter) -> Self {
+        self.comp_filters.push(filter);
+        self
+    }
+
+    /// The name of the component this filter applies to.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns `true` if the calendar object resource `calendar` satisfies this filter.
+    pub fn matches(&self, calendar: &Calendar<'_>) -> bool {
+        let timezone = calendar
+            .properties
+            .iter()
+            .find(|property| property.name == "X-WR-TIMEZONE")
+            .map(|property| property.val.as_str());
+
+        self.name.eq_ignore_ascii_case("VCALENDAR")
+            && !self.is_not_defined
+            && self.test(calendar, None::<&Component>, timezone)
+    }
+
+    /// Returns `true` if `component` satisfies this filter on its own.
+    ///
+    /// This does not know about the enclosing calendar, so `VALARM` time ranges can't be resolved.
+    pub fn matches_component(&self, component: &Component<'_>) -> bool {
+        self.matches_within(std::slice::from_ref(component), None::<&Component>, None)
+    }
+
+    fn matches_within<'a, P: LikeComponent<'a>>(
+        &self,
+        candidates: &[Component<'a>],
+        parent: Option<&P>,
+        timezone: Option<&str>,
+    ) -> bool {
+        let mut named = candidates
+            .iter()
+            .filter(|component| component.name.as_str().eq_ignore_ascii_case(&self.name));
+        if self.is_not_defined {
+            return named.next().is_none();
+        }
+        named.any(|component| self.test(component, parent, timezone))
+    }
+
+    fn test<'a, C: LikeComponent<'a>, P: LikeComponent<'a>>(
+        &self,
+        component: &C,
+        parent: Option<&P>,
+        timezone: Option<&str>,
+    ) -> bool {
+        let in_range = self.time_range.is_none_or(|range| {
+            let range = range.or_timezone(timezone);
+            match parent {
+                Some(parent) if self.name.eq_ignore_ascii_case("VALARM") => {
+                    range.matches_alarm(&to_other(component), &to_other(parent))
+                }
+                _ => range.matches(&to_other(component)),
+            }
+        });
+
+        in_range
+            && self
+                .prop_filters
+                .iter()
+                .all(|filter| filter.matches_within(component.properties(), timezone))
+            && self.comp_filters.iter().all(|filter| {
+                filter.matches_within(component.components(), Some(component), timezone)
+            })
+    }
+}
+
+fn to_other<'a>(component: &impl LikeComponent<'a>) -> Other {
+    Other::from(Component {
+        name: component.name().into(),
+        properties: component.properties().to_vec(),
+        components: component.components().to_vec(),
+    })
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Selection<T> {
+    All,
+    Only(Vec<T>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct PropSelector {
+    name: String,
+    novalue: bool,
+}
+
+/// The `CALDAV:comp` element of a `CALDAV:calendar-data` request, selects what parts of a resource to return.
+///
+/// A fresh selector keeps neither properties nor sub-components, add them with
+/// [`CompSelector::prop`] and [`CompSelector::comp`] or keep everything with
+/// [`CompSelector::all_props`] and [`CompSelector::all_comps`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompSelector {
+    name: String,
+    props: Selection<PropSelector>,
+    comps: Selection<CompSelector>,
+}
+
+impl CompSelector {
+    /// Selects components called `name`.
+    ///
+    /// The outermost selector describes the `VCALENDAR` itself.
+    pub fn new(name: impl Into<String>) -> Self {
+        CompSelector {
+            name: name.into(),
+            props: Selection::Only(Vec::new()),
+            comps: Selection::Only(Vec::new()),
+        }
+    }
+
+    /// Keeps all properties, like `CALDAV:allprop`.
+    pub fn all_props(mut self) -> Self {
+        self.props = Selection::All;
+        self
+    }
+
+    /// Keeps all sub-components unchanged, like `CALDAV:allcomp`.
+    pub fn all_comps(mut self) -> Self {
+        self.comps = Selection::All;
+        self
+    }
+
+    /// Keeps properties called `name`.
+    pub fn prop(self, name: impl Into<String>) -> Self {
+        self.add_prop(name.into(), false)
+    }
+
+    /// Keeps properties called `name` but drops their values, like `novalue="yes"`.
+    pub fn prop_without_value(self, name: impl Into<String>) -> Self {
+        self.add_prop(name.into(), true)
+    }
+
+    /// Keeps sub-components matching `selector`, trimmed down according to it.
+    pub fn comp(mut self, selector: CompSelector) -> Self {
+        match &mut self.comps {
+            Selection::All => self.comps = Selection::Only(vec![selector]),
+            Selection::Only(comps) => comps.push(selector),
+        }
+        self
+    }
+
+    /// The name of the component this selector applies to.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn add_prop(mut self, name: String, novalue: bool) -> Self {
+        let selector = PropSelector { name, novalue };
+        match &mut self.props {
+            Selection::All => self.props = Selection::Only(vec![selector]),
+            Selection::Only(props) => props.push(selector),
+        }
+        self
+    }
+
+    /// Returns the parts of `calendar` this selector asks for.
+    pub fn select<'a>(&self, calendar: &Calendar<'a>) -> Calendar<'a> {
+        Calendar {
+            properties: self.select_properties(&calendar.properties),
+            components: self.select_components(&calendar.components),
+        }
+    }
+
+    /// Returns the parts of `component` this selector asks for.
+    pub fn select_component<'a>(&self, component: &Component<'a>) -> Component<'a> {
+        Component {
+            name: component.name.clone(),
+            properties: self.select_properties(&component.properties),
+            components: self.select_components(&component.components),
+        }
+    }
+
+    fn select_properties<'a>(&self, properties: &[Property<'a>]) -> Vec<Property<'a>> {
+        let Selection::Only(selectors) = &self.props else {
+            return properties.to_vec();
+        };
+        properties
+            .iter()
+            .filter_map(|property| {
+                let selector = selectors
+                    .iter()
+                    .find(|selector| property.name.as_str().eq_ignore_ascii_case(&selector.name))?;
+                let mut property = property.clone();
+                if selector.novalue {
+                    property.val = "".into();
+                }
+                Some(property)
+            })
+            .collect()
+    }
+
+    fn select_components<'a>(&self, components: &[Component<'a>]) -> Vec<Component<'a>> {
+        let Selection::Only(selectors) = &self.comps else {
+            return components.to_vec();
+        };
+        components
+            .iter()
+            .filter_map(|component| {
+                let selector = selectors.iter().find(|selector| {
+                    component.name.as_str().eq_ignore_ascii_case(&selector.name)
+                })?;
+                Some(selector.select_component(component))
+            })
+            .collect()
+    }
+}
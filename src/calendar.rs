@@ -1,9 +1,14 @@
 use chrono::Duration;
-use std::{fmt, mem, ops::Deref};
+use std::{fmt, io, mem, ops::Deref};
 
 #[cfg(feature = "recurrence")]
 use crate::components::build_recurrence_set;
-use crate::{Parameter, Property, components::*};
+use crate::{
+    Change, MergeStrategy, Merged, Method, Normalization, Parameter, Property, Violation,
+    components::*,
+};
+#[cfg(feature = "recurrence")]
+use crate::{Occurrence, TimeRange};
 
 /// Accepted by [`Calendar::timezone`].
 ///
@@ -44,9 +49,13 @@ impl IntoTimezoneId for chrono_tz::Tz {
     }
 }
 
+mod by_uid;
 mod calendar_component;
+pub(crate) mod writer;
 
+pub use by_uid::UidGroup;
 pub use calendar_component::CalendarComponent;
+pub use writer::CalendarWriter;
 
 /// Represents a calendar
 ///
@@ -164,10 +173,66 @@ impl Calendar {
     }
 
     /// Moves all the elements of other into Self, leaving other empty.
+    ///
+    /// Use [`merge`](Calendar::merge) if both may contain versions of the same component.
     pub fn append(&mut self, other: &mut Calendar) {
         self.components.append(&mut other.components);
     }
 
+    /// Merges the components of `other` into this calendar.
+    ///
+    /// Components are matched by `UID` and `RECURRENCE-ID`, and `strategy` decides which version is kept.
+    /// `VTIMEZONE`s are matched by `TZID` and ours are always kept.
+    /// Unmatched components are appended, the properties of `other` are ignored.
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// let mut ours = Calendar::new();
+    /// ours.push(Event::new().uid("merge").summary("old").sequence(1).done());
+    /// let mut theirs = Calendar::new();
+    /// theirs.push(Event::new().uid("merge").summary("new").sequence(2).done());
+    /// theirs.push(Event::new().uid("other").done());
+    ///
+    /// let merged = ours.merge(theirs, &MergeStrategy::Newest);
+    /// assert_eq!(
+    ///     merged.iter().map(|merged| merged.to_string()).collect::<Vec<_>>(),
+    ///     ["VEVENT merge: replaced", "VEVENT other: added"]
+    /// );
+    /// assert_eq!(ours.components[0].as_event().unwrap().get_summary(), Some("new"));
+    /// ```
+    pub fn merge(&mut self, other: Calendar, strategy: &MergeStrategy) -> Vec<Merged> {
+        crate::merge::merge(self, other, strategy)
+    }
+
+    /// Orders the components chronologically, see [`ByStart`](crate::ordering::ByStart).
+    ///
+    /// Floating values are evaluated in the calendar's `X-WR-TIMEZONE`, or UTC if it has none.
+    /// `VTIMEZONE`s are moved to the front, components without start to the end,
+    /// otherwise equal components keep their order.
+    #[cfg(feature = "chrono-tz")]
+    pub fn sort_by_start(&mut self) {
+        let mut order = crate::ordering::ByStart::new();
+        if let Some(timezone) = self.get_timezone().and_then(|tz| tz.parse().ok()) {
+            order = order.with_timezone(timezone);
+        }
+        self.sort_by_start_with(&order);
+    }
+
+    /// Orders the components chronologically, evaluating floating values as `order` says.
+    #[cfg(feature = "chrono-tz")]
+    pub fn sort_by_start_with(&mut self, order: &crate::ordering::ByStart) {
+        crate::ordering::sort_by_start(self, order);
+    }
+
+    /// Puts components and their subcomponents into an order that only depends on their content,
+    /// see [`ordering::canonical`](crate::ordering::canonical).
+    ///
+    /// Properties are always written in a fixed order, except for those of calendars read with `from_str_lossless`,
+    /// so two calendars with the same components serialize the same after this.
+    pub fn sort_canonical(&mut self) {
+        crate::ordering::sort_canonical(self);
+    }
+
     /// Append a given `Property` to the `Calendar`
     pub fn append_property(&mut self, property: impl Into<Property>) -> &mut Self {
         self.properties.push(property.into());
@@ -228,6 +293,27 @@ impl Calendar {
             .or_else(|| self.property_value("X-WR-CALDESC"))
     }
 
+    /// Set the [`METHOD`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.7.2) property,
+    /// replacing a previous one.
+    ///
+    /// Calendars with a method are iTIP scheduling messages, see [`crate::itip`].
+    pub fn method(&mut self, method: Method) -> &mut Self {
+        self.remove_method();
+        self.append_property(method)
+    }
+
+    /// Gets the `METHOD` property.
+    pub fn get_method(&self) -> Option<Method> {
+        Method::from_str(self.property_value("METHOD")?)
+    }
+
+    /// Removes the `METHOD` property.
+    pub fn remove_method(&mut self) -> &mut Self {
+        self.properties
+            .retain(|property| property.key() != "METHOD");
+        self
+    }
+
     /// Set the `X-WR-TIMEZONE` property (the calendar's default timezone).
     ///
     /// Accepts a plain IANA string or, with the `chrono-tz` feature, a `chrono_tz::Tz`
@@ -309,6 +395,112 @@ impl Calendar {
         Ok(())
     }
 
+    /// Checks the calendar and all its components against RFC 5545.
+    ///
+    /// Unlike [`Component::validate`] this also checks that every `TZID` refers to a `VTIMEZONE` of the calendar
+    /// and that events have a `DTSTART` unless the calendar has a `METHOD`.
+    ///
+    /// Parsing with [`str::parse`] keeps only one of a repeated property that may occur once,
+    /// so read text to be checked with `from_str_lossless`, which keeps them all.
+    /// A missing `DTSTAMP` or `UID` is only a [warning](crate::Severity::Warning) where the serializer makes one up,
+    /// which it doesn't for components read losslessly.
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// let mut calendar = Calendar::new();
+    /// calendar.push(
+    ///     Event::new()
+    ///         .uid("validate")
+    ///         .starts(chrono::NaiveDate::from_ymd_opt(2024, 3, 1).unwrap())
+    ///         .add_property("DURATION", "P1D")
+    ///         .ends(chrono::NaiveDate::from_ymd_opt(2024, 3, 2).unwrap())
+    ///         .done(),
+    /// );
+    ///
+    /// let violations = calendar.validate();
+    /// assert_eq!(
+    ///     violations.iter().map(|violation| violation.to_string()).collect::<Vec<_>>(),
+    ///     [
+    ///         "VCALENDAR/VEVENT[0]: missing DTSTAMP",
+    ///         "VCALENDAR/VEVENT[0]: DTEND and DURATION exclude each other",
+    ///     ]
+    /// );
+    /// assert_eq!(violations[0].severity, Severity::Warning);
+    /// assert_eq!(violations[1].severity, Severity::Error);
+    /// ```
+    pub fn validate(&self) -> Vec<Violation> {
+        crate::validation::validate_calendar(self)
+    }
+
+    /// Repairs what [`validate`](Calendar::validate) reports where that needs no guessing,
+    /// and brings the calendar into a canonical form, see [`normalize_with`](Calendar::normalize_with).
+    pub fn normalize(&mut self) -> Vec<Change> {
+        self.normalize_with(&Normalization::default())
+    }
+
+    /// Repairs the calendar in place and reports every [`Change`] that was made.
+    ///
+    /// Always done:
+    /// * property and parameter names are uppercased
+    /// * repeated properties that may occur only once are dropped, the first one is kept
+    /// * missing `VERSION`, `PRODID`, `UID` and `DTSTAMP` are generated, `UID` and `DTSTAMP` unless [`Normalization::stable`]
+    /// * `DURATION` is dropped where `DTEND` or `DUE` are present as well
+    /// * `TZID`s are renamed to IANA names, e.g. `/mozilla.org/20050126_1/Europe/Berlin` or `W. Europe Standard Time` to `Europe/Berlin`
+    /// * properties are brought into a deterministic order
+    ///
+    /// Converting `DURATION` and placing floating times in a time zone are up to `options`.
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// let mut calendar: Calendar = "BEGIN:VCALENDAR\r
+    /// version:2.0\r
+    /// PRODID:normalize\r
+    /// BEGIN:VEVENT\r
+    /// UID:1\r
+    /// DTSTAMP:20240301T100000Z\r
+    /// DTSTART:20240301T100000\r
+    /// DURATION:PT1H\r
+    /// END:VEVENT\r
+    /// BEGIN:VTIMEZONE\r
+    /// TZID:Europe/Berlin\r
+    /// BEGIN:STANDARD\r
+    /// DTSTART:19701025T030000\r
+    /// TZOFFSETFROM:+0200\r
+    /// TZOFFSETTO:+0100\r
+    /// END:STANDARD\r
+    /// END:VTIMEZONE\r
+    /// END:VCALENDAR\r
+    /// "
+    /// .parse()
+    /// .unwrap();
+    ///
+    /// let options = Normalization::new()
+    ///     .ends(EndStyle::End)
+    ///     .floating_timezone("Europe/Berlin");
+    /// let changes = calendar.normalize_with(&options);
+    /// assert_eq!(
+    ///     changes.iter().map(ToString::to_string).collect::<Vec<_>>(),
+    ///     [
+    ///         "VCALENDAR: renamed version to VERSION",
+    ///         "VCALENDAR/VEVENT[0]: replaced DURATION with DTEND:20240301T110000",
+    ///         "VCALENDAR/VEVENT[0]: placed floating DTEND in time zone \"Europe/Berlin\"",
+    ///         "VCALENDAR/VEVENT[0]: placed floating DTSTART in time zone \"Europe/Berlin\"",
+    ///     ]
+    /// );
+    /// assert_eq!(calendar.normalize_with(&options), []);
+    /// ```
+    pub fn normalize_with(&mut self, options: &Normalization) -> Vec<Change> {
+        crate::normalize::normalize(self, options)
+    }
+
+    /// Writes `Calendar` into an [`io::Write`], one content line at a time.
+    ///
+    /// Unlike [`ToString::to_string`] this never builds the whole calendar in memory,
+    /// see [`CalendarWriter`] for producing the components one by one.
+    pub fn write_to<W: io::Write>(&self, out: &mut W) -> io::Result<()> {
+        writer::write_io(out, |out| self.fmt_write(out))
+    }
+
     /// Prints to stdout
     pub fn print(&self) -> Result<(), fmt::Error> {
         print_crlf!("{}", self);
@@ -390,6 +582,53 @@ impl Calendar {
             calendar_tz: tz,
         })
     }
+
+    /// Returns every instance of every component that overlaps `range`, ordered by start.
+    ///
+    /// Recurring components are expanded, instances that are overridden by a component with the
+    /// same `UID` and a matching `RECURRENCE-ID` are reported only once, via the override.
+    /// Overlap is decided by the rules of
+    /// [RFC 4791, Section 9.9](https://datatracker.ietf.org/doc/html/rfc4791#section-9.9),
+    /// see [`TimeRange`] for details.
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// # use chrono::*;
+    /// let standup = Event::new()
+    ///     .starts(Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap())
+    ///     .ends(Utc.with_ymd_and_hms(2026, 3, 2, 9, 15, 0).unwrap())
+    ///     .recurrence(RRule::default().freq(Frequency::Daily))
+    ///     .unwrap()
+    ///     .done();
+    /// let calendar = Calendar::from([standup]);
+    ///
+    /// let week = TimeRange::new(
+    ///     Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap(),
+    ///     Utc.with_ymd_and_hms(2026, 3, 9, 0, 0, 0).unwrap(),
+    /// );
+    /// assert_eq!(calendar.occurrences_in_range(&week).len(), 7);
+    /// ```
+    #[cfg(feature = "recurrence")]
+    pub fn occurrences_in_range(&self, range: &TimeRange) -> Vec<Occurrence<'_>> {
+        crate::time_range::occurrences_in_range(self, range)
+    }
+
+    /// Returns the components that have at least one instance overlapping `range`,
+    /// in the order they appear in the calendar.
+    ///
+    /// See [`Calendar::occurrences_in_range`] to get the individual instances.
+    #[cfg(feature = "recurrence")]
+    pub fn components_in_range(&self, range: &TimeRange) -> Vec<&CalendarComponent> {
+        let occurrences = self.occurrences_in_range(range);
+        self.components
+            .iter()
+            .filter(|component| {
+                occurrences
+                    .iter()
+                    .any(|occurrence| std::ptr::eq(*component, occurrence.component))
+            })
+            .collect()
+    }
 }
 
 /// Borrowed view of an [`Event`] paired with its calendar's timezone.
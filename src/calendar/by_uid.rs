@@ -0,0 +1,288 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::{Calendar, CalendarComponent, Component, Property};
+
+/// The components of a [`Calendar`] that share a `UID`,
+/// usually a recurring item and the occurrences it overrides, see [`Calendar::group_by_uid`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UidGroup<'a> {
+    /// The shared `UID`.
+    pub uid: &'a str,
+    /// The components without `RECURRENCE-ID`, there should only be one.
+    pub masters: Vec<&'a CalendarComponent>,
+    /// The components with a `RECURRENCE-ID`, each overriding one occurrence.
+    pub overrides: Vec<&'a CalendarComponent>,
+}
+
+impl<'a> UidGroup<'a> {
+    /// The component that defines the item, or its first definition if there are several.
+    pub fn master(&self) -> Option<&'a CalendarComponent> {
+        self.masters.first().copied()
+    }
+
+    /// The component that overrides the occurrence with the `RECURRENCE-ID` value `recurrence_id`.
+    pub fn instance(&self, recurrence_id: &str) -> Option<&'a CalendarComponent> {
+        self.overrides
+            .iter()
+            .find(|component| component.property_value("RECURRENCE-ID") == Some(recurrence_id))
+            .copied()
+    }
+
+    /// All components of the group, masters first.
+    pub fn components(&self) -> impl Iterator<Item = &'a CalendarComponent> + '_ {
+        self.masters.iter().chain(&self.overrides).copied()
+    }
+
+    /// Whether the `UID` is used by more than one item:
+    /// by several masters, by several overrides of the same occurrence or by different kinds of components.
+    pub fn is_duplicate(&self) -> bool {
+        let mut recurrence_ids = self
+            .overrides
+            .iter()
+            .filter_map(|component| component.property_value("RECURRENCE-ID"))
+            .collect::<Vec<_>>();
+        recurrence_ids.sort_unstable();
+        let repeated_override = recurrence_ids
+            .windows(2)
+            .any(|pair| matches!(pair, [a, b] if a == b));
+
+        let mut kinds = self.components().map(Component::component_kind);
+        let first_kind = kinds.next();
+        let mixed_kinds = kinds.any(|kind| Some(kind) != first_kind);
+
+        self.masters.len() > 1 || repeated_override || mixed_kinds
+    }
+}
+
+fn has_uid(component: &CalendarComponent, uid: &str) -> bool {
+    component.get_uid() == Some(uid)
+}
+
+fn is_timezone(component: &CalendarComponent) -> bool {
+    component.component_kind() == "VTIMEZONE"
+}
+
+/// Collects the `TZID` parameters used in `component` and the components nested in it.
+fn referenced_timezones<'c>(component: &'c impl Component, tzids: &mut BTreeSet<&'c str>) {
+    let properties = component
+        .properties()
+        .values()
+        .chain(component.multi_properties().values().flatten());
+    tzids.extend(properties.filter_map(|property| Some(property.params().get("TZID")?.value())));
+    for child in component.components() {
+        referenced_timezones(child, tzids);
+    }
+}
+
+impl Calendar {
+    /// All components with the `UID` `uid`, the master and the occurrences it overrides, in the order of the calendar.
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// let mut calendar = Calendar::new();
+    /// calendar.push(Event::with_uid("standup").summary("Standup").done());
+    /// calendar.push(
+    ///     Event::with_uid("standup")
+    ///         .add_property("RECURRENCE-ID", "20240304T090000Z")
+    ///         .summary("Standup, later")
+    ///         .done(),
+    /// );
+    /// calendar.push(Todo::with_uid("notes").done());
+    ///
+    /// assert_eq!(calendar.find_by_uid("standup").count(), 2);
+    ///
+    /// for component in calendar.get_mut_by_uid("standup") {
+    ///     component.append_property(("LOCATION", "Room 4"));
+    /// }
+    /// let removed = calendar.remove_by_uid("standup");
+    /// assert!(removed.iter().all(|component| component.property_value("LOCATION") == Some("Room 4")));
+    /// assert_eq!(calendar.components.len(), 1);
+    /// ```
+    pub fn find_by_uid<'c>(&'c self, uid: &'c str) -> impl Iterator<Item = &'c CalendarComponent> {
+        self.components
+            .iter()
+            .filter(move |component| has_uid(component, uid))
+    }
+
+    /// Like [`find_by_uid`](Calendar::find_by_uid), but mutable.
+    pub fn get_mut_by_uid<'c>(
+        &'c mut self,
+        uid: &'c str,
+    ) -> impl Iterator<Item = &'c mut CalendarComponent> {
+        self.components
+            .iter_mut()
+            .filter(move |component| has_uid(component, uid))
+    }
+
+    /// Removes all components with the `UID` `uid` and returns them.
+    pub fn remove_by_uid(&mut self, uid: &str) -> Vec<CalendarComponent> {
+        let (removed, kept) = std::mem::take(&mut self.components)
+            .into_iter()
+            .partition(|component| has_uid(component, uid));
+        self.components = kept;
+        removed
+    }
+
+    /// The components grouped by their `UID`, in the order each `UID` first occurs.
+    ///
+    /// Components without `UID`, like `VTIMEZONE`s, are left out.
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// let mut calendar = Calendar::new();
+    /// calendar.push(Event::with_uid("series").add_property("RRULE", "FREQ=DAILY").done());
+    /// calendar.push(
+    ///     Event::with_uid("series")
+    ///         .add_property("RECURRENCE-ID", "20240302T100000Z")
+    ///         .done(),
+    /// );
+    /// calendar.push(Event::with_uid("single").done());
+    ///
+    /// let groups = calendar.group_by_uid();
+    /// assert_eq!(groups.len(), 2);
+    /// assert_eq!(groups[0].uid, "series");
+    /// assert!(groups[0].master().is_some());
+    /// assert!(groups[0].instance("20240302T100000Z").is_some());
+    /// assert!(groups[1].overrides.is_empty());
+    /// ```
+    pub fn group_by_uid(&self) -> Vec<UidGroup<'_>> {
+        let mut groups: Vec<UidGroup<'_>> = Vec::new();
+        let mut positions = HashMap::new();
+        for component in &self.components {
+            let Some(uid) = component.get_uid() else {
+                continue;
+            };
+            let position = *positions.entry(uid).or_insert_with(|| {
+                groups.push(UidGroup {
+                    uid,
+                    masters: Vec::new(),
+                    overrides: Vec::new(),
+                });
+                groups.len() - 1
+            });
+            if let Some(group) = groups.get_mut(position) {
+                if component.properties().contains_key("RECURRENCE-ID") {
+                    group.overrides.push(component);
+                } else {
+                    group.masters.push(component);
+                }
+            }
+        }
+        groups
+    }
+
+    /// The groups of [`group_by_uid`](Calendar::group_by_uid) in which the `UID` is used by more than one item,
+    /// see [`UidGroup::is_duplicate`].
+    pub fn duplicate_uids(&self) -> Vec<UidGroup<'_>> {
+        self.group_by_uid()
+            .into_iter()
+            .filter(UidGroup::is_duplicate)
+            .collect()
+    }
+
+    /// Splits the calendar into one calendar per `UID`, as `CalDAV` servers and vdir collections store them.
+    ///
+    /// Each calendar holds a master together with the occurrences it overrides,
+    /// the `VTIMEZONE`s they reference and the `VERSION`, `PRODID` and `CALSCALE` of this calendar.
+    /// Components without `UID` get a calendar of their own, unless they are `VTIMEZONE`s.
+    /// [`Calendar::join`] puts them back together.
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// let mut calendar = Calendar::new();
+    /// calendar.push(Event::with_uid("first").done());
+    /// calendar.push(
+    ///     Event::with_uid("first")
+    ///         .add_property("RECURRENCE-ID", "20240302T100000Z")
+    ///         .done(),
+    /// );
+    /// calendar.push(Todo::with_uid("second").done());
+    ///
+    /// let resources = calendar.split_by_uid();
+    /// assert_eq!(resources.len(), 2);
+    /// assert_eq!(resources[0].components.len(), 2);
+    /// assert_eq!(resources[1].property_value("VERSION"), Some("2.0"));
+    ///
+    /// assert_eq!(Calendar::join(resources), calendar);
+    /// ```
+    pub fn split_by_uid(&self) -> Vec<Calendar> {
+        let mut header = Calendar::new();
+        for property in &mut header.properties {
+            if let Some(value) = self.property_value(property.key()) {
+                *property = Property::new(property.key(), value);
+            }
+        }
+
+        let mut resources: Vec<Calendar> = Vec::new();
+        let mut positions: HashMap<&str, usize> = HashMap::new();
+        for component in &self.components {
+            if is_timezone(component) {
+                continue;
+            }
+            let resource = match component.get_uid() {
+                Some(uid) => positions
+                    .get(uid)
+                    .and_then(|position| resources.get_mut(*position)),
+                None => None,
+            };
+            match resource {
+                Some(resource) => resource.components.push(component.clone()),
+                None => {
+                    if let Some(uid) = component.get_uid() {
+                        positions.insert(uid, resources.len());
+                    }
+                    let mut resource = header.clone();
+                    resource.components.push(component.clone());
+                    resources.push(resource);
+                }
+            }
+        }
+
+        for resource in &mut resources {
+            let mut tzids = BTreeSet::new();
+            for component in &resource.components {
+                referenced_timezones(component, &mut tzids);
+            }
+            let timezones = self
+                .components
+                .iter()
+                .filter(|component| {
+                    is_timezone(component)
+                        && component
+                            .property_value("TZID")
+                            .is_some_and(|tzid| tzids.contains(tzid))
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            resource.components.splice(0..0, timezones);
+        }
+        resources
+    }
+
+    /// Joins calendars into one, e.g. those [`Calendar::split_by_uid`] produced.
+    ///
+    /// The properties are those of the first calendar.
+    /// The `VTIMEZONE`s come first, only the first one of each `TZID` is kept.
+    pub fn join(calendars: impl IntoIterator<Item = Calendar>) -> Calendar {
+        let mut joined = Calendar::empty();
+        let mut timezones = Vec::new();
+        let mut tzids = HashSet::new();
+        for (index, calendar) in calendars.into_iter().enumerate() {
+            if index == 0 {
+                joined.properties = calendar.properties;
+            }
+            for component in calendar.components {
+                if !is_timezone(&component) {
+                    joined.components.push(component);
+                    continue;
+                }
+                let tzid = component.property_value("TZID").map(ToOwned::to_owned);
+                if tzid.is_none_or(|tzid| tzids.insert(tzid)) {
+                    timezones.push(component);
+                }
+            }
+        }
+        joined.components.splice(0..0, timezones);
+        joined
+    }
+}
@@ -1,7 +1,7 @@
-use crate::Component;
+use crate::{Component, Property, components::InnerComponent};
 
 use super::{Event, Other, Todo, Venue};
-use std::fmt;
+use std::{collections::BTreeMap, fmt};
 
 /// Wrapper for [`Todo`], [`Event`] or [`Venue`]
 #[allow(missing_docs)]
@@ -104,7 +104,78 @@ impl From<&mut Other> for CalendarComponent {
     }
 }
 
+/// Forwards to the wrapped component, whichever variant it is.
+macro_rules! delegate {
+    ($self:ident, $inner:ident => $body:expr) => {
+        match $self {
+            CalendarComponent::Todo($inner) => $body,
+            CalendarComponent::Event($inner) => $body,
+            CalendarComponent::Venue($inner) => $body,
+            CalendarComponent::Other($inner) => $body,
+        }
+    };
+}
+
+/// Gives uniform access to the wrapped component regardless of its variant.
+impl Component for CalendarComponent {
+    fn component_kind(&self) -> String {
+        delegate!(self, inner => inner.component_kind())
+    }
+
+    fn properties(&self) -> &BTreeMap<String, Property> {
+        delegate!(self, inner => inner.properties())
+    }
+
+    fn components(&self) -> &[Other] {
+        delegate!(self, inner => inner.components())
+    }
+
+    fn multi_properties(&self) -> &BTreeMap<String, Vec<Property>> {
+        delegate!(self, inner => inner.multi_properties())
+    }
+
+    fn append_property(&mut self, property: impl Into<Property>) -> &mut Self {
+        delegate!(self, inner => {
+            inner.append_property(property);
+        });
+        self
+    }
+
+    fn append_component(&mut self, child: impl Into<Other>) -> &mut Self {
+        delegate!(self, inner => {
+            inner.append_component(child);
+        });
+        self
+    }
+
+    fn append_multi_property(&mut self, property: impl Into<Property>) -> &mut Self {
+        delegate!(self, inner => {
+            inner.append_multi_property(property);
+        });
+        self
+    }
+
+    fn remove_property(&mut self, key: &str) -> &mut Self {
+        delegate!(self, inner => {
+            inner.remove_property(key);
+        });
+        self
+    }
+
+    fn remove_multi_property(&mut self, key: &str) -> &mut Self {
+        delegate!(self, inner => {
+            inner.remove_multi_property(key);
+        });
+        self
+    }
+}
+
 impl CalendarComponent {
+    /// Mutable access to properties and children, used by repairs within the crate.
+    pub(crate) fn inner_mut(&mut self) -> &mut InnerComponent {
+        delegate!(self, inner => inner.inner_mut())
+    }
+
     pub(crate) fn fmt_write<W: fmt::Write>(&self, out: &mut W) -> Result<(), fmt::Error> {
         match *self {
             CalendarComponent::Todo(ref todo) => todo.fmt_write(out),
@@ -0,0 +1,94 @@
+use std::{
+    fmt::{self, Write as _},
+    io,
+};
+
+use crate::{Calendar, Component};
+
+/// Forwards everything written through [`fmt::Write`] to an [`io::Write`],
+/// keeping the [`io::Error`] that [`fmt::Error`] can't carry.
+pub(crate) struct IoAdapter<'a, W: io::Write> {
+    out: &'a mut W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> fmt::Write for IoAdapter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.out.write_all(s.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+            fmt::Error
+        })
+    }
+}
+
+/// Runs a `fmt_write` against an [`io::Write`].
+pub(crate) fn write_io<W: io::Write>(
+    out: &mut W,
+    write: impl FnOnce(&mut IoAdapter<'_, W>) -> fmt::Result,
+) -> io::Result<()> {
+    let mut adapter = IoAdapter { out, error: None };
+    write(&mut adapter).map_err(|fmt::Error| {
+        adapter
+            .error
+            .take()
+            .unwrap_or_else(|| io::Error::other("formatter error"))
+    })
+}
+
+/// Writes a calendar one component at a time,
+/// so that large exports never have to be held in memory as a whole.
+///
+/// Every content line is written to `out` as soon as it is complete,
+/// wrap `out` in a [`std::io::BufWriter`] to avoid lots of small writes.
+///
+/// ```
+/// # use icalendar::*;
+/// let header = Calendar::new().name("exported").done();
+/// let mut writer = CalendarWriter::new(Vec::new(), &header).unwrap();
+/// for n in 0..3 {
+///     writer
+///         .write(&Event::with_uid(&format!("event-{n}")).summary("generated").done())
+///         .unwrap();
+/// }
+/// let bytes = writer.finish().unwrap();
+///
+/// let text = String::from_utf8(bytes).unwrap();
+/// assert!(text.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+/// assert_eq!(text.matches("BEGIN:VEVENT").count(), 3);
+/// assert!(text.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+/// ```
+#[derive(Debug)]
+pub struct CalendarWriter<W: io::Write> {
+    out: W,
+}
+
+impl<W: io::Write> CalendarWriter<W> {
+    /// Starts a calendar with the properties of `header`.
+    ///
+    /// Components already contained in `header` are written right away.
+    pub fn new(mut out: W, header: &Calendar) -> io::Result<Self> {
+        write_io(&mut out, |out| {
+            write_crlf!(out, "BEGIN:VCALENDAR")?;
+            for property in &header.properties {
+                property.fmt_write(out)?;
+            }
+            for component in &header.components {
+                component.fmt_write(out)?;
+            }
+            Ok(())
+        })?;
+        Ok(CalendarWriter { out })
+    }
+
+    /// Appends a component to the calendar.
+    pub fn write<C: Component>(&mut self, component: &C) -> io::Result<()> {
+        component.write_to(&mut self.out)
+    }
+
+    /// Ends the calendar, flushes and hands back the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.out.write_all(b"END:VCALENDAR\r\n")?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
@@ -3,9 +3,9 @@ use crate::recurrence::RecurrenceError;
 use chrono::{DateTime, NaiveDate, Utc};
 use uuid::Uuid;
 
-use std::{collections::BTreeMap, fmt, mem};
+use std::{collections::BTreeMap, fmt, io, mem};
 
-use crate::{Attendee, properties::*};
+use crate::{Attendee, Violation, properties::*};
 use date_time::{format_utc_date_time, naive_date_to_property, parse_utc_date_time};
 
 pub mod alarm;
@@ -69,6 +69,23 @@ impl InnerComponent {
     }
 }
 
+/// All properties in the order they were read by [`Calendar::from_str_lossless`](crate::Calendar::from_str_lossless),
+/// followed by those added since, or `None` if none of them were read that way.
+fn in_source_order<'c>(
+    properties: &'c BTreeMap<String, Property>,
+    multi_properties: &'c BTreeMap<String, Vec<Property>>,
+) -> Option<Vec<&'c Property>> {
+    let mut all = properties
+        .values()
+        .chain(multi_properties.values().flatten())
+        .collect::<Vec<_>>();
+    if all.iter().all(|property| property.position().is_none()) {
+        return None;
+    }
+    all.sort_by_key(|property| property.position().unwrap_or(usize::MAX));
+    Some(all)
+}
+
 /// Implemented by everything that goes into a `Calendar`
 pub trait Component {
     /// Returns kind of component.
@@ -96,21 +113,28 @@ pub trait Component {
     fn fmt_write<W: fmt::Write>(&self, out: &mut W) -> Result<(), fmt::Error> {
         write_crlf!(out, "BEGIN:{}", self.component_kind())?;
 
-        if !self.properties().contains_key("DTSTAMP") {
-            let now = Utc::now();
-            write_crlf!(out, "DTSTAMP:{}", format_utc_date_time(now))?;
-        }
+        // components that were read are written without generating anything
+        if let Some(properties) = in_source_order(self.properties(), self.multi_properties()) {
+            for property in properties {
+                property.fmt_write(out)?;
+            }
+        } else {
+            if !self.properties().contains_key("DTSTAMP") {
+                let now = Utc::now();
+                write_crlf!(out, "DTSTAMP:{}", format_utc_date_time(now))?;
+            }
 
-        for property in self.properties().values() {
-            property.fmt_write(out)?;
-        }
+            for property in self.properties().values() {
+                property.fmt_write(out)?;
+            }
 
-        if !self.properties().contains_key("UID") {
-            write_crlf!(out, "UID:{}", Uuid::new_v4())?;
-        }
+            if !self.properties().contains_key("UID") {
+                write_crlf!(out, "UID:{}", Uuid::new_v4())?;
+            }
 
-        for property in self.multi_properties().values().flatten() {
-            property.fmt_write(out)?;
+            for property in self.multi_properties().values().flatten() {
+                property.fmt_write(out)?;
+            }
         }
 
         for component in self.components() {
@@ -121,6 +145,11 @@ pub trait Component {
         Ok(())
     }
 
+    /// Writes [`Component`] into an [`io::Write`], one content line at a time.
+    fn write_to<W: io::Write>(&self, out: &mut W) -> io::Result<()> {
+        crate::calendar::writer::write_io(out, |out| self.fmt_write(out))
+    }
+
     /// Serializes this component into [`rfc5545`](http://tools.ietf.org/html/rfc5545) again
     ///
     /// # Panic
@@ -137,6 +166,13 @@ pub trait Component {
         Ok(out_string)
     }
 
+    /// Checks this component and its children against RFC 5545.
+    ///
+    /// `TZID` references can only be checked as part of a whole calendar, see [`Calendar::validate`](crate::Calendar::validate).
+    fn validate(&self) -> Vec<Violation> {
+        crate::validation::validate_component(self)
+    }
+
     /// Append a given [`Property`]
     fn append_property(&mut self, property: impl Into<Property>) -> &mut Self;
 
@@ -283,6 +319,21 @@ pub trait Component {
             .unwrap_or_default()
     }
 
+    /// Set the [`ORGANIZER`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.4.3) by its CAL-ADDRESS (e.g., "mailto:user@example.com")
+    fn organizer(&mut self, cal_address: &str) -> &mut Self {
+        self.add_property("ORGANIZER", cal_address)
+    }
+
+    /// Removes the `ORGANIZER`
+    fn remove_organizer(&mut self) -> &mut Self {
+        self.remove_property("ORGANIZER")
+    }
+
+    /// Gets the CAL-ADDRESS of the `ORGANIZER`
+    fn get_organizer(&self) -> Option<&str> {
+        self.property_value("ORGANIZER")
+    }
+
     /// Set the UID
     fn uid(&mut self, uid: &str) -> &mut Self {
         self.add_property("UID", uid)
@@ -636,7 +687,10 @@ macro_rules! component_impl {
 
             /// Adds a [`Property`]
             fn append_property(&mut self, property: impl Into<Property>) -> &mut Self {
-                let property = property.into();
+                let mut property = property.into();
+                if let Some(replaced) = self.inner.properties.get(property.key()) {
+                    property = property.replacing(replaced);
+                }
                 self.inner
                     .properties
                     .insert(property.key().to_owned(), property);
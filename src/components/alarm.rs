@@ -333,6 +333,7 @@ pub mod properties {
                 key: String::from("ACTION"),
                 val: action.to_string(),
                 params: Default::default(),
+                source: None,
             }
         }
     }
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone as _, Utc};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone as _, Utc};
 
 use crate::{Property, ValueType};
 
@@ -21,10 +21,18 @@ pub(crate) fn format_utc_date_time(utc_dt: DateTime<Utc>) -> String {
     utc_dt.format(UTC_DATE_TIME_FORMAT).to_string()
 }
 
+/// Parses an [RFC 5545 duration](https://datatracker.ietf.org/doc/html/rfc5545#section-3.3.6),
+/// which unlike ISO 8601 may carry a leading sign.
 pub(crate) fn parse_duration(s: &str) -> Option<Duration> {
-    iso8601::duration(s)
+    let (negative, unsigned) = match s.as_bytes().first() {
+        Some(b'-') => (true, s.get(1..)?),
+        Some(b'+') => (false, s.get(1..)?),
+        _ => (false, s),
+    };
+    let duration = iso8601::duration(unsigned)
         .ok()
-        .and_then(|iso| Duration::from_std(iso.into()).ok())
+        .and_then(|iso| Duration::from_std(iso.into()).ok())?;
+    Some(if negative { -duration } else { duration })
 }
 
 pub(crate) fn naive_date_to_property(date: NaiveDate, key: &str) -> Property {
@@ -333,6 +341,51 @@ impl DatePerhapsTime {
         }
     }
 
+    /// The local date and time of this value, midnight for `DATE` values.
+    pub(crate) fn wall_clock(&self) -> NaiveDateTime {
+        match self {
+            Self::Date(date) => date.and_time(NaiveTime::MIN),
+            Self::DateTime(CalendarDateTime::Floating(date_time)) => *date_time,
+            Self::DateTime(CalendarDateTime::Utc(date_time)) => date_time.naive_utc(),
+            Self::DateTime(CalendarDateTime::WithTimezone { date_time, .. }) => *date_time,
+        }
+    }
+
+    /// Adds `delta` to the wall-clock value, keeping the form (date, floating, UTC, zoned) intact.
+    pub(crate) fn shifted(&self, delta: Duration) -> Self {
+        match self {
+            Self::Date(date) => Self::Date(*date + delta),
+            Self::DateTime(CalendarDateTime::Floating(date_time)) => {
+                CalendarDateTime::Floating(*date_time + delta).into()
+            }
+            Self::DateTime(CalendarDateTime::Utc(date_time)) => {
+                CalendarDateTime::Utc(*date_time + delta).into()
+            }
+            Self::DateTime(CalendarDateTime::WithTimezone { date_time, tzid }) => {
+                CalendarDateTime::WithTimezone {
+                    date_time: *date_time + delta,
+                    tzid: tzid.clone(),
+                }
+                .into()
+            }
+        }
+    }
+
+    /// Pins this value to an absolute instant.
+    ///
+    /// Floating date-times, `DATE` values and unknown `TZID`s are evaluated in `reference`.
+    #[cfg(feature = "chrono-tz")]
+    pub(crate) fn resolve(&self, reference: chrono_tz::Tz) -> Option<DateTime<Utc>> {
+        match self {
+            Self::DateTime(CalendarDateTime::Utc(date_time)) => Some(*date_time),
+            Self::DateTime(CalendarDateTime::WithTimezone { date_time, tzid }) => {
+                let tz = tzid.parse::<chrono_tz::Tz>().unwrap_or(reference);
+                local_to_utc(tz, *date_time)
+            }
+            other => local_to_utc(reference, other.wall_clock()),
+        }
+    }
+
     /// Discards time, assumes UTC, and returns an owned instance of a pure date
     pub fn date_naive(&self) -> NaiveDate {
         use crate::DatePerhapsTime::*;
@@ -345,6 +398,18 @@ impl DatePerhapsTime {
     }
 }
 
+/// Resolves a local time in `tz`, skipping forward over DST gaps.
+#[cfg(feature = "chrono-tz")]
+fn local_to_utc(tz: chrono_tz::Tz, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+    tz.from_local_datetime(&local)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(local + Duration::hours(1)))
+                .earliest()
+        })
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
 /// TODO: make public or delete
 #[cfg(feature = "chrono-tz")]
 #[allow(dead_code)]
@@ -512,6 +577,7 @@ mod try_from_tests {
             params: vec![crate::parser::Parameter {
                 key: "VALUE".into(),
                 val: Some("DATE-TIME".into()),
+                list: false,
             }],
         };
 
@@ -532,6 +598,7 @@ mod try_from_tests {
             params: vec![crate::parser::Parameter {
                 key: "VALUE".into(),
                 val: Some("DATE-TIME".into()),
+                list: false,
             }],
         };
 
@@ -550,10 +617,12 @@ mod try_from_tests {
                 crate::parser::Parameter {
                     key: "VALUE".into(),
                     val: Some("DATE-TIME".into()),
+                    list: false,
                 },
                 crate::parser::Parameter {
                     key: "TZID".into(),
                     val: Some("MY-TZ-ID".into()),
+                    list: false,
                 },
             ],
         };
@@ -579,10 +648,12 @@ mod try_from_tests {
                 crate::parser::Parameter {
                     key: "VALUE".into(),
                     val: Some("DATE-TIME".into()),
+                    list: false,
                 },
                 crate::parser::Parameter {
                     key: "TZID".into(),
                     val: None,
+                    list: false,
                 },
             ],
         };
@@ -600,6 +671,7 @@ mod try_from_tests {
             params: vec![crate::parser::Parameter {
                 key: "VALUE".into(),
                 val: Some("DATE-TIME".into()),
+                list: false,
             }],
         };
 
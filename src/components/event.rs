@@ -24,6 +24,11 @@ impl Event {
         }
     }
 
+    /// Mutable access to properties and children, used by repairs within the crate.
+    pub(crate) fn inner_mut(&mut self) -> &mut InnerComponent {
+        &mut self.inner
+    }
+
     /// Defines the overall status or confirmation
     pub fn status(&mut self, status: EventStatus) -> &mut Self {
         self.append_property(status)
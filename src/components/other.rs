@@ -65,3 +65,10 @@ impl From<(String, InnerComponent)> for Other {
         Self { name, inner }
     }
 }
+
+impl Other {
+    /// Mutable access to properties and children, used by repairs within the crate.
+    pub(crate) fn inner_mut(&mut self) -> &mut InnerComponent {
+        &mut self.inner
+    }
+}
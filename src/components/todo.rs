@@ -27,6 +27,11 @@ impl Todo {
         }
     }
 
+    /// Mutable access to properties and children, used by repairs within the crate.
+    pub(crate) fn inner_mut(&mut self) -> &mut InnerComponent {
+        &mut self.inner
+    }
+
     /// Set the [`PERCENT-COMPLETE`](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.1.8) property
     ///
     /// Ranges between 0 - 100
@@ -24,6 +24,11 @@ impl Venue {
         }
     }
 
+    /// Mutable access to properties and children, used by repairs within the crate.
+    pub(crate) fn inner_mut(&mut self) -> &mut InnerComponent {
+        &mut self.inner
+    }
+
     /// Set the STREET-ADDRESS `Property`
     ///
     /// This specifies the street address of a location. If the location requires a multiple-line
@@ -0,0 +1,528 @@
+//! # Comparing calendars
+//!
+//! [`diff()`] compares two versions of a calendar and describes what changed as a [`Patch`]:
+//! the components that were added or removed, matched by `UID` and `RECURRENCE-ID`,
+//! and the properties that changed in components both versions have.
+//! Differences that don't change the meaning, like folding, escaping or the order of parameters, are ignored.
+//! [`apply()`] makes the same changes to another copy of the old version.
+//!
+//! ```
+//! # use icalendar::{*, diff};
+//! let mut old = Calendar::empty();
+//! old.push(Event::with_uid("standup").summary("Standup").done());
+//!
+//! let mut new = old.clone();
+//! new.events_mut().next().unwrap().summary("Daily standup");
+//! new.push(Event::with_uid("retro").summary("Retro").done());
+//!
+//! let patch = diff::diff(&old, &new);
+//! assert_eq!(
+//!     patch.to_string(),
+//!     "VEVENT standup: changed SUMMARY:Standup to SUMMARY:Daily standup\nVEVENT retro: added\n"
+//! );
+//!
+//! diff::apply(&mut old, &patch).unwrap();
+//! assert!(diff::diff(&old, &new).is_empty());
+//! ```
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    Calendar, CalendarComponent, Component, Property,
+    components::{InnerComponent, Other},
+    properties::MULTIS,
+};
+
+/// Identifies a component across versions of a calendar.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ComponentId {
+    /// The kind of component, e.g. `VEVENT`.
+    pub name: String,
+    /// The `UID`, or the `TZID` of a `VTIMEZONE`.
+    pub id: String,
+    /// The `RECURRENCE-ID` of an overridden occurrence.
+    pub recurrence_id: Option<String>,
+}
+
+impl ComponentId {
+    fn of(component: &CalendarComponent) -> Option<Self> {
+        let name = component.component_kind();
+        let id = if name == "VTIMEZONE" {
+            component.property_value("TZID")?
+        } else {
+            component.get_uid()?
+        };
+        Some(ComponentId {
+            id: id.to_owned(),
+            recurrence_id: component
+                .property_value("RECURRENCE-ID")
+                .map(ToOwned::to_owned),
+            name,
+        })
+    }
+}
+
+impl fmt::Display for ComponentId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.id)?;
+        if let Some(recurrence_id) = &self.recurrence_id {
+            write!(f, " at {recurrence_id}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A difference in the properties of a component or of the calendar itself.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PropertyChange {
+    /// Only the new version has the property.
+    Added(Property),
+    /// Only the old version has the property.
+    Removed(Property),
+    /// The only property of its name has a different value or different parameters.
+    Changed {
+        /// The property in the old version.
+        old: Property,
+        /// The property in the new version.
+        new: Property,
+    },
+}
+
+/// A difference in the components of a calendar.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ComponentChange {
+    /// Only the new version has the component.
+    Added(CalendarComponent),
+    /// Only the old version has the component.
+    Removed(CalendarComponent),
+    /// Both versions have the component, but with different content.
+    Modified {
+        /// The modified component.
+        id: ComponentId,
+        /// What changed in its properties.
+        properties: Vec<PropertyChange>,
+        /// The new nested components, like `VALARM`s, if any of them changed.
+        components: Option<Vec<Other>>,
+    },
+}
+
+/// The differences between two versions of a calendar, see [`diff()`].
+///
+/// Its [`Display`](fmt::Display) lists one change per line, e.g. for audit logs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Patch {
+    /// What changed in the properties of the calendar itself.
+    pub properties: Vec<PropertyChange>,
+    /// What changed in its components.
+    pub components: Vec<ComponentChange>,
+}
+
+impl Patch {
+    /// Whether both versions mean the same.
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty() && self.components.is_empty()
+    }
+}
+
+fn write_property_change(
+    f: &mut fmt::Formatter<'_>,
+    subject: &dyn fmt::Display,
+    change: &PropertyChange,
+) -> fmt::Result {
+    match change {
+        PropertyChange::Added(property) => {
+            writeln!(f, "{subject}: added {}", property.to_line()?)
+        }
+        PropertyChange::Removed(property) => {
+            writeln!(f, "{subject}: removed {}", property.to_line()?)
+        }
+        PropertyChange::Changed { old, new } => writeln!(
+            f,
+            "{subject}: changed {} to {}",
+            old.to_line()?,
+            new.to_line()?
+        ),
+    }
+}
+
+impl fmt::Display for Patch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.properties {
+            write_property_change(f, &"VCALENDAR", change)?;
+        }
+        for change in &self.components {
+            match change {
+                ComponentChange::Added(component) | ComponentChange::Removed(component) => {
+                    match ComponentId::of(component) {
+                        Some(id) => write!(f, "{id}")?,
+                        None => f.write_str(&component.component_kind())?,
+                    }
+                    let verb = match change {
+                        ComponentChange::Added(_) => "added",
+                        _ => "removed",
+                    };
+                    writeln!(f, ": {verb}")?;
+                }
+                ComponentChange::Modified {
+                    id,
+                    properties,
+                    components,
+                } => {
+                    for change in properties {
+                        write_property_change(f, id, change)?;
+                    }
+                    if components.is_some() {
+                        writeln!(f, "{id}: replaced nested components")?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Why [`apply()`] could not apply a [`Patch`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PatchError {
+    /// A component that is removed or modified is not in the calendar.
+    MissingComponent(String),
+    /// A component that is added is in the calendar already.
+    ExistingComponent(ComponentId),
+    /// A property that is removed or changed is not in the component, at least not with the expected value.
+    MissingProperty {
+        /// The component, or `VCALENDAR`.
+        component: String,
+        /// The expected property as content line.
+        property: String,
+    },
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchError::MissingComponent(component) => write!(f, "{component} is missing"),
+            PatchError::ExistingComponent(id) => write!(f, "{id} exists already"),
+            PatchError::MissingProperty {
+                component,
+                property,
+            } => write!(f, "{component} has no {property}"),
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+/// Whether two properties mean the same, regardless of case and the order of parameters.
+fn same_property(a: &Property, b: &Property) -> bool {
+    let parameters = |property: &Property| {
+        let mut parameters = property
+            .params()
+            .iter()
+            .map(|param| (param.key().to_ascii_uppercase(), param.value().to_owned()))
+            .collect::<Vec<_>>();
+        parameters.sort();
+        parameters
+    };
+    a.key().eq_ignore_ascii_case(b.key())
+        && a.value() == b.value()
+        && parameters(a) == parameters(b)
+}
+
+fn all_properties(component: &impl Component) -> impl Iterator<Item = &Property> {
+    component
+        .properties()
+        .values()
+        .chain(component.multi_properties().values().flatten())
+}
+
+/// Pairs up properties that mean the same, a single remaining one of a name on each side counts as changed.
+fn property_changes<'p>(
+    old: impl IntoIterator<Item = &'p Property>,
+    new: impl IntoIterator<Item = &'p Property>,
+) -> Vec<PropertyChange> {
+    let mut old = old.into_iter().collect::<Vec<_>>();
+    let mut new = new.into_iter().collect::<Vec<_>>();
+    old.retain(
+        |old| match new.iter().position(|new| same_property(old, new)) {
+            Some(position) => {
+                new.remove(position);
+                false
+            }
+            None => true,
+        },
+    );
+
+    let mut names = old
+        .iter()
+        .chain(&new)
+        .map(|property| property.key().to_ascii_uppercase())
+        .collect::<Vec<_>>();
+    names.sort();
+    names.dedup();
+
+    let mut changes = Vec::new();
+    for name in names {
+        let named = |properties: &[&Property]| {
+            properties
+                .iter()
+                .filter(|property| property.key().eq_ignore_ascii_case(&name))
+                .map(|property| (*property).clone())
+                .collect::<Vec<_>>()
+        };
+        let (old, new) = (named(&old), named(&new));
+        if let ([old], [new]) = (old.as_slice(), new.as_slice()) {
+            changes.push(PropertyChange::Changed {
+                old: old.clone(),
+                new: new.clone(),
+            });
+        } else {
+            changes.extend(old.into_iter().map(PropertyChange::Removed));
+            changes.extend(new.into_iter().map(PropertyChange::Added));
+        }
+    }
+    changes
+}
+
+fn same_component(a: &impl Component, b: &impl Component) -> bool {
+    a.component_kind() == b.component_kind()
+        && property_changes(all_properties(a), all_properties(b)).is_empty()
+        && same_components(a.components(), b.components())
+}
+
+fn same_components(a: &[Other], b: &[Other]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same_component(a, b))
+}
+
+/// The first component of each [`ComponentId`], and all others.
+fn index(
+    components: &[CalendarComponent],
+) -> (
+    HashMap<ComponentId, &CalendarComponent>,
+    Vec<&CalendarComponent>,
+) {
+    let mut identified = HashMap::new();
+    let mut others = Vec::new();
+    for component in components {
+        match ComponentId::of(component) {
+            Some(id) if !identified.contains_key(&id) => {
+                identified.insert(id, component);
+            }
+            _ => others.push(component),
+        }
+    }
+    (identified, others)
+}
+
+/// Compares the `old` and the `new` version of a calendar.
+///
+/// Components are matched by their [`ComponentId`], those without one,
+/// or with one that occurs more than once, only match if they mean the same.
+/// Properties are matched by name and compared by meaning.
+pub fn diff(old: &Calendar, new: &Calendar) -> Patch {
+    let (old_identified, old_others) = index(&old.components);
+    let (new_identified, new_others) = index(&new.components);
+
+    let mut components = Vec::new();
+    for component in &old.components {
+        let id = ComponentId::of(component);
+        let identified = id
+            .as_ref()
+            .and_then(|id| old_identified.get(id))
+            .is_some_and(|identified| std::ptr::eq(*identified, component));
+        if identified {
+            let Some(id) = id else { continue };
+            let Some(new) = new_identified.get(&id) else {
+                components.push(ComponentChange::Removed(component.clone()));
+                continue;
+            };
+            let properties = property_changes(all_properties(component), all_properties(*new));
+            let nested = (!same_components(component.components(), new.components()))
+                .then(|| new.components().to_vec());
+            if !properties.is_empty() || nested.is_some() {
+                components.push(ComponentChange::Modified {
+                    id,
+                    properties,
+                    components: nested,
+                });
+            }
+        } else if !new_others.iter().any(|new| same_component(component, *new)) {
+            components.push(ComponentChange::Removed(component.clone()));
+        }
+    }
+    for component in &new.components {
+        let identified = ComponentId::of(component)
+            .and_then(|id| new_identified.get(&id).map(|identified| (id, *identified)))
+            .filter(|(_, identified)| std::ptr::eq(*identified, component));
+        let added = match identified {
+            Some((id, _)) => !old_identified.contains_key(&id),
+            None => !old_others.iter().any(|old| same_component(*old, component)),
+        };
+        if added {
+            components.push(ComponentChange::Added(component.clone()));
+        }
+    }
+
+    Patch {
+        properties: property_changes(&old.properties, &new.properties),
+        components,
+    }
+}
+
+/// Makes the changes of `patch` to `calendar`, which should be the old version it was made from.
+///
+/// Fails without changing anything if the calendar doesn't match what the patch expects,
+/// e.g. because a changed property has a different value by now.
+pub fn apply(calendar: &mut Calendar, patch: &Patch) -> Result<(), PatchError> {
+    let mut patched = calendar.clone();
+
+    for change in &patch.properties {
+        apply_to_calendar(&mut patched.properties, change)?;
+    }
+
+    for change in &patch.components {
+        match change {
+            ComponentChange::Added(component) => {
+                if let Some(id) = ComponentId::of(component)
+                    && find(&patched.components, &id).is_some()
+                {
+                    return Err(PatchError::ExistingComponent(id));
+                }
+                patched.components.push(component.clone());
+            }
+            ComponentChange::Removed(component) => {
+                let position = match ComponentId::of(component) {
+                    Some(id) => find(&patched.components, &id),
+                    None => patched
+                        .components
+                        .iter()
+                        .position(|candidate| same_component(candidate, component)),
+                };
+                let Some(position) = position else {
+                    return Err(PatchError::MissingComponent(component.component_kind()));
+                };
+                patched.components.remove(position);
+            }
+            ComponentChange::Modified {
+                id,
+                properties,
+                components,
+            } => {
+                let component = find(&patched.components, id)
+                    .and_then(|position| patched.components.get_mut(position))
+                    .ok_or_else(|| PatchError::MissingComponent(id.to_string()))?;
+                let inner = component.inner_mut();
+                for change in properties {
+                    apply_to_component(inner, id, change)?;
+                }
+                if let Some(components) = components {
+                    inner.components.clone_from(components);
+                }
+            }
+        }
+    }
+
+    *calendar = patched;
+    Ok(())
+}
+
+fn find(components: &[CalendarComponent], id: &ComponentId) -> Option<usize> {
+    components
+        .iter()
+        .position(|component| ComponentId::of(component).as_ref() == Some(id))
+}
+
+fn missing(component: &dyn fmt::Display, property: &Property) -> PatchError {
+    PatchError::MissingProperty {
+        component: component.to_string(),
+        property: property.to_line().unwrap_or_default(),
+    }
+}
+
+fn apply_to_calendar(
+    properties: &mut Vec<Property>,
+    change: &PropertyChange,
+) -> Result<(), PatchError> {
+    let position = |expected: &Property| {
+        properties
+            .iter()
+            .position(|property| same_property(property, expected))
+            .ok_or_else(|| missing(&"VCALENDAR", expected))
+    };
+    match change {
+        PropertyChange::Added(new) => properties.push(new.clone()),
+        PropertyChange::Removed(old) => {
+            let position = position(old)?;
+            properties.remove(position);
+        }
+        PropertyChange::Changed { old, new } => {
+            let position = position(old)?;
+            if let Some(property) = properties.get_mut(position) {
+                *property = new.clone();
+            }
+        }
+    }
+    Ok(())
+}
+
+fn apply_to_component(
+    inner: &mut InnerComponent,
+    id: &ComponentId,
+    change: &PropertyChange,
+) -> Result<(), PatchError> {
+    // take the expected property out first, the new one is added like any other
+    let (old, new) = match change {
+        PropertyChange::Added(new) => (None, Some(new)),
+        PropertyChange::Removed(old) => (Some(old), None),
+        PropertyChange::Changed { old, new } => (Some(old), Some(new)),
+    };
+    if let Some(old) = old {
+        let single = inner
+            .properties
+            .iter()
+            .find(|(_, property)| same_property(property, old))
+            .map(|(key, _)| key.clone());
+        if let Some(key) = single {
+            let replaced = inner.properties.remove(&key);
+            if let (Some(new), Some(replaced)) = (new, replaced)
+                && new.key() == key
+            {
+                // keeps its place when written losslessly
+                inner
+                    .properties
+                    .insert(key, new.clone().replacing(&replaced));
+                return Ok(());
+            }
+        } else {
+            let multi = inner
+                .multi_properties
+                .iter_mut()
+                .find_map(|(_, properties)| {
+                    let position = properties
+                        .iter()
+                        .position(|property| same_property(property, old))?;
+                    Some(properties.remove(position))
+                });
+            let Some(replaced) = multi else {
+                return Err(missing(id, old));
+            };
+            inner
+                .multi_properties
+                .retain(|_, properties| !properties.is_empty());
+            if let Some(new) = new {
+                inner.insert_multi(new.clone().replacing(&replaced));
+                return Ok(());
+            }
+        }
+    }
+    if let Some(new) = new {
+        let key = new.key();
+        if MULTIS.contains(&key)
+            || inner.multi_properties.contains_key(key)
+            || inner.properties.contains_key(key)
+        {
+            inner.insert_multi(new.clone());
+        } else {
+            inner.properties.insert(key.to_owned(), new.clone());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,718 @@
+//! # Scheduling over email
+//!
+//! Renders [iTIP](crate::itip) messages as [iMIP (RFC 6047)](https://datatracker.ietf.org/doc/html/rfc6047) emails
+//! and finds the calendar in received ones.
+//!
+//! [`MimeMessage::new()`] derives the envelope from the calendar: organizer and attendees become `From` and `To`
+//! depending on the direction of the method, the `SUMMARY` becomes the subject and a short plain text summary is added.
+//! [`MimeMessage::to_bytes()`] produces a `multipart/alternative` RFC 5322 message
+//! with that text and a `text/calendar; method=...; charset=UTF-8` part, ready to be handed to a mail transfer agent.
+//!
+//! ```
+//! # use icalendar::{*, imip::MimeMessage};
+//! let event = Event::with_uid("kickoff@example.com")
+//!     .summary("Project Kickoff")
+//!     .starts(chrono::Utc::now())
+//!     .organizer("mailto:bob@example.com")
+//!     .attendee(Attendee::new("mailto:alice@example.com".into()).cn("Alice".into()))
+//!     .done();
+//! let invitation = itip::request(&event).unwrap();
+//!
+//! let email = MimeMessage::new(&invitation).unwrap();
+//! assert_eq!(email.subject, "Invitation: Project Kickoff");
+//! assert_eq!(email.to[0].to_string(), "Alice <alice@example.com>");
+//!
+//! let raw = email.to_bytes();
+//! assert_eq!(imip::extract(&raw).unwrap().method, Some(Method::Request));
+//! ```
+use std::fmt;
+
+use chrono::Utc;
+
+use crate::{
+    Attendee, Calendar, CalendarComponent, CalendarDateTime, Component, DatePerhapsTime, Method,
+    Parameter, PartStat,
+};
+
+/// Things that can go wrong rendering or reading an iMIP message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ImipError {
+    /// The calendar has no `METHOD` property, which iMIP requires.
+    MissingMethod,
+    /// The message does not contain a `text/calendar` part.
+    NoCalendarPart,
+    /// The MIME structure of the message is broken.
+    Malformed(&'static str),
+    /// The calendar part uses a `Content-Transfer-Encoding` other than `7bit`, `8bit`, `binary`, `base64` or `quoted-printable`.
+    UnsupportedEncoding(String),
+    /// The calendar part uses a charset other than `UTF-8`, `US-ASCII` or `ISO-8859-1`.
+    UnsupportedCharset(String),
+    /// The calendar part is not valid in its declared charset.
+    InvalidText,
+    /// The `method` of the `Content-Type` does not match the `METHOD` of the calendar.
+    MethodMismatch {
+        /// The `method` parameter of the `Content-Type`.
+        content_type: String,
+        /// The `METHOD` property of the calendar.
+        calendar: String,
+    },
+    /// The calendar part could not be parsed.
+    #[cfg(feature = "parser")]
+    Calendar(String),
+}
+
+impl fmt::Display for ImipError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImipError::MissingMethod => write!(f, "calendar has no METHOD"),
+            ImipError::NoCalendarPart => write!(f, "message has no text/calendar part"),
+            ImipError::Malformed(reason) => write!(f, "malformed MIME message: {reason}"),
+            ImipError::UnsupportedEncoding(encoding) => {
+                write!(f, "unsupported Content-Transfer-Encoding {encoding:?}")
+            }
+            ImipError::UnsupportedCharset(charset) => write!(f, "unsupported charset {charset:?}"),
+            ImipError::InvalidText => write!(f, "calendar part is not valid in its charset"),
+            ImipError::MethodMismatch {
+                content_type,
+                calendar,
+            } => write!(
+                f,
+                "Content-Type says method={content_type} but the calendar has METHOD:{calendar}"
+            ),
+            #[cfg(feature = "parser")]
+            ImipError::Calendar(reason) => write!(f, "invalid calendar: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for ImipError {}
+
+/// An email address with an optional display name, as used in `From` and `To`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mailbox {
+    /// The display name, usually the `CN` parameter.
+    pub name: Option<String>,
+    /// The address without `mailto:`.
+    pub address: String,
+}
+
+impl Mailbox {
+    /// Creates a mailbox without display name.
+    pub fn new(address: impl Into<String>) -> Self {
+        Mailbox {
+            name: None,
+            address: address.into(),
+        }
+    }
+
+    /// Sets the display name.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Turns a `mailto:` CAL-ADDRESS into a mailbox, other URIs can't receive email.
+    pub fn from_cal_address(cal_address: &str, name: Option<&str>) -> Option<Self> {
+        let scheme = cal_address.get(..7)?;
+        if !scheme.eq_ignore_ascii_case("mailto:") {
+            return None;
+        }
+        let address = cal_address.get(7..)?.trim();
+        (!address.is_empty()).then(|| Mailbox {
+            name: name.filter(|name| !name.is_empty()).map(Into::into),
+            address: address.into(),
+        })
+    }
+
+    fn same_address(&self, other: &Mailbox) -> bool {
+        self.address.eq_ignore_ascii_case(&other.address)
+    }
+
+    /// The mailbox as header value, with non-ASCII names as RFC 2047 encoded-words.
+    fn encoded(&self) -> String {
+        match &self.name {
+            Some(name) if needs_encoding(name) => {
+                format!("{} <{}>", encode_word(name), self.address)
+            }
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Mailbox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) if name.chars().any(|c| "()<>[]:;@\\,.\"".contains(c)) => {
+                let escaped = name.replace('\\', "\\\\").replace('"', "\\\"");
+                write!(f, "\"{escaped}\" <{}>", self.address)
+            }
+            Some(name) => write!(f, "{name} <{}>", self.address),
+            None => write!(f, "{}", self.address),
+        }
+    }
+}
+
+/// An iMIP email carrying a calendar.
+///
+/// The envelope fields are public so the derived values can be adjusted before rendering.
+#[derive(Clone, Debug)]
+pub struct MimeMessage {
+    /// The sender, derived from `ORGANIZER` or the replying `ATTENDEE`.
+    pub from: Option<Mailbox>,
+    /// The recipients, derived from `ATTENDEE`s or `ORGANIZER`.
+    pub to: Vec<Mailbox>,
+    /// The subject, derived from method and `SUMMARY`.
+    pub subject: String,
+    /// The `text/plain` alternative for clients without calendar support.
+    pub text: String,
+    method: Method,
+    calendar: String,
+}
+
+impl MimeMessage {
+    /// Prepares an email for the iTIP message `calendar`.
+    ///
+    /// Methods sent by the organizer (`PUBLISH`, `REQUEST`, `ADD`, `CANCEL`, `DECLINECOUNTER`) go from the `ORGANIZER` to all `ATTENDEE`s,
+    /// `REPLY` and `REFRESH` go from the attendee to the organizer.
+    /// A `COUNTER` goes to the organizer but the sender can't be told from the calendar.
+    /// Only `mailto:` addresses are used.
+    pub fn new(calendar: &Calendar) -> Result<Self, ImipError> {
+        let method = calendar.get_method().ok_or(ImipError::MissingMethod)?;
+        let event = calendar
+            .components
+            .iter()
+            .find_map(|component| match component {
+                CalendarComponent::Event(event) => Some(event),
+                _ => None,
+            });
+
+        let organizer = event.and_then(|event| {
+            let organizer = event.properties().get("ORGANIZER")?;
+            let name = organizer.params().get("CN").map(Parameter::value);
+            Mailbox::from_cal_address(organizer.value(), name)
+        });
+        let attendees = event.map(Component::get_attendees).unwrap_or_default();
+        let mut attendee_boxes = attendees.iter().filter_map(|attendee| {
+            Mailbox::from_cal_address(&attendee.cal_address, attendee.cn.as_deref())
+        });
+
+        let (from, to) = match method {
+            Method::Reply | Method::Refresh => {
+                (attendee_boxes.next(), Vec::from_iter(organizer.clone()))
+            }
+            Method::Counter => (None, Vec::from_iter(organizer.clone())),
+            Method::Publish
+            | Method::Request
+            | Method::Add
+            | Method::Cancel
+            | Method::DeclineCounter => {
+                let to = attendee_boxes
+                    .filter(|attendee| {
+                        organizer
+                            .as_ref()
+                            .is_none_or(|organizer| !organizer.same_address(attendee))
+                    })
+                    .collect();
+                (organizer.clone(), to)
+            }
+        };
+
+        let reply_status = attendees
+            .first()
+            .and_then(|attendee| attendee.part_stat)
+            .filter(|_| method == Method::Reply);
+        let topic = match (method, reply_status) {
+            (Method::Publish, _) => "Event",
+            (Method::Request, _) => "Invitation",
+            (Method::Reply, status) => reply_verb(status),
+            (Method::Add, _) => "Added occurrences",
+            (Method::Cancel, _) => "Cancelled",
+            (Method::Refresh, _) => "Update requested",
+            (Method::Counter, _) => "New time proposed",
+            (Method::DeclineCounter, _) => "Proposal declined",
+        };
+        // replies usually don't repeat the SUMMARY
+        let subject = match event.and_then(|event| event.get_summary()) {
+            Some(summary) if method == Method::Publish => summary.to_owned(),
+            Some(summary) => format!("{topic}: {summary}"),
+            None => topic.to_owned(),
+        };
+
+        let mut text = match method {
+            Method::Reply => {
+                let who = attendees
+                    .first()
+                    .map(|attendee: &Attendee| {
+                        attendee.cn.clone().unwrap_or_else(|| {
+                            attendee
+                                .cal_address
+                                .trim_start_matches("mailto:")
+                                .to_owned()
+                        })
+                    })
+                    .unwrap_or_else(|| "An attendee".into());
+                format!("{who} replied: {}\n", reply_verb(reply_status))
+            }
+            _ => format!("{subject}\n"),
+        };
+        if let Some(event) = event {
+            if let Some(start) = event.get_start() {
+                text.push_str(&format!("\nWhen: {}", describe(&start)));
+            }
+            if let Some(location) = event.property_value("LOCATION") {
+                text.push_str(&format!("\nWhere: {location}"));
+            }
+            if let Some(organizer) = &organizer {
+                text.push_str(&format!("\nOrganizer: {organizer}"));
+            }
+            if let Some(description) = event.get_description() {
+                text.push_str(&format!("\n\n{description}"));
+            }
+            text.push('\n');
+        }
+
+        Ok(MimeMessage {
+            from,
+            to,
+            subject,
+            text,
+            method,
+            calendar: calendar.to_string(),
+        })
+    }
+
+    /// The iTIP method of the calendar part.
+    pub fn method(&self) -> Method {
+        self.method
+    }
+
+    /// Renders the complete message with headers and CRLF line endings.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        let boundary = format!("=_icalendar_{id}");
+        let domain = self
+            .from
+            .as_ref()
+            .and_then(|from| from.address.rsplit_once('@'))
+            .map_or("localhost", |(_, domain)| domain);
+
+        let mut out = String::new();
+        if let Some(from) = &self.from {
+            header(&mut out, "From", &from.encoded());
+        }
+        if !self.to.is_empty() {
+            let to = self.to.iter().map(Mailbox::encoded).collect::<Vec<_>>();
+            header(&mut out, "To", &to.join(", "));
+        }
+        header(&mut out, "Subject", &encode_header_text(&self.subject));
+        header(&mut out, "Date", &Utc::now().to_rfc2822());
+        header(&mut out, "Message-ID", &format!("<{id}@{domain}>"));
+        header(&mut out, "MIME-Version", "1.0");
+        header(
+            &mut out,
+            "Content-Type",
+            &format!("multipart/alternative; boundary=\"{boundary}\""),
+        );
+        out.push_str("\r\n");
+
+        out.push_str(&format!("--{boundary}\r\n"));
+        body_part(&mut out, "text/plain; charset=UTF-8", &self.text);
+        out.push_str(&format!("--{boundary}\r\n"));
+        body_part(
+            &mut out,
+            &format!("text/calendar; method={}; charset=UTF-8", self.method),
+            &self.calendar,
+        );
+        out.push_str(&format!("--{boundary}--\r\n"));
+        out.into_bytes()
+    }
+}
+
+fn reply_verb(status: Option<PartStat>) -> &'static str {
+    match status {
+        Some(PartStat::Accepted) => "Accepted",
+        Some(PartStat::Declined) => "Declined",
+        Some(PartStat::Tentative) => "Tentative",
+        Some(PartStat::Delegated) => "Delegated",
+        _ => "Reply",
+    }
+}
+
+fn describe(start: &DatePerhapsTime) -> String {
+    match start {
+        DatePerhapsTime::Date(date) => date.format("%Y-%m-%d").to_string(),
+        DatePerhapsTime::DateTime(CalendarDateTime::Floating(naive)) => {
+            naive.format("%Y-%m-%d %H:%M").to_string()
+        }
+        DatePerhapsTime::DateTime(CalendarDateTime::Utc(utc)) => {
+            utc.format("%Y-%m-%d %H:%M UTC").to_string()
+        }
+        DatePerhapsTime::DateTime(CalendarDateTime::WithTimezone { date_time, tzid }) => {
+            format!("{} {tzid}", date_time.format("%Y-%m-%d %H:%M"))
+        }
+    }
+}
+
+/// Appends a header, folding at whitespace once the line gets longer than 76 characters.
+fn header(out: &mut String, name: &str, value: &str) {
+    // a line break in a value would start a header of its own
+    let value = value.replace(|c: char| c.is_ascii_control(), " ");
+    out.push_str(name);
+    out.push(':');
+    let mut line_len = name.len() + 1;
+    for (index, word) in value.split(' ').enumerate() {
+        if index > 0 && line_len + 1 + word.len() > 76 {
+            out.push_str("\r\n");
+            line_len = 0;
+        }
+        out.push(' ');
+        out.push_str(word);
+        line_len += 1 + word.len();
+    }
+    out.push_str("\r\n");
+}
+
+/// Writes a body part, `7bit` if possible and `base64` otherwise.
+fn body_part(out: &mut String, content_type: &str, text: &str) {
+    let text = text.replace("\r\n", "\n").replace('\n', "\r\n");
+    header(out, "Content-Type", content_type);
+    if text.is_ascii() && text.split("\r\n").all(|line| line.len() <= 998) {
+        header(out, "Content-Transfer-Encoding", "7bit");
+        out.push_str("\r\n");
+        out.push_str(&text);
+        // the line break before the next delimiter is not part of the body
+        out.push_str("\r\n");
+    } else {
+        header(out, "Content-Transfer-Encoding", "base64");
+        out.push_str("\r\n");
+        for line in base64_encode(text.as_bytes()).as_bytes().chunks(76) {
+            out.push_str(std::str::from_utf8(line).unwrap_or_default());
+            out.push_str("\r\n");
+        }
+    }
+}
+
+fn encode_header_text(text: &str) -> String {
+    if needs_encoding(text) {
+        encode_word(text)
+    } else {
+        text.to_owned()
+    }
+}
+
+/// Whether `text` can't be written into a header as is.
+fn needs_encoding(text: &str) -> bool {
+    !text.is_ascii() || text.chars().any(|c| c.is_ascii_control())
+}
+
+/// RFC 2047 `B` encoded-words, split on character boundaries to stay within 75 characters each.
+fn encode_word(text: &str) -> String {
+    let mut words = Vec::new();
+    let mut start = 0;
+    for (index, c) in text.char_indices() {
+        if index + c.len_utf8() - start > 45 {
+            words.push(&text[start..index]);
+            start = index;
+        }
+    }
+    words.push(&text[start..]);
+    words
+        .into_iter()
+        .map(|word| format!("=?UTF-8?B?{}?=", base64_encode(word.as_bytes())))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk.first().copied().unwrap_or(0),
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = u32::from(b[0]) << 16 | u32::from(b[1]) << 8 | u32::from(b[2]);
+        for position in 0..4 {
+            if position <= chunk.len() {
+                let sextet = (n >> (18 - 6 * position)) as usize & 63;
+                out.extend(BASE64.get(sextet).copied().map(char::from));
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn base64_decode(text: &[u8]) -> Result<Vec<u8>, ImipError> {
+    let mut out = Vec::with_capacity(text.len() / 4 * 3);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &c in text {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            c if c.is_ascii_whitespace() => continue,
+            _ => return Err(ImipError::Malformed("invalid base64")),
+        };
+        buffer = buffer << 6 | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Ok(out)
+}
+
+fn quoted_printable_decode(text: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len());
+    let mut rest = text;
+    while let Some((&c, tail)) = rest.split_first() {
+        rest = tail;
+        if c != b'=' {
+            out.push(c);
+            continue;
+        }
+        match rest {
+            [b'\r', b'\n', tail @ ..] | [b'\n', tail @ ..] => rest = tail,
+            [high, low, tail @ ..] if high.is_ascii_hexdigit() && low.is_ascii_hexdigit() => {
+                let hex = [*high, *low];
+                let hex = std::str::from_utf8(&hex).unwrap_or_default();
+                out.push(u8::from_str_radix(hex, 16).unwrap_or_default());
+                rest = tail;
+            }
+            // lenient like most mail clients: keep a stray `=`
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// The calendar part found in an email by [`extract()`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CalendarPart {
+    /// The `method` parameter of the `Content-Type`, if it names a known method.
+    pub method: Option<Method>,
+    /// The decoded iCalendar text.
+    pub content: String,
+}
+
+/// A MIME entity: its headers, unfolded, and its raw body.
+struct Entity<'a> {
+    headers: Vec<(String, String)>,
+    body: &'a [u8],
+}
+
+impl<'a> Entity<'a> {
+    fn parse(raw: &'a [u8]) -> Self {
+        let (head, body) = find(raw, b"\r\n\r\n")
+            .and_then(|end| Some((raw.get(..end)?, raw.get(end + 4..)?)))
+            .or_else(|| {
+                find(raw, b"\n\n").and_then(|end| Some((raw.get(..end)?, raw.get(end + 2..)?)))
+            })
+            .unwrap_or((raw, &[]));
+        let head = String::from_utf8_lossy(head);
+
+        let mut headers: Vec<(String, String)> = Vec::new();
+        for line in head.lines() {
+            if line.starts_with([' ', '\t']) {
+                if let Some((_, value)) = headers.last_mut() {
+                    value.push(' ');
+                    value.push_str(line.trim());
+                }
+            } else if let Some((name, value)) = line.split_once(':') {
+                headers.push((name.trim().to_ascii_lowercase(), value.trim().to_owned()));
+            }
+        }
+        Entity { headers, body }
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The lowercase media type and its parameters, `text/plain` if there is no `Content-Type`.
+    fn content_type(&self) -> (String, Vec<(String, String)>) {
+        let Some(value) = self.header("content-type") else {
+            return ("text/plain".into(), Vec::new());
+        };
+        let mut parts = split_parameters(value).into_iter();
+        let media_type = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let parameters = parts
+            .filter_map(|part| {
+                let (key, value) = part.split_once('=')?;
+                let value = value.trim();
+                let value = value
+                    .strip_prefix('"')
+                    .and_then(|value| value.strip_suffix('"'))
+                    .map_or_else(|| value.to_owned(), |value| value.replace("\\\"", "\""));
+                Some((key.trim().to_ascii_lowercase(), value))
+            })
+            .collect();
+        (media_type, parameters)
+    }
+}
+
+/// Splits a header value at `;` outside of quotes.
+fn split_parameters(value: &str) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut quoted = false;
+    for c in value.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' if !quoted => {
+                parts.push(String::new());
+                continue;
+            }
+            _ => {}
+        }
+        if let Some(part) = parts.last_mut() {
+            part.push(c);
+        }
+    }
+    parts
+        .into_iter()
+        .map(|part| part.trim().to_owned())
+        .collect()
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn parameter<'p>(parameters: &'p [(String, String)], key: &str) -> Option<&'p str> {
+    parameters
+        .iter()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.as_str())
+}
+
+/// The bodies between the delimiter lines of a multipart body.
+fn multipart_bodies<'a>(body: &'a [u8], boundary: &str) -> Result<Vec<&'a [u8]>, ImipError> {
+    let delimiter = format!("--{boundary}");
+    let mut parts = Vec::new();
+    let mut current: Option<usize> = None;
+    let mut offset = 0;
+    for line in body.split_inclusive(|&b| b == b'\n') {
+        let content = line.trim_ascii_end();
+        if let Some(rest) = content.strip_prefix(delimiter.as_bytes()) {
+            if let Some(start) = current {
+                // the line break before the delimiter belongs to it
+                let before = body.get(..offset).unwrap_or_default();
+                let end = before
+                    .strip_suffix(b"\r\n")
+                    .or_else(|| before.strip_suffix(b"\n"))
+                    .map_or(offset, <[u8]>::len);
+                parts.extend(body.get(start..end.max(start)));
+            }
+            if rest.starts_with(b"--") {
+                return Ok(parts);
+            }
+            current = Some(offset + line.len());
+        }
+        offset += line.len();
+    }
+    if parts.is_empty() && current.is_none() {
+        Err(ImipError::Malformed("multipart boundary not found"))
+    } else {
+        // tolerate a missing close delimiter
+        if let Some(start) = current {
+            parts.extend(body.get(start..));
+        }
+        Ok(parts)
+    }
+}
+
+/// Finds the first `text/calendar` entity, depth first.
+fn find_calendar<'a>(entity: Entity<'a>, depth: usize) -> Result<Option<Entity<'a>>, ImipError> {
+    let (media_type, parameters) = entity.content_type();
+    if media_type == "text/calendar" {
+        return Ok(Some(entity));
+    }
+    if !media_type.starts_with("multipart/") || depth > 16 {
+        return Ok(None);
+    }
+    let boundary = parameter(&parameters, "boundary")
+        .ok_or(ImipError::Malformed("multipart without boundary"))?;
+    for body in multipart_bodies(entity.body, boundary)? {
+        if let Some(found) = find_calendar(Entity::parse(body), depth + 1)? {
+            return Ok(Some(found));
+        }
+    }
+    Ok(None)
+}
+
+/// Finds and decodes the `text/calendar` part of a raw email.
+///
+/// Transfer encodings and the charset are undone; the calendar itself is not checked.
+pub fn extract(message: &[u8]) -> Result<CalendarPart, ImipError> {
+    let (method, content) = decode(message)?;
+    Ok(CalendarPart {
+        method: method.and_then(|method| Method::from_str(&method)),
+        content,
+    })
+}
+
+/// Extracts and parses the calendar of a raw email.
+///
+/// RFC 6047 requires the `method` of the `Content-Type` to match the `METHOD` of the calendar,
+/// a mismatch is reported as [`ImipError::MethodMismatch`].
+#[cfg(feature = "parser")]
+pub fn parse(message: &[u8]) -> Result<Calendar, ImipError> {
+    let (declared, content) = decode(message)?;
+    let calendar: Calendar = content
+        .parse()
+        .map_err(|error: crate::parser::ParseError| ImipError::Calendar(error.to_string()))?;
+    match (declared, calendar.property_value("METHOD")) {
+        (Some(declared), method) if method.is_none_or(|method| declared != method) => {
+            Err(ImipError::MethodMismatch {
+                content_type: declared,
+                calendar: method.unwrap_or_default().to_owned(),
+            })
+        }
+        _ => Ok(calendar),
+    }
+}
+
+/// The uppercase `method` parameter and the decoded text of the calendar part.
+fn decode(message: &[u8]) -> Result<(Option<String>, String), ImipError> {
+    let entity = find_calendar(Entity::parse(message), 0)?.ok_or(ImipError::NoCalendarPart)?;
+    let (_, parameters) = entity.content_type();
+
+    let encoding = entity
+        .header("content-transfer-encoding")
+        .unwrap_or("7bit")
+        .to_ascii_lowercase();
+    let bytes = match encoding.as_str() {
+        "7bit" | "8bit" | "binary" => entity.body.to_vec(),
+        "base64" => base64_decode(entity.body)?,
+        "quoted-printable" => quoted_printable_decode(entity.body),
+        _ => return Err(ImipError::UnsupportedEncoding(encoding)),
+    };
+
+    let charset = parameter(&parameters, "charset")
+        .unwrap_or("utf-8")
+        .to_ascii_lowercase();
+    let content = match charset.as_str() {
+        "utf-8" | "utf8" | "us-ascii" => {
+            String::from_utf8(bytes).map_err(|_| ImipError::InvalidText)?
+        }
+        "iso-8859-1" | "latin1" => bytes.into_iter().map(char::from).collect(),
+        _ => return Err(ImipError::UnsupportedCharset(charset)),
+    };
+
+    let method = parameter(&parameters, "method").map(str::to_ascii_uppercase);
+    Ok((method, content))
+}
@@ -0,0 +1,703 @@
+//! # Scheduling messages
+//!
+//! Builds [iTIP (RFC 5546)](https://datatracker.ietf.org/doc/html/rfc5546) messages for [`Event`]s.
+//! Each message is a [`Calendar`] with a [`Method`] that contains the event, or the parts of it the method allows.
+//! Incoming replies and cancellations are applied to a stored calendar with [`apply()`].
+//!
+//! All builders check the result with [`validate()`] against the restriction tables of
+//! [RFC 5546, Section 3.2](https://datatracker.ietf.org/doc/html/rfc5546#section-3.2),
+//! so an event missing e.g. its `ORGANIZER` is rejected instead of producing a message other clients would refuse.
+//!
+//! ```
+//! # use icalendar::{*, itip};
+//! let event = Event::with_uid("kickoff@example.com")
+//!     .summary("Project Kickoff")
+//!     .starts(chrono::Utc::now())
+//!     .organizer("mailto:bob@example.com")
+//!     .attendee(Attendee::new("mailto:alice@example.com".into()).rsvp(true))
+//!     .done();
+//!
+//! let invitation = itip::request(&event).unwrap();
+//! assert_eq!(invitation.get_method(), Some(Method::Request));
+//!
+//! let answer = itip::reply(&event, "mailto:alice@example.com", PartStat::Accepted).unwrap();
+//! assert_eq!(answer.get_method(), Some(Method::Reply));
+//! ```
+use std::fmt;
+
+use chrono::Utc;
+
+use crate::{
+    Attendee, Calendar, CalendarComponent, Component, DatePerhapsTime, Event, EventLike,
+    EventStatus, Method, PartStat, Property,
+    components::date_time::{format_utc_date_time, parse_utc_date_time},
+};
+
+/// Reasons why an event can't be sent with a method.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ItipError {
+    /// The calendar has no `METHOD` property.
+    MissingMethod,
+    /// The message does not contain any event.
+    NoEvent,
+    /// The events of a message must all have the same `UID`.
+    MixedUids,
+    /// A property the method requires is missing.
+    MissingProperty {
+        /// The method of the message.
+        method: Method,
+        /// The name of the missing property.
+        property: &'static str,
+    },
+    /// A property occurs more often than the method allows.
+    TooManyProperties {
+        /// The method of the message.
+        method: Method,
+        /// The name of the repeated property.
+        property: &'static str,
+    },
+    /// The method does not allow this property.
+    ForbiddenProperty {
+        /// The method of the message.
+        method: Method,
+        /// The name of the property.
+        property: &'static str,
+    },
+    /// The method does not allow `VALARM`s.
+    ForbiddenAlarm {
+        /// The method of the message.
+        method: Method,
+    },
+    /// `DTEND` and `DURATION` must not occur together.
+    EndAndDuration,
+    /// The `STATUS` is not allowed for the method.
+    InvalidStatus {
+        /// The method of the message.
+        method: Method,
+        /// The offending value.
+        status: String,
+    },
+    /// The given CAL-ADDRESS is not an attendee of the event.
+    UnknownAttendee(String),
+    /// [`apply()`] can't process messages with this method.
+    UnsupportedMethod(Method),
+}
+
+impl fmt::Display for ItipError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ItipError::MissingMethod => write!(f, "calendar has no METHOD"),
+            ItipError::NoEvent => write!(f, "message does not contain a VEVENT"),
+            ItipError::MixedUids => write!(f, "all events of a message must share their UID"),
+            ItipError::MissingProperty { method, property } => {
+                write!(f, "{method} requires {property}")
+            }
+            ItipError::TooManyProperties { method, property } => {
+                write!(f, "{method} allows only one {property}")
+            }
+            ItipError::ForbiddenProperty { method, property } => {
+                write!(f, "{method} does not allow {property}")
+            }
+            ItipError::ForbiddenAlarm { method } => write!(f, "{method} does not allow VALARM"),
+            ItipError::EndAndDuration => write!(f, "DTEND and DURATION are mutually exclusive"),
+            ItipError::InvalidStatus { method, status } => {
+                write!(f, "{method} does not allow STATUS:{status}")
+            }
+            ItipError::UnknownAttendee(address) => write!(f, "{address} is not an attendee"),
+            ItipError::UnsupportedMethod(method) => write!(f, "can't apply {method} messages"),
+        }
+    }
+}
+
+impl std::error::Error for ItipError {}
+
+/// How often a property may occur in a `VEVENT`, the second column of the RFC 5546 tables.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Presence {
+    /// `1`
+    Required,
+    /// `1+`
+    OneOrMore,
+    /// `0 or 1`
+    Optional,
+    /// `0+`
+    Any,
+    /// `0`
+    Forbidden,
+}
+
+use Presence::*;
+
+/// Properties restricted for every method, only the presence differs.
+const PROPERTIES: [&str; 31] = [
+    "ATTACH",
+    "ATTENDEE",
+    "CATEGORIES",
+    "CLASS",
+    "COMMENT",
+    "CONTACT",
+    "CREATED",
+    "DESCRIPTION",
+    "DTEND",
+    "DTSTAMP",
+    "DTSTART",
+    "DURATION",
+    "EXDATE",
+    "GEO",
+    "LAST-MODIFIED",
+    "LOCATION",
+    "ORGANIZER",
+    "PRIORITY",
+    "RDATE",
+    "RECURRENCE-ID",
+    "RELATED-TO",
+    "REQUEST-STATUS",
+    "RESOURCES",
+    "RRULE",
+    "SEQUENCE",
+    "STATUS",
+    "SUMMARY",
+    "TRANSP",
+    "UID",
+    "URL",
+    "VALARM",
+];
+
+/// The restriction table of `method` for `VEVENT`s, in the order of [`PROPERTIES`].
+///
+/// `VALARM` is listed as a pseudo property.
+/// Properties not listed here, like `X-` properties, are always allowed.
+fn restrictions(method: Method) -> [Presence; 31] {
+    match method {
+        // ATTACH ATTENDEE CATEGORIES CLASS COMMENT CONTACT CREATED DESCRIPTION DTEND DTSTAMP
+        // DTSTART DURATION EXDATE GEO LAST-MODIFIED LOCATION ORGANIZER PRIORITY RDATE RECURRENCE-ID
+        // RELATED-TO REQUEST-STATUS RESOURCES RRULE SEQUENCE STATUS SUMMARY TRANSP UID URL VALARM
+        Method::Publish => [
+            Any, Forbidden, Any, Optional, Any, Optional, Optional, Optional, Optional, Required,
+            Required, Optional, Any, Optional, Optional, Optional, Required, Optional, Any,
+            Optional, Any, Forbidden, Optional, Optional, Optional, Optional, Required, Optional,
+            Required, Optional, Any,
+        ],
+        Method::Request => [
+            Any, OneOrMore, Any, Optional, Any, Any, Optional, Optional, Optional, Required,
+            Required, Optional, Any, Optional, Optional, Optional, Required, Optional, Any,
+            Optional, Any, Any, Any, Optional, Optional, Optional, Required, Optional, Required,
+            Optional, Any,
+        ],
+        Method::Reply => [
+            Any, Required, Any, Optional, Any, Optional, Optional, Optional, Optional, Required,
+            Optional, Optional, Any, Optional, Optional, Optional, Required, Optional, Any,
+            Optional, Optional, Any, Any, Optional, Optional, Optional, Optional, Optional,
+            Required, Optional, Forbidden,
+        ],
+        Method::Add => [
+            Any, Any, Any, Optional, Any, Any, Optional, Optional, Optional, Required, Required,
+            Optional, Forbidden, Optional, Optional, Optional, Required, Optional, Forbidden,
+            Forbidden, Optional, Forbidden, Any, Forbidden, Required, Optional, Required, Optional,
+            Required, Optional, Any,
+        ],
+        Method::Cancel => [
+            Any, Any, Any, Optional, Any, Any, Optional, Optional, Optional, Required, Optional,
+            Optional, Any, Optional, Optional, Optional, Required, Optional, Any, Optional, Any,
+            Forbidden, Any, Optional, Required, Optional, Optional, Optional, Required, Optional,
+            Forbidden,
+        ],
+        Method::Refresh => [
+            Forbidden, Required, Forbidden, Forbidden, Optional, Forbidden, Forbidden, Forbidden,
+            Forbidden, Required, Forbidden, Forbidden, Forbidden, Forbidden, Forbidden, Forbidden,
+            Required, Forbidden, Forbidden, Optional, Forbidden, Forbidden, Forbidden, Forbidden,
+            Forbidden, Forbidden, Forbidden, Forbidden, Required, Forbidden, Forbidden,
+        ],
+        Method::Counter => [
+            Any, Any, Any, Optional, Any, Any, Optional, Optional, Optional, Required, Required,
+            Optional, Any, Optional, Optional, Optional, Required, Optional, Any, Optional, Any,
+            Any, Any, Optional, Optional, Optional, Required, Optional, Required, Optional, Any,
+        ],
+        Method::DeclineCounter => [
+            Forbidden, Required, Forbidden, Forbidden, Any, Forbidden, Forbidden, Forbidden,
+            Forbidden, Required, Forbidden, Forbidden, Forbidden, Forbidden, Forbidden, Forbidden,
+            Required, Forbidden, Forbidden, Optional, Forbidden, Any, Forbidden, Forbidden,
+            Optional, Forbidden, Forbidden, Forbidden, Required, Forbidden, Forbidden,
+        ],
+    }
+}
+
+/// Checks that `message` is a well-formed iTIP message for events.
+///
+/// Every `VEVENT` must satisfy the restriction table of the message's `METHOD`,
+/// components other than events are ignored.
+pub fn validate(message: &Calendar) -> Result<(), ItipError> {
+    let method = message.get_method().ok_or(ItipError::MissingMethod)?;
+    let events = message.events().collect::<Vec<_>>();
+    let first = events.first().ok_or(ItipError::NoEvent)?;
+    if events
+        .iter()
+        .any(|event| event.get_uid() != first.get_uid())
+    {
+        return Err(ItipError::MixedUids);
+    }
+    events
+        .iter()
+        .try_for_each(|event| validate_event(method, event))
+}
+
+fn validate_event(method: Method, event: &Event) -> Result<(), ItipError> {
+    for (property, presence) in PROPERTIES.into_iter().zip(restrictions(method)) {
+        let count = if property == "VALARM" {
+            event.components().len()
+        } else {
+            usize::from(event.properties().contains_key(property))
+                + event.multi_properties().get(property).map_or(0, Vec::len)
+        };
+        match (presence, count) {
+            (Required | OneOrMore, 0) => {
+                return Err(ItipError::MissingProperty { method, property });
+            }
+            (Required | Optional, 2..) => {
+                return Err(ItipError::TooManyProperties { method, property });
+            }
+            (Forbidden, 1..) if property == "VALARM" => {
+                return Err(ItipError::ForbiddenAlarm { method });
+            }
+            (Forbidden, 1..) => return Err(ItipError::ForbiddenProperty { method, property }),
+            _ => {}
+        }
+    }
+
+    if event.properties().contains_key("DTEND") && event.properties().contains_key("DURATION") {
+        return Err(ItipError::EndAndDuration);
+    }
+    if let Some(status) = event.property_value("STATUS") {
+        let allowed = match method {
+            Method::Cancel => status == "CANCELLED",
+            _ => EventStatus::from_str(status).is_some(),
+        };
+        if !allowed {
+            return Err(ItipError::InvalidStatus {
+                method,
+                status: status.to_owned(),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn message(method: Method, event: Event) -> Result<Calendar, ItipError> {
+    let message = Calendar::new().method(method).push(event).done();
+    validate(&message)?;
+    Ok(message)
+}
+
+fn same_address(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+/// Starts a new event that identifies the same event (or instance) as `event`.
+fn reference(event: &Event) -> Event {
+    let mut reference = Event::new();
+    for key in ["UID", "ORGANIZER", "RECURRENCE-ID", "SEQUENCE"] {
+        if let Some(property) = event.properties().get(key) {
+            reference.append_property(property.clone());
+        }
+    }
+    reference.timestamp(Utc::now());
+    reference
+}
+
+fn find_attendee(event: &Event, cal_address: &str) -> Result<Property, ItipError> {
+    event
+        .multi_properties()
+        .get("ATTENDEE")
+        .into_iter()
+        .flatten()
+        .find(|attendee| same_address(attendee.value(), cal_address))
+        .cloned()
+        .ok_or_else(|| ItipError::UnknownAttendee(cal_address.to_owned()))
+}
+
+/// Invites the attendees of `event` or sends them an update.
+///
+/// The event needs a `UID`, `ORGANIZER`, `DTSTART`, `SUMMARY` and at least one `ATTENDEE`.
+/// Remember to increase the `SEQUENCE` when sending updates that change the schedule.
+pub fn request(event: &Event) -> Result<Calendar, ItipError> {
+    message(Method::Request, event.clone().timestamp(Utc::now()).done())
+}
+
+/// Adds the instances described by `event` to an existing recurring event.
+///
+/// The event carries the `UID` and the increased `SEQUENCE` of the recurring event,
+/// but no recurrence rules of its own.
+pub fn add(event: &Event) -> Result<Calendar, ItipError> {
+    message(Method::Add, event.clone().timestamp(Utc::now()).done())
+}
+
+/// Cancels `event`, or the instance identified by its `RECURRENCE-ID`.
+///
+/// The `SEQUENCE` is increased, the `STATUS` becomes `CANCELLED` and alarms are dropped.
+pub fn cancel(event: &Event) -> Result<Calendar, ItipError> {
+    let mut cancellation = Event::new();
+    for property in event.properties().values() {
+        cancellation.append_property(property.clone());
+    }
+    for property in event
+        .multi_properties()
+        .iter()
+        .filter(|(key, _)| *key != "REQUEST-STATUS")
+        .flat_map(|(_, properties)| properties)
+    {
+        cancellation.append_multi_property(property.clone());
+    }
+
+    let sequence = event.get_sequence().unwrap_or(0) + 1;
+    cancellation
+        .remove_property("REQUEST-STATUS")
+        .sequence(sequence)
+        .status(EventStatus::Cancelled)
+        .timestamp(Utc::now());
+    message(Method::Cancel, cancellation)
+}
+
+/// Answers the invitation `event` on behalf of the attendee `cal_address`.
+///
+/// The reply only contains the replying attendee, with their `PARTSTAT` set to `part_stat`.
+pub fn reply(event: &Event, cal_address: &str, part_stat: PartStat) -> Result<Calendar, ItipError> {
+    let attendee = find_attendee(event, cal_address)?;
+    let attendee = Attendee {
+        part_stat: Some(part_stat),
+        rsvp: None,
+        ..Attendee::try_from(&attendee)
+            .map_err(|_| ItipError::UnknownAttendee(cal_address.to_owned()))?
+    };
+
+    let mut reply = reference(event);
+    reply.attendee(attendee);
+    message(Method::Reply, reply)
+}
+
+/// Asks the organizer of `event` for its latest version on behalf of the attendee `cal_address`.
+pub fn refresh(event: &Event, cal_address: &str) -> Result<Calendar, ItipError> {
+    let mut refresh = reference(event);
+    refresh.remove_property("SEQUENCE");
+    refresh.append_multi_property(Property::new("ATTENDEE", cal_address));
+    message(Method::Refresh, refresh)
+}
+
+/// Proposes `proposal` as a change to an event.
+///
+/// The proposal is the full event as the attendee would like it to be, with the original `UID` and `SEQUENCE`.
+pub fn counter(proposal: &Event) -> Result<Calendar, ItipError> {
+    message(
+        Method::Counter,
+        proposal.clone().timestamp(Utc::now()).done(),
+    )
+}
+
+/// Rejects the counter proposal `counter` sent by the attendee `cal_address`.
+pub fn decline_counter(counter: &Event, cal_address: &str) -> Result<Calendar, ItipError> {
+    let attendee = find_attendee(counter, cal_address)
+        .unwrap_or_else(|_| Property::new("ATTENDEE", cal_address));
+
+    let mut decline = reference(counter);
+    decline.append_multi_property(attendee);
+    message(Method::DeclineCounter, decline)
+}
+
+/// Parameter on `ATTENDEE` remembering the `DTSTAMP` of the last processed reply,
+/// the same convention `CalendarServer` uses.
+const REPLY_DTSTAMP: &str = "X-CALENDARSERVER-DTSTAMP";
+
+/// Why a component of an incoming message could not be applied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Conflict {
+    /// No stored event has the `UID`.
+    UnknownEvent,
+    /// The stored event has a different `ORGANIZER` than the message.
+    OrganizerMismatch,
+    /// The replying CAL-ADDRESS is not an attendee of the stored event.
+    UnknownAttendee(String),
+}
+
+/// What [`apply()`] did with one component of an incoming message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// An attendee's participation status was updated from a `REPLY`.
+    AttendeeUpdated {
+        /// The `UID` of the event.
+        uid: String,
+        /// The instance the reply was about, `None` for the whole event.
+        recurrence_id: Option<DatePerhapsTime>,
+        /// The CAL-ADDRESS of the attendee.
+        attendee: String,
+        /// The new participation status.
+        part_stat: PartStat,
+    },
+    /// The event or one of its instances was cancelled.
+    Cancelled {
+        /// The `UID` of the event.
+        uid: String,
+        /// The cancelled instance, `None` for the whole event.
+        recurrence_id: Option<DatePerhapsTime>,
+    },
+    /// The message is older than what is stored and was ignored.
+    Stale {
+        /// The `UID` of the event.
+        uid: String,
+        /// The instance the message was about, `None` for the whole event.
+        recurrence_id: Option<DatePerhapsTime>,
+    },
+    /// The message does not fit the stored calendar and was ignored.
+    Conflict {
+        /// The `UID` of the event.
+        uid: String,
+        /// The instance the message was about, `None` for the whole event.
+        recurrence_id: Option<DatePerhapsTime>,
+        /// What does not fit.
+        conflict: Conflict,
+    },
+}
+
+/// Applies the incoming iTIP `message` to the `stored` calendar.
+///
+/// Supported are `REPLY`, which the organizer applies to update the `PARTSTAT` of the replying attendee,
+/// and `CANCEL`, which attendees apply to mark events or instances as cancelled.
+/// Components are matched by `UID` and `RECURRENCE-ID`. A reply for a single instance of a recurring event
+/// creates an overridden instance if there is none yet, a cancelled instance without override is excluded with `EXDATE`.
+///
+/// Messages for an older `SEQUENCE`, or with the same `SEQUENCE` but an older `DTSTAMP`, are reported as [`Outcome::Stale`].
+/// Every event of the message produces one [`Outcome`], an `Err` means the message itself is invalid.
+pub fn apply(stored: &mut Calendar, message: &Calendar) -> Result<Vec<Outcome>, ItipError> {
+    validate(message)?;
+    let method = message.get_method().ok_or(ItipError::MissingMethod)?;
+    let apply_event = match method {
+        Method::Reply => apply_reply,
+        Method::Cancel => apply_cancel,
+        other => return Err(ItipError::UnsupportedMethod(other)),
+    };
+    Ok(message
+        .events()
+        .map(|incoming| apply_event(stored, incoming))
+        .collect())
+}
+
+/// Compares two `RECURRENCE-ID`s, which may be written in different timezones.
+fn same_instance(a: &DatePerhapsTime, b: &DatePerhapsTime) -> bool {
+    if a == b {
+        return true;
+    }
+    #[cfg(feature = "chrono-tz")]
+    if let (DatePerhapsTime::DateTime(a), DatePerhapsTime::DateTime(b)) = (a, b)
+        && let (Some(a), Some(b)) = (a.try_into_utc(), b.try_into_utc())
+    {
+        return a == b;
+    }
+    false
+}
+
+/// Indices of the stored master and the override for `recurrence_id` with the given `UID`.
+fn locate(
+    stored: &Calendar,
+    uid: &str,
+    recurrence_id: Option<&DatePerhapsTime>,
+) -> (Option<usize>, Option<usize>) {
+    let mut master = None;
+    let mut instance = None;
+    for (index, component) in stored.components.iter().enumerate() {
+        let CalendarComponent::Event(event) = component else {
+            continue;
+        };
+        if event.get_uid() != Some(uid) {
+            continue;
+        }
+        match (event.get_recurrence_id(), recurrence_id) {
+            (None, _) => master = Some(index),
+            (Some(stored_id), Some(wanted)) if same_instance(&stored_id, wanted) => {
+                instance = Some(index);
+            }
+            _ => {}
+        }
+    }
+    (master, instance)
+}
+
+/// `true` if `incoming` is older than `stored`, by `SEQUENCE` and then `DTSTAMP`.
+fn is_stale(stored: &Event, incoming: &Event) -> bool {
+    let stored_sequence = stored.get_sequence().unwrap_or(0);
+    let incoming_sequence = incoming.get_sequence().unwrap_or(0);
+    incoming_sequence < stored_sequence
+        || (incoming_sequence == stored_sequence
+            && incoming.get_timestamp() < stored.get_timestamp())
+}
+
+fn event_at(stored: &mut Calendar, index: usize) -> Option<&mut Event> {
+    match stored.components.get_mut(index)? {
+        CalendarComponent::Event(event) => Some(event),
+        _ => None,
+    }
+}
+
+/// Creates an overridden instance of `master` starting at `recurrence_id`.
+///
+/// `DTEND` keeps the wall-clock distance to `DTSTART` it has in the master.
+fn override_instance(master: &Event, recurrence_id: &DatePerhapsTime) -> Event {
+    let mut instance = Event::new();
+    for (key, property) in master.properties() {
+        if !matches!(key.as_str(), "RRULE" | "DTSTART" | "DTEND") {
+            instance.append_property(property.clone());
+        }
+    }
+    for property in master
+        .multi_properties()
+        .iter()
+        .filter(|(key, _)| !matches!(key.as_str(), "RDATE" | "EXDATE"))
+        .flat_map(|(_, properties)| properties)
+    {
+        instance.append_multi_property(property.clone());
+    }
+    for alarm in master.components() {
+        instance.append_component(alarm.clone());
+    }
+
+    if let (Some(start), Some(end)) = (master.get_start(), master.get_end()) {
+        instance.ends(recurrence_id.shifted(end.wall_clock() - start.wall_clock()));
+    }
+    instance
+        .starts(recurrence_id.clone())
+        .recurrence_id(recurrence_id.clone())
+        .done()
+}
+
+fn apply_reply(stored: &mut Calendar, reply: &Event) -> Outcome {
+    let uid = reply.get_uid().unwrap_or_default().to_owned();
+    let recurrence_id = reply.get_recurrence_id();
+    let conflict = |conflict| Outcome::Conflict {
+        uid: uid.clone(),
+        recurrence_id: recurrence_id.clone(),
+        conflict,
+    };
+
+    // an override for the replied occurrence is only inserted once the reply is accepted
+    let (master, instance) = locate(stored, &uid, recurrence_id.as_ref());
+    let (index, mut new_instance) = match (master, instance, &recurrence_id) {
+        (_, Some(instance), _) => (instance, None),
+        (Some(master), None, None) => (master, None),
+        (Some(master), None, Some(recurrence_id)) => {
+            let Some(master_event) = event_at(stored, master) else {
+                return conflict(Conflict::UnknownEvent);
+            };
+            let instance = override_instance(master_event, recurrence_id);
+            (master + 1, Some(instance))
+        }
+        (None, None, _) => return conflict(Conflict::UnknownEvent),
+    };
+    let event = match new_instance.as_mut() {
+        Some(instance) => instance,
+        None => match event_at(stored, index) {
+            Some(event) => event,
+            None => return conflict(Conflict::UnknownEvent),
+        },
+    };
+
+    if event.get_organizer().is_some_and(|organizer| {
+        reply
+            .get_organizer()
+            .is_some_and(|other| !same_address(organizer, other))
+    }) {
+        return conflict(Conflict::OrganizerMismatch);
+    }
+    if reply.get_sequence().unwrap_or(0) < event.get_sequence().unwrap_or(0) {
+        return Outcome::Stale { uid, recurrence_id };
+    }
+
+    let Some(replying) = reply.get_attendees().into_iter().next() else {
+        return conflict(Conflict::UnknownAttendee(String::new()));
+    };
+    let mut attendees = event
+        .multi_properties()
+        .get("ATTENDEE")
+        .cloned()
+        .unwrap_or_default();
+    let Some(attendee) = attendees
+        .iter_mut()
+        .find(|attendee| same_address(attendee.value(), &replying.cal_address))
+    else {
+        return conflict(Conflict::UnknownAttendee(replying.cal_address));
+    };
+
+    let replied_at = reply.get_timestamp();
+    let last_reply = attendee.get_param_as(REPLY_DTSTAMP, parse_utc_date_time);
+    if last_reply.is_some() && replied_at <= last_reply {
+        return Outcome::Stale { uid, recurrence_id };
+    }
+
+    let part_stat = replying.part_stat.unwrap_or(PartStat::NeedsAction);
+    attendee.append_parameter(part_stat);
+    attendee.params.remove("RSVP");
+    if let Some(replied_at) = replied_at {
+        attendee.add_parameter(REPLY_DTSTAMP, &format_utc_date_time(replied_at));
+    }
+    event.remove_multi_property("ATTENDEE");
+    for attendee in attendees {
+        event.append_multi_property(attendee);
+    }
+    if let Some(instance) = new_instance {
+        stored.components.insert(index, instance.into());
+    }
+
+    Outcome::AttendeeUpdated {
+        uid,
+        recurrence_id,
+        attendee: replying.cal_address,
+        part_stat,
+    }
+}
+
+fn cancel_event(event: &mut Event, cancellation: &Event) {
+    event.status(EventStatus::Cancelled);
+    if let Some(sequence) = cancellation.get_sequence() {
+        event.sequence(sequence);
+    }
+    if let Some(timestamp) = cancellation.get_timestamp() {
+        event.timestamp(timestamp);
+    }
+}
+
+fn apply_cancel(stored: &mut Calendar, cancellation: &Event) -> Outcome {
+    let uid = cancellation.get_uid().unwrap_or_default().to_owned();
+    let recurrence_id = cancellation.get_recurrence_id();
+
+    let (master, instance) = locate(stored, &uid, recurrence_id.as_ref());
+    let Some(target) = instance.or(master) else {
+        return Outcome::Conflict {
+            uid,
+            recurrence_id,
+            conflict: Conflict::UnknownEvent,
+        };
+    };
+    if event_at(stored, target).is_some_and(|event| is_stale(event, cancellation)) {
+        return Outcome::Stale { uid, recurrence_id };
+    }
+
+    match (&recurrence_id, instance) {
+        // the whole event, including all overridden instances
+        (None, _) => {
+            for component in &mut stored.components {
+                if let CalendarComponent::Event(event) = component
+                    && event.get_uid() == Some(uid.as_str())
+                {
+                    cancel_event(event, cancellation);
+                }
+            }
+        }
+        (Some(_), Some(instance)) => {
+            if let Some(event) = event_at(stored, instance) {
+                cancel_event(event, cancellation);
+            }
+        }
+        (Some(recurrence_id), None) => {
+            if let Some(event) = event_at(stored, target) {
+                event.exdate(recurrence_id.clone());
+            }
+        }
+    }
+    Outcome::Cancelled { uid, recurrence_id }
+}
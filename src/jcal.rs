@@ -0,0 +1,301 @@
+//! # jCal
+//!
+//! Converts calendars to and from [jCal (RFC 7265)](https://datatracker.ietf.org/doc/html/rfc7265),
+//! the JSON representation of iCalendar that other tools understand,
+//! unlike the `serde` implementation of [`parser::Calendar`] which mirrors this crate's types.
+//!
+//! Every component is written as `[name, [properties], [components]]` and every property as
+//! `[name, {parameters}, type, value, ...]`. Names are lowercase, the type comes from the `VALUE` parameter
+//! or the default [`ValueType`] of the property, and values are typed: dates and times use the extended
+//! ISO 8601 format, numbers and booleans are JSON numbers and booleans, and `RRULE`s are objects.
+//!
+//! ```
+//! # use icalendar::{*, jcal};
+//! let calendar = Calendar::new()
+//!     .push(
+//!         Event::with_uid("weekly@example.com")
+//!             .summary("Standup")
+//!             .starts(chrono::NaiveDate::from_ymd_opt(2026, 3, 2).unwrap())
+//!             .add_property("RRULE", "FREQ=WEEKLY;COUNT=4")
+//!             .done(),
+//!     )
+//!     .done();
+//!
+//! let json = jcal::to_value(&calendar);
+//! assert_eq!(json[0], "vcalendar");
+//! let event = &json[2][0];
+//! assert_eq!(event[0], "vevent");
+//! assert_eq!(event[1][0], serde_json::json!(["dtstart", {}, "date", "2026-03-02"]));
+//! assert_eq!(event[1][1], serde_json::json!(["rrule", {}, "recur", {"freq": "WEEKLY", "count": 4}]));
+//!
+//! assert_eq!(jcal::from_value(&json).unwrap(), calendar);
+//! ```
+use std::{fmt, str::FromStr};
+
+use serde_json::{Map, Number, Value};
+
+use crate::{
+    Calendar, ValueType,
+    parser::{self, Property, components::LikeComponent},
+    typed_value::{TypedProperty, TypedValue},
+};
+
+/// Reasons why a jCal document can't be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JcalError {
+    /// The input is not valid JSON.
+    Json(String),
+    /// The JSON does not have the shape of a jCal document.
+    Malformed(&'static str),
+}
+
+impl fmt::Display for JcalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JcalError::Json(reason) => write!(f, "invalid JSON: {reason}"),
+            JcalError::Malformed(reason) => write!(f, "invalid jCal: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for JcalError {}
+
+/// Converts a calendar into jCal.
+///
+/// Unlike the text form, missing `DTSTAMP`s and `UID`s are not filled in.
+pub fn to_value(calendar: &Calendar) -> Value {
+    encode(&parser::Calendar::from(calendar.clone()))
+}
+
+/// Converts a calendar into a jCal string.
+pub fn to_string(calendar: &Calendar) -> String {
+    to_value(calendar).to_string()
+}
+
+/// Reads a calendar from jCal.
+pub fn from_value(value: &Value) -> Result<Calendar, JcalError> {
+    decode(value).map(Into::into)
+}
+
+/// Reads a calendar from a jCal string.
+pub fn from_str(json: &str) -> Result<Calendar, JcalError> {
+    let value: Value =
+        serde_json::from_str(json).map_err(|error| JcalError::Json(error.to_string()))?;
+    from_value(&value)
+}
+
+/// Converts a parsed calendar into jCal, keeping the order of everything.
+pub fn encode(calendar: &parser::Calendar<'_>) -> Value {
+    encode_component(calendar)
+}
+
+/// Reads jCal into a calendar that can be written in the text format.
+pub fn decode(value: &Value) -> Result<parser::Calendar<'static>, JcalError> {
+    let (name, properties, components) = decode_component(value)?;
+    if name != "VCALENDAR" {
+        return Err(JcalError::Malformed(
+            "the outermost component must be vcalendar",
+        ));
+    }
+    Ok(parser::Calendar {
+        properties,
+        components,
+    })
+}
+
+fn encode_component<'a>(component: &impl LikeComponent<'a>) -> Value {
+    Value::Array(vec![
+        component.name().to_ascii_lowercase().into(),
+        component
+            .properties()
+            .iter()
+            .map(|property| encode_property(TypedProperty::from_property(property)))
+            .collect(),
+        component
+            .components()
+            .iter()
+            .map(encode_component)
+            .collect(),
+    ])
+}
+
+fn encode_property(property: TypedProperty) -> Value {
+    let params = property
+        .params
+        .into_iter()
+        .map(|(key, mut values)| {
+            let value = match values.len() {
+                1 => values.pop().unwrap_or_default().into(),
+                _ => values.into(),
+            };
+            (key.to_ascii_lowercase(), value)
+        })
+        .collect::<Map<_, _>>();
+    let value_type = property.value_type.map_or("unknown".into(), |value_type| {
+        value_type.as_str().to_ascii_lowercase()
+    });
+
+    let mut out = vec![
+        property.name.to_ascii_lowercase().into(),
+        Value::Object(params),
+        value_type.into(),
+    ];
+    out.extend(property.values.iter().map(encode_value));
+    Value::Array(out)
+}
+
+fn encode_value(value: &TypedValue) -> Value {
+    match value {
+        TypedValue::Boolean(boolean) => (*boolean).into(),
+        TypedValue::Integer(integer) => (*integer).into(),
+        TypedValue::Float(float) => Number::from_f64(*float).map_or(Value::Null, Value::Number),
+        TypedValue::Text(text) => text.as_str().into(),
+        TypedValue::Period { start, end } => format!("{start}/{end}").into(),
+        TypedValue::Recur(parts) => Value::Object(
+            parts
+                .iter()
+                .map(|(name, values)| {
+                    let value = match values.as_slice() {
+                        [value] => encode_value(value),
+                        values => values.iter().map(encode_value).collect(),
+                    };
+                    (name.clone(), value)
+                })
+                .collect(),
+        ),
+        TypedValue::Structured(parts) => parts.iter().map(encode_value).collect(),
+    }
+}
+
+/// A JSON string, number or boolean as typed value.
+fn decode_scalar(value: &Value) -> Result<TypedValue, JcalError> {
+    match value {
+        Value::String(text) => Ok(TypedValue::Text(text.clone())),
+        Value::Number(number) => Ok(number.as_i64().map_or_else(
+            || TypedValue::Float(number.as_f64().unwrap_or_default()),
+            TypedValue::Integer,
+        )),
+        Value::Bool(boolean) => Ok(TypedValue::Boolean(*boolean)),
+        _ => Err(JcalError::Malformed("expected a string, number or boolean")),
+    }
+}
+
+fn decode_value(value_type: Option<ValueType>, value: &Value) -> Result<TypedValue, JcalError> {
+    match value {
+        Value::Object(parts) => Ok(TypedValue::Recur(
+            parts
+                .iter()
+                .map(|(name, values)| {
+                    let values = match values {
+                        Value::Array(values) => values.iter().map(decode_scalar).collect(),
+                        value => decode_scalar(value).map(|value| vec![value]),
+                    }?;
+                    Ok((name.to_ascii_lowercase(), values))
+                })
+                .collect::<Result<_, JcalError>>()?,
+        )),
+        Value::Array(parts) => Ok(TypedValue::Structured(
+            parts.iter().map(decode_scalar).collect::<Result<_, _>>()?,
+        )),
+        Value::String(text) if value_type == Some(ValueType::Period) => {
+            Ok(match text.split_once('/') {
+                Some((start, end)) => TypedValue::Period {
+                    start: start.into(),
+                    end: end.into(),
+                },
+                None => TypedValue::Text(text.clone()),
+            })
+        }
+        value => decode_scalar(value),
+    }
+}
+
+type DecodedComponent = (
+    String,
+    Vec<Property<'static>>,
+    Vec<parser::Component<'static>>,
+);
+
+fn decode_component(value: &Value) -> Result<DecodedComponent, JcalError> {
+    let [name, properties, components] = value
+        .as_array()
+        .map(Vec::as_slice)
+        .ok_or(JcalError::Malformed("a component must be an array"))?
+    else {
+        return Err(JcalError::Malformed(
+            "a component must have a name, properties and components",
+        ));
+    };
+    let name = name
+        .as_str()
+        .ok_or(JcalError::Malformed("a component name must be a string"))?
+        .to_ascii_uppercase();
+    let properties = properties
+        .as_array()
+        .ok_or(JcalError::Malformed("properties must be an array"))?
+        .iter()
+        .map(|property| decode_property(property).map(TypedProperty::into_property))
+        .collect::<Result<_, _>>()?;
+    let components = components
+        .as_array()
+        .ok_or(JcalError::Malformed("components must be an array"))?
+        .iter()
+        .map(|component| {
+            let (name, properties, components) = decode_component(component)?;
+            Ok(parser::Component {
+                name: name.into(),
+                properties,
+                components,
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((name, properties, components))
+}
+
+fn decode_property(value: &Value) -> Result<TypedProperty, JcalError> {
+    let Some([name, params, value_type, values @ ..]) = value.as_array().map(Vec::as_slice) else {
+        return Err(JcalError::Malformed(
+            "a property must be an array of name, parameters, type and values",
+        ));
+    };
+    if values.is_empty() {
+        return Err(JcalError::Malformed("a property needs a value"));
+    }
+    let name = name
+        .as_str()
+        .ok_or(JcalError::Malformed("a property name must be a string"))?
+        .to_ascii_uppercase();
+    let value_type = value_type
+        .as_str()
+        .ok_or(JcalError::Malformed("a property type must be a string"))?;
+    let value_type = ValueType::from_str(&value_type.to_ascii_uppercase()).ok();
+
+    let params = params
+        .as_object()
+        .ok_or(JcalError::Malformed("parameters must be an object"))?
+        .iter()
+        .map(|(key, value)| {
+            let values = match value {
+                Value::Array(items) => items.iter().collect(),
+                value => vec![value],
+            };
+            let values = values
+                .into_iter()
+                .map(|value| Ok(decode_scalar(value)?.to_text(None)))
+                .collect::<Result<_, JcalError>>()?;
+            Ok((key.to_ascii_uppercase(), values))
+        })
+        .collect::<Result<_, JcalError>>()?;
+
+    let values = values
+        .iter()
+        .map(|value| decode_value(value_type, value))
+        .collect::<Result<_, _>>()?;
+
+    Ok(TypedProperty {
+        name,
+        params,
+        value_type,
+        values,
+    })
+}
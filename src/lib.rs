@@ -39,32 +39,57 @@ macro_rules! write_crlf {
 mod assert;
 
 //pub mod period;
+#[cfg(all(feature = "parser", feature = "recurrence"))]
+pub mod caldav;
 mod calendar;
 mod components;
+pub mod diff;
+pub mod imip;
+pub mod itip;
+#[cfg(feature = "jcal")]
+pub mod jcal;
+mod merge;
+mod normalize;
+pub mod ordering;
 #[cfg(feature = "parser")]
 pub mod parser;
 mod properties;
 #[cfg(feature = "recurrence")]
 mod recurrence;
+#[cfg(feature = "recurrence")]
+mod time_range;
+#[cfg(any(feature = "jcal", feature = "xcal"))]
+mod typed_value;
+mod validation;
 mod value_types;
+#[cfg(feature = "parser")]
+pub mod vdir;
+#[cfg(feature = "xcal")]
+pub mod xcal;
 
 pub use crate::{
-    calendar::{Calendar, CalendarComponent, IntoTimezoneId},
+    calendar::{Calendar, CalendarComponent, CalendarWriter, IntoTimezoneId, UidGroup},
     components::{
         Component, Event, EventLike, Todo, Venue,
         alarm::{Alarm, Related, Trigger},
         date_time::{CalendarDateTime, DatePerhapsTime},
     },
+    merge::{MergeOutcome, MergeStrategy, Merged, Side},
+    normalize::{Change, ChangeKind, EndStyle, Normalization},
     properties::{
-        Class, EventStatus, Parameter, Property, TodoStatus,
+        Class, EventStatus, Method, Parameter, Parameters, Property, TodoStatus,
         attendee::{Attendee, CUType, PartStat, Role},
     },
+    validation::{Severity, Violation, ViolationKind},
     value_types::ValueType,
 };
 
 #[cfg(feature = "recurrence")]
 pub use recurrence::*;
 
+#[cfg(feature = "recurrence")]
+pub use time_range::{Occurrence, TimeRange};
+
 #[cfg(feature = "recurrence")]
 pub use crate::calendar::{CalendarEvent, CalendarTodo};
 
@@ -0,0 +1,205 @@
+//! Merging calendars that contain different versions of the same components.
+//!
+//! [`Calendar::merge`] matches components by `UID` and `RECURRENCE-ID`,
+//! lets a [`MergeStrategy`] decide which version to keep and reports the outcome for every component as [`Merged`].
+use std::{cmp::Ordering, collections::HashMap, fmt};
+
+#[cfg(feature = "chrono-tz")]
+use crate::DatePerhapsTime;
+use crate::{Calendar, CalendarComponent, Component};
+
+/// One of the two versions of a component [`Calendar::merge`] has to choose between.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    /// The version in the calendar that is merged into.
+    Ours,
+    /// The version in the calendar that is merged in.
+    Theirs,
+}
+
+/// How [`Calendar::merge`] decides between two versions of a component.
+#[derive(Default)]
+pub enum MergeStrategy {
+    /// The version with the higher `SEQUENCE` wins,
+    /// after that the one modified last by `LAST-MODIFIED` and then by `DTSTAMP`.
+    /// On a tie ours is kept.
+    #[default]
+    Newest,
+    /// Always keeps ours.
+    Ours,
+    /// Always takes theirs.
+    Theirs,
+    /// Asks the closure, which gets ours and theirs.
+    Custom(Box<Decide>),
+}
+
+/// Picks between ours and theirs.
+type Decide = dyn Fn(&CalendarComponent, &CalendarComponent) -> Side;
+
+impl fmt::Debug for MergeStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeStrategy::Newest => f.write_str("Newest"),
+            MergeStrategy::Ours => f.write_str("Ours"),
+            MergeStrategy::Theirs => f.write_str("Theirs"),
+            MergeStrategy::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+impl MergeStrategy {
+    /// Decides with `decide`, which gets ours and theirs.
+    pub fn custom(
+        decide: impl Fn(&CalendarComponent, &CalendarComponent) -> Side + 'static,
+    ) -> Self {
+        MergeStrategy::Custom(Box::new(decide))
+    }
+
+    fn pick(&self, ours: &CalendarComponent, theirs: &CalendarComponent) -> Side {
+        match self {
+            MergeStrategy::Newest => match newness(theirs).cmp(&newness(ours)) {
+                Ordering::Greater => Side::Theirs,
+                Ordering::Less | Ordering::Equal => Side::Ours,
+            },
+            MergeStrategy::Ours => Side::Ours,
+            MergeStrategy::Theirs => Side::Theirs,
+            MergeStrategy::Custom(decide) => decide(ours, theirs),
+        }
+    }
+}
+
+/// What [`Calendar::merge`] did with a component of the other calendar.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Merged {
+    /// The kind of component, e.g. `VEVENT`.
+    pub name: String,
+    /// The `UID`, or the `TZID` of a `VTIMEZONE`.
+    pub id: Option<String>,
+    /// The `RECURRENCE-ID` of an overridden occurrence.
+    pub recurrence_id: Option<String>,
+    /// What happened to it.
+    pub outcome: MergeOutcome,
+}
+
+/// The kinds of [`Merged`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MergeOutcome {
+    /// There was no version of it yet.
+    Added,
+    /// It replaced our version.
+    Replaced,
+    /// Our version was kept, because both are equal or ours won.
+    Unchanged,
+}
+
+impl fmt::Display for Merged {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+        if let Some(id) = &self.id {
+            write!(f, " {id}")?;
+        }
+        if let Some(recurrence_id) = &self.recurrence_id {
+            write!(f, " at {recurrence_id}")?;
+        }
+        match self.outcome {
+            MergeOutcome::Added => f.write_str(": added"),
+            MergeOutcome::Replaced => f.write_str(": replaced"),
+            MergeOutcome::Unchanged => f.write_str(": unchanged"),
+        }
+    }
+}
+
+/// What identifies a component across calendars.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Key {
+    /// The `UID` and the instant of the `RECURRENCE-ID`, see [`instant`].
+    Uid(String, Option<String>),
+    Timezone(String),
+}
+
+fn key(component: &CalendarComponent) -> Option<Key> {
+    if component.component_kind() == "VTIMEZONE" {
+        let tzid = component.property_value("TZID")?;
+        return Some(Key::Timezone(tzid.to_owned()));
+    }
+    let uid = component.get_uid()?;
+    Some(Key::Uid(uid.to_owned(), instant(component)))
+}
+
+/// The `RECURRENCE-ID` in UTC where its time zone is known,
+/// so the same occurrence written in different time zones matches.
+fn instant(component: &CalendarComponent) -> Option<String> {
+    let recurrence_id = component.property_value("RECURRENCE-ID")?;
+    #[cfg(feature = "chrono-tz")]
+    if let Some(DatePerhapsTime::DateTime(date_time)) = component.get_recurrence_id()
+        && let Some(utc) = date_time.try_into_utc()
+    {
+        return Some(utc.format("%Y%m%dT%H%M%SZ").to_string());
+    }
+    Some(recurrence_id.to_owned())
+}
+
+/// Orders versions of a component from old to new.
+fn newness(component: &CalendarComponent) -> impl Ord {
+    (
+        component.get_sequence().unwrap_or(0),
+        component.get_last_modified(),
+        component.get_timestamp(),
+    )
+}
+
+pub(crate) fn merge(
+    calendar: &mut Calendar,
+    other: Calendar,
+    strategy: &MergeStrategy,
+) -> Vec<Merged> {
+    let mut index = HashMap::new();
+    for (position, component) in calendar.components.iter().enumerate() {
+        if let Some(key) = key(component) {
+            index.entry(key).or_insert(position);
+        }
+    }
+
+    let mut merged = Vec::with_capacity(other.components.len());
+    for theirs in other.components {
+        let key = key(&theirs);
+        let id = match &key {
+            Some(Key::Uid(uid, _)) => Some(uid.clone()),
+            Some(Key::Timezone(tzid)) => Some(tzid.clone()),
+            None => None,
+        };
+        let recurrence_id = theirs
+            .property_value("RECURRENCE-ID")
+            .map(ToOwned::to_owned);
+        let name = theirs.component_kind();
+
+        let ours = key
+            .as_ref()
+            .and_then(|key| index.get(key))
+            .and_then(|position| calendar.components.get_mut(*position));
+        let outcome = match ours {
+            // time zones with the same TZID are assumed to describe the same rules
+            Some(_) if matches!(key, Some(Key::Timezone(_))) => MergeOutcome::Unchanged,
+            Some(ours) if *ours != theirs && strategy.pick(ours, &theirs) == Side::Theirs => {
+                *ours = theirs;
+                MergeOutcome::Replaced
+            }
+            Some(_) => MergeOutcome::Unchanged,
+            None => {
+                if let Some(key) = key {
+                    index.insert(key, calendar.components.len());
+                }
+                calendar.components.push(theirs);
+                MergeOutcome::Added
+            }
+        };
+        merged.push(Merged {
+            name,
+            id,
+            recurrence_id,
+            outcome,
+        });
+    }
+    merged
+}
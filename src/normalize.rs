@@ -0,0 +1,819 @@
+//! Repairs for calendars that don't conform to [RFC 5545](https://datatracker.ietf.org/doc/html/rfc5545).
+//!
+//! [`Calendar::normalize`] fixes whatever [`Calendar::validate`] complains about where the intent is clear,
+//! and brings names, time zone references and the order of properties into one canonical form.
+//! Every modification is reported as a [`Change`].
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    fmt, mem,
+};
+
+use chrono::{Datelike as _, Utc};
+use uuid::Uuid;
+
+use crate::{
+    Calendar, CalendarDateTime, Component, DatePerhapsTime, IntoTimezoneId, Parameter, Property,
+    ValueType,
+    components::{
+        InnerComponent, Other,
+        date_time::{format_utc_date_time, parse_duration, parse_naive_date_time},
+    },
+    validation::{at_most_once, required},
+};
+
+/// How [`Calendar::normalize_with`] expresses when events and to-dos end.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EndStyle {
+    /// `DTEND` for events and `DUE` for to-dos.
+    End,
+    /// `DURATION`, relative to `DTSTART`.
+    Duration,
+}
+
+/// Optional repairs for [`Calendar::normalize_with`], on top of those that are always made.
+///
+/// ```
+/// # use icalendar::{EndStyle, Normalization};
+/// let options = Normalization::new()
+///     .ends(EndStyle::End)
+///     .floating_timezone("Europe/Berlin");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Normalization {
+    ends: Option<EndStyle>,
+    floating_timezone: Option<String>,
+    stable: bool,
+}
+
+impl Normalization {
+    /// Only the repairs that are always made.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Converts `DURATION` into `DTEND` or `DUE` or the other way around.
+    ///
+    /// Only done where `DTSTART` can be read, `DATE` starts are only given whole days.
+    /// Durations are added to the wall-clock time, ignoring daylight saving transitions.
+    pub fn ends(mut self, style: EndStyle) -> Self {
+        self.ends = Some(style);
+        self
+    }
+
+    /// Places floating `DATE-TIME`s in the time zone `tzid`, keeping their wall-clock time.
+    ///
+    /// If the calendar has no `VTIMEZONE` for `tzid`, one is generated from the time zone database
+    /// with the `chrono-tz` feature, covering the years of the placed values.
+    /// Without it, or for zones the database doesn't know, floating values are left alone.
+    pub fn floating_timezone(mut self, tzid: impl IntoTimezoneId) -> Self {
+        self.floating_timezone = Some(tzid.into_timezone_id());
+        self
+    }
+
+    /// Leaves a missing `UID` or `DTSTAMP` missing instead of generating one that differs on every run,
+    /// so normalizing the same calendar always gives the same result.
+    pub fn stable(mut self) -> Self {
+        self.stable = true;
+        self
+    }
+}
+
+/// A modification made by [`Calendar::normalize`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    /// The affected component, e.g. `VCALENDAR/VEVENT[2]/VALARM[0]`,
+    /// indices counting the components of the parent.
+    pub path: String,
+    /// What was changed.
+    pub kind: ChangeKind,
+}
+
+/// The kinds of [`Change`]s.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ChangeKind {
+    /// A missing required property was generated.
+    AddedProperty {
+        /// The name of the property.
+        name: String,
+        /// The generated value.
+        value: String,
+    },
+    /// A repeated or conflicting property was dropped.
+    RemovedProperty {
+        /// The name of the property.
+        name: String,
+        /// The value that was dropped.
+        value: String,
+    },
+    /// A property was replaced by an equivalent one, like `DURATION` by `DTEND`.
+    ConvertedProperty {
+        /// The name of the replaced property.
+        from: String,
+        /// The name of the replacement.
+        to: String,
+        /// The value of the replacement.
+        value: String,
+    },
+    /// A property name was uppercased.
+    RenamedProperty {
+        /// The name as it was.
+        from: String,
+        /// The name as it is now.
+        to: String,
+    },
+    /// A parameter name was uppercased.
+    RenamedParameter {
+        /// The name of the property the parameter belongs to.
+        property: String,
+        /// The name as it was.
+        from: String,
+        /// The name as it is now.
+        to: String,
+    },
+    /// A time zone was renamed to its IANA name throughout the calendar.
+    CanonicalizedTimezone {
+        /// The `TZID` as it was.
+        from: String,
+        /// The `TZID` as it is now.
+        to: String,
+    },
+    /// A floating `DATE-TIME` was placed in the time zone given by [`Normalization::floating_timezone`].
+    AssignedTimezone {
+        /// The name of the property.
+        name: String,
+        /// The `TZID` that was added.
+        tzid: String,
+    },
+    /// A `VTIMEZONE` was generated for [`Normalization::floating_timezone`].
+    AddedTimezone {
+        /// The `TZID` of the new `VTIMEZONE`.
+        tzid: String,
+    },
+    /// The properties of the component were brought into canonical order.
+    ReorderedProperties,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.path)?;
+        match &self.kind {
+            ChangeKind::AddedProperty { name, value } => write!(f, "added {name}:{value}"),
+            ChangeKind::RemovedProperty { name, value } => write!(f, "removed {name}:{value}"),
+            ChangeKind::ConvertedProperty { from, to, value } => {
+                write!(f, "replaced {from} with {to}:{value}")
+            }
+            ChangeKind::RenamedProperty { from, to } => write!(f, "renamed {from} to {to}"),
+            ChangeKind::RenamedParameter { property, from, to } => {
+                write!(f, "renamed parameter {from} of {property} to {to}")
+            }
+            ChangeKind::CanonicalizedTimezone { from, to } => {
+                write!(f, "renamed time zone {from:?} to {to:?}")
+            }
+            ChangeKind::AssignedTimezone { name, tzid } => {
+                write!(f, "placed floating {name} in time zone {tzid:?}")
+            }
+            ChangeKind::AddedTimezone { tzid } => write!(f, "added VTIMEZONE {tzid:?}"),
+            ChangeKind::ReorderedProperties => f.write_str("reordered properties"),
+        }
+    }
+}
+
+/// A value for a required property that can be made up, only the same one every time if `stable`.
+fn generated(name: &str, stable: bool) -> Option<String> {
+    match name {
+        "DTSTAMP" if !stable => Some(format_utc_date_time(Utc::now())),
+        "UID" if !stable => Some(Uuid::new_v4().to_string()),
+        "VERSION" => Some("2.0".into()),
+        "PRODID" => Some("ICALENDAR-RS".into()),
+        _ => None,
+    }
+}
+
+/// How many years past the last placed value a generated `VTIMEZONE` covers for recurring components.
+const RECURRENCE_YEARS: i32 = 10;
+
+/// Whether [`generate_timezone`] knows `tzid`.
+#[cfg(feature = "chrono-tz")]
+fn known_timezone(tzid: &str) -> bool {
+    tzid.parse::<chrono_tz::Tz>().is_ok()
+}
+
+#[cfg(not(feature = "chrono-tz"))]
+fn known_timezone(_tzid: &str) -> bool {
+    false
+}
+
+/// A `VTIMEZONE` for `tzid` from the time zone database,
+/// with an observance for the start of year `first` and every change of offset until the end of year `last`.
+#[cfg(feature = "chrono-tz")]
+fn generate_timezone(tzid: &str, first: i32, last: i32) -> Option<Other> {
+    use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Offset as _, TimeZone as _};
+    use chrono_tz::{OffsetComponents as _, OffsetName as _, TzOffset};
+
+    let tz = tzid.parse::<chrono_tz::Tz>().ok()?;
+    let new_year = |year| {
+        Some(
+            NaiveDate::from_ymd_opt(year, 1, 1)?
+                .and_time(NaiveTime::MIN)
+                .and_utc(),
+        )
+    };
+    let (start, end) = (new_year(first)?, new_year(last.checked_add(1)?)?);
+    let offset = |instant: DateTime<Utc>| tz.offset_from_utc_datetime(&instant.naive_utc());
+    let format_offset = |offset: &TzOffset| {
+        let seconds = offset.fix().local_minus_utc();
+        let (sign, seconds) = if seconds < 0 {
+            ('-', -seconds)
+        } else {
+            ('+', seconds)
+        };
+        let formatted = format!("{sign}{:02}{:02}", seconds / 3600, seconds / 60 % 60);
+        match seconds % 60 {
+            0 => formatted,
+            rest => format!("{formatted}{rest:02}"),
+        }
+    };
+    let observance = |from: TzOffset, to: TzOffset, at: DateTime<Utc>| {
+        let kind = if to.dst_offset().is_zero() {
+            "STANDARD"
+        } else {
+            "DAYLIGHT"
+        };
+        let local = at.naive_utc() + Duration::seconds(from.fix().local_minus_utc().into());
+        let mut inner = InnerComponent::default();
+        for (key, value) in [
+            ("DTSTART", local.format("%Y%m%dT%H%M%S").to_string()),
+            ("TZOFFSETFROM", format_offset(&from)),
+            ("TZOFFSETTO", format_offset(&to)),
+        ] {
+            inner
+                .properties
+                .insert(key.into(), Property::new(key, value));
+        }
+        if let Some(name) = to.abbreviation() {
+            inner
+                .properties
+                .insert("TZNAME".into(), Property::new("TZNAME", name));
+        }
+        Other::from((kind.to_owned(), inner))
+    };
+
+    let mut timezone = InnerComponent::default();
+    timezone
+        .properties
+        .insert("TZID".into(), Property::new("TZID", tzid));
+    timezone
+        .components
+        .push(observance(offset(start), offset(start), start));
+    let mut day = start;
+    while day < end {
+        let next = day + Duration::days(1);
+        if offset(next) != offset(day) {
+            // offsets change at whole seconds
+            let (mut before, mut after) = (day, next);
+            while after - before > Duration::seconds(1) {
+                let middle = before + (after - before) / 2;
+                if offset(middle) == offset(before) {
+                    before = middle;
+                } else {
+                    after = middle;
+                }
+            }
+            timezone
+                .components
+                .push(observance(offset(before), offset(after), after));
+        }
+        day = next;
+    }
+    Some(Other::from(("VTIMEZONE".to_owned(), timezone)))
+}
+
+#[cfg(not(feature = "chrono-tz"))]
+fn generate_timezone(_tzid: &str, _first: i32, _last: i32) -> Option<Other> {
+    None
+}
+
+/// Properties holding a `DATE-TIME` that may be floating.
+fn takes_timezone(name: &str) -> bool {
+    matches!(
+        name,
+        "DTSTART" | "DTEND" | "DUE" | "RECURRENCE-ID" | "EXDATE" | "RDATE"
+    )
+}
+
+/// The IANA name for a `TZID`, if it differs in more than case or decoration.
+fn canonical_tzid(tzid: &str) -> Option<String> {
+    let trimmed = tzid.trim();
+    let stripped = match trimmed.strip_prefix('/') {
+        // globally unique ids like `/mozilla.org/20050126_1/Europe/Berlin`
+        Some(unique) => {
+            let segments = unique.split('/').collect::<Vec<_>>();
+            let mut rest = segments.as_slice();
+            if let [origin, tail @ ..] = rest
+                && origin.contains('.')
+            {
+                rest = tail;
+                if let [version, tail @ ..] = rest
+                    && version.contains(|c: char| c.is_ascii_digit())
+                {
+                    rest = tail;
+                }
+            }
+            rest.join("/")
+        }
+        None => trimmed.to_owned(),
+    };
+    if let Some(iana) = windows_timezone(&stripped) {
+        return Some(iana.to_owned());
+    }
+    #[cfg(feature = "chrono-tz")]
+    if let Some(tz) = chrono_tz::TZ_VARIANTS
+        .iter()
+        .find(|tz| tz.name().eq_ignore_ascii_case(&stripped))
+    {
+        return Some(tz.name().to_owned());
+    }
+    (!stripped.is_empty()).then_some(stripped)
+}
+
+/// The IANA zones for the Windows time zone names most often found in Outlook exports,
+/// following the CLDR mapping.
+fn windows_timezone(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "UTC" | "Coordinated Universal Time" => "Etc/UTC",
+        "GMT Standard Time" => "Europe/London",
+        "W. Europe Standard Time" => "Europe/Berlin",
+        "Romance Standard Time" => "Europe/Paris",
+        "Central Europe Standard Time" => "Europe/Budapest",
+        "Central European Standard Time" => "Europe/Warsaw",
+        "GTB Standard Time" => "Europe/Bucharest",
+        "FLE Standard Time" => "Europe/Kiev",
+        "Russian Standard Time" => "Europe/Moscow",
+        "Turkey Standard Time" => "Europe/Istanbul",
+        "Israel Standard Time" => "Asia/Jerusalem",
+        "South Africa Standard Time" => "Africa/Johannesburg",
+        "Arabian Standard Time" => "Asia/Dubai",
+        "India Standard Time" => "Asia/Calcutta",
+        "China Standard Time" => "Asia/Shanghai",
+        "Singapore Standard Time" => "Asia/Singapore",
+        "Tokyo Standard Time" => "Asia/Tokyo",
+        "Korea Standard Time" => "Asia/Seoul",
+        "AUS Eastern Standard Time" => "Australia/Sydney",
+        "New Zealand Standard Time" => "Pacific/Auckland",
+        "Hawaiian Standard Time" => "Pacific/Honolulu",
+        "Alaskan Standard Time" => "America/Anchorage",
+        "Pacific Standard Time" => "America/Los_Angeles",
+        "US Mountain Standard Time" => "America/Phoenix",
+        "Mountain Standard Time" => "America/Denver",
+        "Central Standard Time" => "America/Chicago",
+        "Eastern Standard Time" => "America/New_York",
+        "Atlantic Standard Time" => "America/Halifax",
+        "SA Pacific Standard Time" => "America/Bogota",
+        "E. South America Standard Time" => "America/Sao_Paulo",
+        _ => return None,
+    })
+}
+
+/// The canonical order of calendar properties: the ones every calendar has first, then by name.
+fn calendar_order(a: &Property, b: &Property) -> Ordering {
+    let rank = |property: &Property| {
+        ["VERSION", "PRODID", "CALSCALE", "METHOD"]
+            .iter()
+            .position(|name| *name == property.key())
+            .unwrap_or(usize::MAX)
+    };
+    rank(a).cmp(&rank(b)).then_with(|| a.key().cmp(b.key()))
+}
+
+/// The canonical order of repeated properties: by value, then by parameters.
+pub(crate) fn value_order(a: &Property, b: &Property) -> Ordering {
+    let params = |property: &Property| {
+        property
+            .params()
+            .iter()
+            .map(|param| (param.key().to_owned(), param.value().to_owned()))
+            .collect::<Vec<_>>()
+    };
+    a.value()
+        .cmp(b.value())
+        .then_with(|| params(a).cmp(&params(b)))
+}
+
+/// Calls `f` with the kind, contents and path of `inner` and all its descendants.
+fn descend(
+    kind: &str,
+    inner: &mut InnerComponent,
+    path: &str,
+    f: &mut impl FnMut(&str, &mut InnerComponent, &str),
+) {
+    f(kind, inner, path);
+    for (index, child) in inner.components.iter_mut().enumerate() {
+        let kind = child.component_kind();
+        let path = format!("{path}/{kind}[{index}]");
+        descend(&kind, child.inner_mut(), &path, f);
+    }
+}
+
+/// Calls `f` for every component of `calendar`, nested ones included.
+fn each_component(calendar: &mut Calendar, f: &mut impl FnMut(&str, &mut InnerComponent, &str)) {
+    for (index, component) in calendar.components.iter_mut().enumerate() {
+        let kind = component.component_kind();
+        let path = format!("VCALENDAR/{kind}[{index}]");
+        descend(&kind, component.inner_mut(), &path, f);
+    }
+}
+
+/// All properties of `inner`, single and multi.
+fn all_properties(inner: &mut InnerComponent) -> impl Iterator<Item = &mut Property> {
+    inner
+        .properties
+        .values_mut()
+        .chain(inner.multi_properties.values_mut().flatten())
+}
+
+struct Normalizer<'o> {
+    options: &'o Normalization,
+    changes: Vec<Change>,
+    /// The `TZID` floating values are placed in, if there is or can be a `VTIMEZONE` for it.
+    floating_tzid: Option<String>,
+    /// The first and last year of the values placed in `floating_tzid`.
+    floating_years: Option<(i32, i32)>,
+    /// Whether a recurring component had values placed in `floating_tzid`.
+    floating_recurs: bool,
+}
+
+impl Normalizer<'_> {
+    fn report(&mut self, path: &str, kind: ChangeKind) {
+        self.changes.push(Change {
+            path: path.to_owned(),
+            kind,
+        });
+    }
+
+    /// Uppercases the names of `property` and its parameters.
+    fn uppercase(&mut self, property: &mut Property, path: &str) {
+        let name = property.key.to_ascii_uppercase();
+        if name != property.key {
+            let from = mem::replace(&mut property.key, name.clone());
+            let to = name.clone();
+            self.report(path, ChangeKind::RenamedProperty { from, to });
+        }
+        property.params = mem::take(&mut property.params)
+            .into_iter()
+            .map(|param| {
+                let upper = param.key().to_ascii_uppercase();
+                if upper == param.key() {
+                    return param;
+                }
+                let (from, to) = (param.key().to_owned(), upper.clone());
+                let property = name.clone();
+                self.report(path, ChangeKind::RenamedParameter { property, from, to });
+                param.renamed(&upper)
+            })
+            .collect();
+    }
+
+    fn names(&mut self, inner: &mut InnerComponent, path: &str) {
+        for (_, mut property) in mem::take(&mut inner.properties) {
+            self.uppercase(&mut property, path);
+            // a lowercase duplicate of a property is dropped with the other duplicates
+            if inner.properties.contains_key(property.key()) {
+                inner.insert_multi(property);
+            } else {
+                inner.properties.insert(property.key().to_owned(), property);
+            }
+        }
+        for mut property in mem::take(&mut inner.multi_properties)
+            .into_values()
+            .flatten()
+        {
+            self.uppercase(&mut property, path);
+            inner.insert_multi(property);
+        }
+    }
+
+    /// Keeps the first of repeated properties that may occur only once.
+    fn duplicates(&mut self, kind: &str, inner: &mut InnerComponent, path: &str) {
+        for name in at_most_once(kind) {
+            let Some(repeated) = inner.multi_properties.remove(*name) else {
+                continue;
+            };
+            let mut repeated = repeated.into_iter();
+            if !inner.properties.contains_key(*name)
+                && let Some(first) = repeated.next()
+            {
+                inner.properties.insert((*name).to_owned(), first);
+            }
+            for property in repeated {
+                let (name, value) = (property.key, property.val);
+                self.report(path, ChangeKind::RemovedProperty { name, value });
+            }
+        }
+    }
+
+    fn missing(&mut self, kind: &str, inner: &mut InnerComponent, path: &str) {
+        for name in required(kind) {
+            if inner.properties.contains_key(*name) || inner.multi_properties.contains_key(*name) {
+                continue;
+            }
+            if let Some(value) = generated(name, self.options.stable) {
+                inner
+                    .properties
+                    .insert((*name).to_owned(), Property::new(*name, &value));
+                let name = (*name).to_owned();
+                self.report(path, ChangeKind::AddedProperty { name, value });
+            }
+        }
+    }
+
+    /// Resolves `DTEND`/`DUE` together with `DURATION` and converts one into the other.
+    fn ends(&mut self, kind: &str, inner: &mut InnerComponent, path: &str) {
+        let end_name = match kind {
+            "VEVENT" => "DTEND",
+            "VTODO" => "DUE",
+            _ => return,
+        };
+        let properties = &mut inner.properties;
+        if properties.contains_key(end_name)
+            && let Some(duration) = properties.remove("DURATION")
+        {
+            let (name, value) = (duration.key, duration.val);
+            self.report(path, ChangeKind::RemovedProperty { name, value });
+        }
+
+        let Some(start) = properties
+            .get("DTSTART")
+            .and_then(DatePerhapsTime::from_property)
+        else {
+            return;
+        };
+        let (from, replacement) = match self.options.ends {
+            Some(EndStyle::End) => {
+                let Some(duration) = properties
+                    .get("DURATION")
+                    .and_then(|duration| parse_duration(duration.value()))
+                else {
+                    return;
+                };
+                if matches!(start, DatePerhapsTime::Date(_)) && duration.num_seconds() % 86_400 != 0
+                {
+                    return;
+                }
+                ("DURATION", start.shifted(duration).to_property(end_name))
+            }
+            Some(EndStyle::Duration) => {
+                let Some(end) = properties
+                    .get(end_name)
+                    .and_then(DatePerhapsTime::from_property)
+                else {
+                    return;
+                };
+                use CalendarDateTime::*;
+                use DatePerhapsTime::{Date, DateTime};
+                let duration = match (start, end) {
+                    (Date(start), Date(end)) => end - start,
+                    (DateTime(Utc(start)), DateTime(Utc(end))) => end - start,
+                    (DateTime(Floating(start)), DateTime(Floating(end))) => end - start,
+                    (
+                        DateTime(WithTimezone {
+                            date_time: start,
+                            tzid: start_tzid,
+                        }),
+                        DateTime(WithTimezone {
+                            date_time: end,
+                            tzid: end_tzid,
+                        }),
+                    ) if start_tzid == end_tzid => end - start,
+                    _ => return,
+                };
+                if duration < chrono::Duration::zero() {
+                    return;
+                }
+                (end_name, Property::new("DURATION", duration.to_string()))
+            }
+            None => return,
+        };
+        properties.remove(from);
+        let (from, to) = (from.to_owned(), replacement.key().to_owned());
+        let value = replacement.value().to_owned();
+        properties.insert(to.clone(), replacement);
+        self.report(path, ChangeKind::ConvertedProperty { from, to, value });
+    }
+
+    /// Renames time zones to their IANA names, wherever they are referenced.
+    fn timezones(&mut self, calendar: &mut Calendar) {
+        let mut tzids = BTreeSet::new();
+        tzids.extend(
+            calendar
+                .property_value("X-WR-TIMEZONE")
+                .map(ToOwned::to_owned),
+        );
+        each_component(calendar, &mut |kind, inner, _| {
+            if kind == "VTIMEZONE"
+                && let Some(tzid) = inner.properties.get("TZID")
+            {
+                tzids.insert(tzid.value().to_owned());
+            }
+            for property in all_properties(inner) {
+                if let Some(tzid) = property.params().get("TZID") {
+                    tzids.insert(tzid.value().to_owned());
+                }
+            }
+        });
+
+        // never merge two time zones into one
+        let mut taken = tzids.clone();
+        let mut renames = BTreeMap::new();
+        for tzid in tzids {
+            if let Some(canonical) = canonical_tzid(&tzid)
+                && canonical != tzid
+                && taken.insert(canonical.clone())
+            {
+                renames.insert(tzid, canonical);
+            }
+        }
+        if renames.is_empty() {
+            return;
+        }
+
+        let rename = |property: &mut Property| {
+            if let Some(canonical) = renames.get(property.value()) {
+                property.val = canonical.clone();
+            }
+        };
+        calendar
+            .properties
+            .iter_mut()
+            .filter(|property| property.key() == "X-WR-TIMEZONE")
+            .for_each(rename);
+        each_component(calendar, &mut |kind, inner, _| {
+            if kind == "VTIMEZONE" {
+                inner.properties.get_mut("TZID").map(rename);
+            }
+            for property in all_properties(inner) {
+                if let Some(canonical) = property
+                    .params()
+                    .get("TZID")
+                    .and_then(|tzid| renames.get(tzid.value()))
+                {
+                    property.params.insert(Parameter::new("TZID", canonical));
+                }
+            }
+        });
+        for (from, to) in renames {
+            self.report("VCALENDAR", ChangeKind::CanonicalizedTimezone { from, to });
+        }
+    }
+
+    /// Picks the `TZID` for floating values, unless the calendar lacks a `VTIMEZONE` for it that can't be generated.
+    fn floating_target(&mut self, calendar: &Calendar) {
+        let Some(tzid) = &self.options.floating_timezone else {
+            return;
+        };
+        let defined = calendar.components.iter().any(|component| {
+            component.component_kind() == "VTIMEZONE"
+                && component.property_value("TZID") == Some(tzid)
+        });
+        if defined || known_timezone(tzid) {
+            self.floating_tzid = Some(tzid.clone());
+        }
+    }
+
+    fn floating(&mut self, kind: &str, inner: &mut InnerComponent, path: &str) {
+        let Some(tzid) = self.floating_tzid.clone() else {
+            return;
+        };
+        // observances are in local time by definition
+        if matches!(kind, "STANDARD" | "DAYLIGHT") {
+            return;
+        }
+        let mut assigned = Vec::new();
+        for property in all_properties(inner) {
+            let values = property
+                .value()
+                .split(',')
+                .map(|value| value.split('/').next().and_then(parse_naive_date_time))
+                .collect::<Option<Vec<_>>>();
+            let floating = takes_timezone(property.key())
+                && !property.params().contains_key("TZID")
+                && property.value_type() != Some(ValueType::Date);
+            if let Some(values) = values.filter(|_| floating) {
+                property.params.insert(Parameter::new("TZID", &tzid));
+                assigned.push(property.key().to_owned());
+                for value in values {
+                    let year = value.year();
+                    let (first, last) = self.floating_years.get_or_insert((year, year));
+                    *first = year.min(*first);
+                    *last = year.max(*last);
+                }
+            }
+        }
+        if !assigned.is_empty()
+            && (inner.properties.contains_key("RRULE")
+                || inner.multi_properties.contains_key("RDATE"))
+        {
+            self.floating_recurs = true;
+        }
+        for name in assigned {
+            let tzid = tzid.clone();
+            self.report(path, ChangeKind::AssignedTimezone { name, tzid });
+        }
+    }
+
+    /// Adds a `VTIMEZONE` for the floating values that were placed in a zone the calendar doesn't define.
+    fn floating_timezone(&mut self, calendar: &mut Calendar) {
+        let (Some(tzid), Some((first, mut last))) = (&self.floating_tzid, self.floating_years)
+        else {
+            return;
+        };
+        let defined = calendar.components.iter().any(|component| {
+            component.component_kind() == "VTIMEZONE"
+                && component.property_value("TZID") == Some(tzid)
+        });
+        if self.floating_recurs {
+            last += RECURRENCE_YEARS;
+        }
+        if !defined && let Some(timezone) = generate_timezone(tzid, first, last) {
+            let path = format!("VCALENDAR/VTIMEZONE[{}]", calendar.components.len());
+            calendar.components.push(timezone.into());
+            let tzid = tzid.clone();
+            self.report(&path, ChangeKind::AddedTimezone { tzid });
+        }
+    }
+
+    /// Sorts repeated properties, single ones are kept sorted by name anyway.
+    fn order(&mut self, inner: &mut InnerComponent, path: &str) {
+        let mut reordered = false;
+        for properties in inner.multi_properties.values_mut() {
+            if !properties.is_sorted_by(|a, b| value_order(a, b).is_le()) {
+                properties.sort_by(value_order);
+                reordered = true;
+            }
+        }
+        if reordered {
+            self.report(path, ChangeKind::ReorderedProperties);
+        }
+    }
+
+    fn calendar_properties(&mut self, properties: &mut Vec<Property>) {
+        const PATH: &str = "VCALENDAR";
+        for property in properties.iter_mut() {
+            self.uppercase(property, PATH);
+        }
+
+        for name in at_most_once("VCALENDAR") {
+            let mut seen = false;
+            for property in mem::take(properties) {
+                if property.key() != *name || !mem::replace(&mut seen, true) {
+                    properties.push(property);
+                } else {
+                    let (name, value) = (property.key, property.val);
+                    self.report(PATH, ChangeKind::RemovedProperty { name, value });
+                }
+            }
+        }
+
+        for name in required("VCALENDAR") {
+            if !properties.iter().any(|property| property.key() == *name)
+                && let Some(value) = generated(name, self.options.stable)
+            {
+                properties.push(Property::new(*name, &value));
+                let name = (*name).to_owned();
+                self.report(PATH, ChangeKind::AddedProperty { name, value });
+            }
+        }
+
+        if !properties.is_sorted_by(|a, b| calendar_order(a, b).is_le()) {
+            properties.sort_by(calendar_order);
+            self.report(PATH, ChangeKind::ReorderedProperties);
+        }
+    }
+}
+
+/// Repairs `calendar` in place and reports what was changed.
+pub(crate) fn normalize(calendar: &mut Calendar, options: &Normalization) -> Vec<Change> {
+    let mut normalizer = Normalizer {
+        options,
+        changes: Vec::new(),
+        floating_tzid: None,
+        floating_years: None,
+        floating_recurs: false,
+    };
+    normalizer.calendar_properties(&mut calendar.properties);
+    each_component(calendar, &mut |kind, inner, path| {
+        normalizer.names(inner, path);
+        normalizer.duplicates(kind, inner, path);
+        normalizer.missing(kind, inner, path);
+        normalizer.ends(kind, inner, path);
+    });
+    normalizer.timezones(calendar);
+    normalizer.floating_target(calendar);
+    each_component(calendar, &mut |kind, inner, path| {
+        normalizer.floating(kind, inner, path);
+        normalizer.order(inner, path);
+    });
+    normalizer.floating_timezone(calendar);
+    normalizer.changes
+}
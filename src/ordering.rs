@@ -0,0 +1,210 @@
+//! # Ordering components
+//!
+//! [`Calendar::sort_canonical`] puts components into an order that only depends on their content,
+//! along with repeated properties and the parameters of each property,
+//! so calendars with the same components serialize to the same text. [`canonical`] is the comparator behind it.
+//!
+//! With the `chrono-tz` feature [`Calendar::sort_by_start`] orders components chronologically,
+//! [`ByStart`] compares components and date or date-time values the same way.
+//!
+//! ```
+//! # use icalendar::*;
+//! # use chrono::*;
+//! let mut calendar = Calendar::new();
+//! calendar.push(Event::with_uid("late").starts(Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap()).done());
+//! calendar.push(Todo::with_uid("undated").done());
+//! calendar.push(Event::with_uid("all-day").all_day(NaiveDate::from_ymd_opt(2026, 3, 2).unwrap()).done());
+//! calendar.push(Event::with_uid("early").starts(Utc.with_ymd_and_hms(2026, 3, 2, 8, 0, 0).unwrap()).done());
+//!
+//! # #[cfg(feature = "chrono-tz")] {
+//! calendar.sort_by_start();
+//! let uids = calendar.components.iter().filter_map(|c| c.get_uid()).collect::<Vec<_>>();
+//! assert_eq!(uids, ["all-day", "early", "late", "undated"]);
+//! # }
+//!
+//! calendar.sort_canonical();
+//! let uids = calendar.components.iter().filter_map(|c| c.get_uid()).collect::<Vec<_>>();
+//! assert_eq!(uids, ["all-day", "early", "late", "undated"]);
+//! ```
+use std::cmp::Ordering;
+
+#[cfg(feature = "chrono-tz")]
+use chrono::{DateTime, Utc};
+
+#[cfg(feature = "chrono-tz")]
+use crate::DatePerhapsTime;
+use crate::{
+    Calendar, CalendarComponent, Component, components::InnerComponent, normalize::value_order,
+};
+
+/// Whether `component` is a `VTIMEZONE`, which are kept in front of the components referring to them.
+fn is_timezone<C: Component + ?Sized>(component: &C) -> bool {
+    component.component_kind() == "VTIMEZONE"
+}
+
+/// Compares components chronologically by their effective start.
+///
+/// The effective start is `DTSTART`, or `DUE` for to-dos without one, or else `CREATED`.
+/// Date-times in a known `TZID` are compared in UTC,
+/// floating date-times, `DATE` values and unknown `TZID`s are evaluated in the [reference timezone](ByStart::with_timezone).
+/// At the same instant a `DATE` comes before a date-time, components without start come last.
+#[cfg(feature = "chrono-tz")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ByStart {
+    timezone: chrono_tz::Tz,
+}
+
+#[cfg(feature = "chrono-tz")]
+impl Default for ByStart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "chrono-tz")]
+impl ByStart {
+    /// Evaluates floating values in UTC.
+    pub fn new() -> Self {
+        ByStart {
+            timezone: chrono_tz::UTC,
+        }
+    }
+
+    /// Sets the timezone in which floating date-times and `DATE` values are evaluated.
+    pub fn with_timezone(mut self, timezone: chrono_tz::Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// The reference timezone for floating values.
+    pub fn timezone(&self) -> chrono_tz::Tz {
+        self.timezone
+    }
+
+    /// The effective start of `component` in UTC.
+    pub fn start<C: Component + ?Sized>(&self, component: &C) -> Option<DateTime<Utc>> {
+        self.key(&effective_start(component)?)
+            .map(|(instant, _)| instant)
+    }
+
+    /// Compares two components by their effective start.
+    pub fn compare<C: Component + ?Sized>(&self, a: &C, b: &C) -> Ordering {
+        let a = effective_start(a);
+        let b = effective_start(b);
+        self.compare_optional(a.as_ref(), b.as_ref())
+    }
+
+    /// Compares two date or date-time values, values that can't be resolved come last.
+    pub fn compare_values(&self, a: &DatePerhapsTime, b: &DatePerhapsTime) -> Ordering {
+        self.compare_optional(Some(a), Some(b))
+    }
+
+    fn compare_optional(
+        &self,
+        a: Option<&DatePerhapsTime>,
+        b: Option<&DatePerhapsTime>,
+    ) -> Ordering {
+        match (a.and_then(|a| self.key(a)), b.and_then(|b| self.key(b))) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+
+    /// The instant of `value`, and whether it has a time.
+    fn key(&self, value: &DatePerhapsTime) -> Option<(DateTime<Utc>, bool)> {
+        let instant = value.resolve(self.timezone)?;
+        Some((instant, matches!(value, DatePerhapsTime::DateTime(_))))
+    }
+}
+
+#[cfg(feature = "chrono-tz")]
+fn effective_start<C: Component + ?Sized>(component: &C) -> Option<DatePerhapsTime> {
+    ["DTSTART", "DUE", "CREATED"]
+        .into_iter()
+        .find_map(|key| DatePerhapsTime::from_property(component.properties().get(key)?))
+}
+
+/// Compares components in the canonical order of [`Calendar::sort_canonical`].
+///
+/// `VTIMEZONE`s come first, then components are ordered by kind, `UID` (or `TZID`),
+/// `RECURRENCE-ID` with the master first, and finally by their properties and subcomponents.
+pub fn canonical(a: &CalendarComponent, b: &CalendarComponent) -> Ordering {
+    canonical_key(a).cmp(&canonical_key(b))
+}
+
+type CanonicalKey = (bool, String, Option<String>, Option<String>, Vec<String>);
+
+fn canonical_key(component: &CalendarComponent) -> CanonicalKey {
+    let id = if is_timezone(component) {
+        component.property_value("TZID")
+    } else {
+        component.get_uid()
+    };
+    (
+        !is_timezone(component),
+        component.component_kind(),
+        id.map(ToOwned::to_owned),
+        component
+            .property_value("RECURRENCE-ID")
+            .map(ToOwned::to_owned),
+        content(component),
+    )
+}
+
+/// The content lines of `component`, with subcomponents in canonical order.
+fn content<C: Component + ?Sized>(component: &C) -> Vec<String> {
+    let mut lines = vec![format!("BEGIN:{}", component.component_kind())];
+    lines.extend(
+        component
+            .properties()
+            .values()
+            .chain(component.multi_properties().values().flatten())
+            .map(|property| property.to_line().unwrap_or_default()),
+    );
+    let mut children = component
+        .components()
+        .iter()
+        .map(content)
+        .collect::<Vec<_>>();
+    children.sort();
+    lines.extend(children.into_iter().flatten());
+    lines.push(format!("END:{}", component.component_kind()));
+    lines
+}
+
+fn sort_children(inner: &mut InnerComponent) {
+    sort_properties(inner);
+    for child in &mut inner.components {
+        sort_children(child.inner_mut());
+    }
+    inner.components.sort_by_cached_key(content);
+}
+
+/// Sorts the parameters of every property and repeated properties by value, as normalizing does.
+fn sort_properties(inner: &mut InnerComponent) {
+    let properties = inner.properties.values_mut();
+    for property in properties.chain(inner.multi_properties.values_mut().flatten()) {
+        property.params.sort();
+    }
+    for properties in inner.multi_properties.values_mut() {
+        properties.sort_by(value_order);
+    }
+}
+
+pub(crate) fn sort_canonical(calendar: &mut Calendar) {
+    for component in &mut calendar.components {
+        sort_children(component.inner_mut());
+    }
+    calendar.components.sort_by_cached_key(canonical_key);
+}
+
+#[cfg(feature = "chrono-tz")]
+pub(crate) fn sort_by_start(calendar: &mut Calendar, order: &ByStart) {
+    calendar.components.sort_by(|a, b| {
+        is_timezone(b)
+            .cmp(&is_timezone(a))
+            .then_with(|| order.compare(a, b))
+    });
+}
@@ -0,0 +1,325 @@
+use nom::Finish;
+use nom_language::error::VerboseError;
+
+use super::{
+    Calendar, Component, Location, ParseError, Warning, WarningKind, components::components,
+    into_calendar,
+};
+
+/// `0x80..=0x9F` in windows-1252, everything else matches ISO-8859-1.
+const WINDOWS_1252: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž', '\u{8f}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}', 'ž', 'Ÿ',
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Charset {
+    Utf8,
+    Windows1252,
+}
+
+/// Parse iCalendar file content that is not known to be valid UTF-8.
+///
+/// The content is unfolded before it is decoded, so multi-byte characters split by folding survive.
+/// A byte order mark selects UTF-8 or UTF-16, otherwise UTF-8 is assumed.
+/// Properties with a `CHARSET` parameter are decoded accordingly and lose the parameter,
+/// `ISO-8859-1` is read as its superset `windows-1252`, which is what most producers really mean.
+/// Invalid sequences are replaced with `U+FFFD`.
+///
+/// Every replacement and every charset that could not be honored is reported as [`Warning`],
+/// located in the folded `input`.
+/// The syntax is still checked strictly, see [`read_calendar_lenient`](super::read_calendar_lenient) for a forgiving parser.
+///
+/// ```
+/// # use icalendar::parser::read_calendar_bytes;
+/// let input = b"BEGIN:VCALENDAR\r
+/// BEGIN:VEVENT\r
+/// SUMMARY:a caf\xc3\r
+///  \xa9 split by folding\r
+/// LOCATION;CHARSET=ISO-8859-1:M\xfcnchen\r
+/// DESCRIPTION:caf\xe9\r
+/// END:VEVENT\r
+/// END:VCALENDAR\r
+/// ";
+/// let (calendar, warnings) = read_calendar_bytes(input).unwrap();
+/// let event = &calendar.components[0];
+/// assert_eq!(event.properties[0].val, "a café split by folding");
+/// assert_eq!(event.properties[1].val, "München");
+/// assert!(event.properties[1].params.is_empty());
+/// assert_eq!(event.properties[2].val, "caf\u{fffd}");
+/// assert_eq!(
+///     warnings[0].to_string(),
+///     "line 6, column 16: replaced bytes that are not valid UTF-8"
+/// );
+/// ```
+pub fn read_calendar_bytes(input: &[u8]) -> Result<(Calendar<'static>, Vec<Warning>), ParseError> {
+    let mut warnings = Vec::new();
+    let transcoded;
+    let input = if let Some(rest) = input.strip_prefix(b"\xef\xbb\xbf") {
+        rest
+    } else if let Some(rest) = input.strip_prefix(b"\xff\xfe") {
+        transcoded = utf16(rest, u16::from_le_bytes, &mut warnings);
+        &transcoded
+    } else if let Some(rest) = input.strip_prefix(b"\xfe\xff") {
+        transcoded = utf16(rest, u16::from_be_bytes, &mut warnings);
+        &transcoded
+    } else {
+        input
+    };
+
+    let mut physical = input
+        .split(|byte| *byte == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .collect::<Vec<_>>();
+    if input.ends_with(b"\n") {
+        physical.pop();
+    }
+
+    let mut folded = String::new();
+    let mut unfolded = String::new();
+    let mut start = 0;
+    while start < physical.len() {
+        let continuations = physical
+            .iter()
+            .skip(start + 1)
+            .take_while(|(_, line)| line.starts_with(b" ") || line.starts_with(b"\t"))
+            .count();
+        let end = start + 1 + continuations;
+        let segments = physical.get(start..end).unwrap_or_default();
+        decode_line(segments, &mut folded, &mut unfolded, &mut warnings);
+        start = end;
+    }
+
+    let (_, components) = components(&unfolded)
+        .finish()
+        .map_err(|e: VerboseError<&str>| ParseError::in_folded(&folded, 1, &unfolded, &e))?;
+    let mut calendar = into_calendar(components);
+    for property in &mut calendar.properties {
+        property
+            .params
+            .retain(|param| !is_charset(param.key.as_str()));
+    }
+    calendar.components.iter_mut().for_each(drop_charset);
+
+    Ok((
+        Calendar {
+            properties: calendar
+                .properties
+                .into_iter()
+                .map(super::Property::into_owned)
+                .collect(),
+            components: calendar
+                .components
+                .into_iter()
+                .map(Component::into_owned)
+                .collect(),
+        },
+        warnings,
+    ))
+}
+
+fn is_charset(key: &str) -> bool {
+    key.eq_ignore_ascii_case("CHARSET")
+}
+
+/// Removes `CHARSET` parameters, the values are UTF-8 now.
+fn drop_charset(component: &mut Component<'_>) {
+    for property in &mut component.properties {
+        property
+            .params
+            .retain(|param| !is_charset(param.key.as_str()));
+    }
+    component.components.iter_mut().for_each(drop_charset);
+}
+
+/// Transcodes UTF-16 to UTF-8, replacing unpaired surrogates.
+fn utf16(input: &[u8], unit: fn([u8; 2]) -> u16, warnings: &mut Vec<Warning>) -> Vec<u8> {
+    let units = input.chunks(2).map(|pair| {
+        unit([
+            pair.first().copied().unwrap_or(0),
+            pair.get(1).copied().unwrap_or(0),
+        ])
+    });
+    let mut output = String::new();
+    let (mut line, mut column) = (1, 1);
+    for decoded in char::decode_utf16(units) {
+        let c = decoded.unwrap_or_else(|_| {
+            warnings.push(Warning {
+                location: Location {
+                    line,
+                    column,
+                    snippet: String::new(),
+                },
+                kind: WarningKind::InvalidEncoding {
+                    charset: "UTF-16".into(),
+                },
+            });
+            char::REPLACEMENT_CHARACTER
+        });
+        output.push(c);
+        if c == '\n' {
+            (line, column) = (line + 1, 1);
+        } else {
+            column += 1;
+        }
+    }
+    output.into_bytes()
+}
+
+/// The value of the `CHARSET` parameter in a content line.
+fn charset_of(line: &[u8]) -> Option<String> {
+    let mut quoted = false;
+    let head_end = line
+        .iter()
+        .position(|byte| {
+            quoted ^= *byte == b'"';
+            !quoted && *byte == b':'
+        })
+        .unwrap_or(line.len());
+    let head = String::from_utf8_lossy(line.get(..head_end).unwrap_or_default());
+    head.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        is_charset(key.trim()).then(|| value.trim().trim_matches('"').to_owned())
+    })
+}
+
+/// Decodes the physical lines `segments` that make up one content line,
+/// appending them to `folded` as they are and to `unfolded` joined.
+fn decode_line(
+    segments: &[(usize, &[u8])],
+    folded: &mut String,
+    unfolded: &mut String,
+    warnings: &mut Vec<Warning>,
+) {
+    // the content line, and where each physical line starts in it
+    let mut bytes = Vec::new();
+    let mut starts = Vec::new();
+    for (index, (_, segment)) in segments.iter().enumerate() {
+        starts.push(bytes.len());
+        bytes.extend_from_slice(segment.get(usize::from(index > 0)..).unwrap_or_default());
+    }
+    let segment_of = |offset: usize| {
+        starts
+            .partition_point(|start| *start <= offset)
+            .saturating_sub(1)
+    };
+
+    let mut unsupported = None;
+    let charset = match charset_of(&bytes) {
+        None => Charset::Utf8,
+        Some(name) => match name.to_ascii_lowercase().as_str() {
+            "utf-8" | "utf8" | "us-ascii" | "ascii" => Charset::Utf8,
+            "iso-8859-1" | "iso8859-1" | "latin1" | "latin-1" | "windows-1252" | "cp1252" => {
+                Charset::Windows1252
+            }
+            _ => {
+                unsupported = Some(name);
+                Charset::Utf8
+            }
+        },
+    };
+
+    let mut pieces = vec![String::new(); segments.len()];
+    // replacements as physical line index and column
+    let mut replaced = Vec::new();
+    match charset {
+        Charset::Windows1252 => {
+            for (offset, byte) in bytes.iter().enumerate() {
+                let c = match byte {
+                    0x80..=0x9f => WINDOWS_1252
+                        .get(usize::from(byte - 0x80))
+                        .copied()
+                        .unwrap_or(char::REPLACEMENT_CHARACTER),
+                    _ => char::from(*byte),
+                };
+                if let Some(piece) = pieces.get_mut(segment_of(offset)) {
+                    piece.push(c);
+                }
+            }
+        }
+        Charset::Utf8 => {
+            let mut offset = 0;
+            while let Some(rest) = bytes.get(offset..).filter(|rest| !rest.is_empty()) {
+                let (valid, invalid) = match std::str::from_utf8(rest) {
+                    Ok(valid) => (valid, None),
+                    Err(error) => (
+                        std::str::from_utf8(rest.get(..error.valid_up_to()).unwrap_or_default())
+                            .unwrap_or_default(),
+                        Some(
+                            error
+                                .error_len()
+                                .unwrap_or(rest.len() - error.valid_up_to()),
+                        ),
+                    ),
+                };
+                for (position, c) in valid.char_indices() {
+                    if let Some(piece) = pieces.get_mut(segment_of(offset + position)) {
+                        piece.push(c);
+                    }
+                }
+                offset += valid.len();
+                if let Some(len) = invalid {
+                    let segment = segment_of(offset);
+                    if let Some(piece) = pieces.get_mut(segment) {
+                        // continuation lines start with the whitespace that was removed
+                        let column = piece.chars().count() + usize::from(segment > 0) + 1;
+                        replaced.push((segment, column));
+                        piece.push(char::REPLACEMENT_CHARACTER);
+                    }
+                    offset += len;
+                }
+            }
+        }
+    }
+
+    let mut physical_lines = Vec::new();
+    for (index, ((_, segment), piece)) in segments.iter().zip(&pieces).enumerate() {
+        let mut line = String::new();
+        if index > 0
+            && let Some(whitespace) = segment.first()
+        {
+            line.push(char::from(*whitespace));
+        }
+        line.push_str(piece);
+        physical_lines.push(line);
+    }
+    if let (Some(charset), Some((line, _)), Some(snippet)) =
+        (unsupported, segments.first(), physical_lines.first())
+    {
+        warnings.push(Warning {
+            location: Location {
+                line: *line,
+                column: 1,
+                snippet: snippet.clone(),
+            },
+            kind: WarningKind::UnsupportedCharset { charset },
+        });
+    }
+    for (segment, column) in replaced {
+        if let (Some((line, _)), Some(snippet)) =
+            (segments.get(segment), physical_lines.get(segment))
+        {
+            warnings.push(Warning {
+                location: Location {
+                    line: *line,
+                    column,
+                    snippet: snippet.clone(),
+                },
+                kind: WarningKind::InvalidEncoding {
+                    charset: "UTF-8".into(),
+                },
+            });
+        }
+    }
+
+    for line in physical_lines {
+        folded.push_str(&line);
+        folded.push('\n');
+    }
+    for piece in pieces {
+        unfolded.push_str(&piece);
+    }
+    unfolded.push('\n');
+}
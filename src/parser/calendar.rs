@@ -1,6 +1,15 @@
 use crate::calendar::CalendarComponent;
 
-use super::{Component, Property, components::LikeComponent, read_calendar, unfold};
+use nom::Finish;
+use nom_language::error::VerboseError;
+
+#[cfg(test)]
+use super::read_calendar;
+use super::{
+    Component, ParseError, Property,
+    components::{LikeComponent, components},
+    into_calendar, unfold,
+};
 use core::{fmt, str::FromStr};
 
 /// Helper-type for reserialization
@@ -130,10 +139,14 @@ impl<'a> From<Vec<Component<'a>>> for crate::Calendar {
 }
 
 impl FromStr for crate::Calendar {
-    type Err = String;
+    type Err = ParseError;
 
+    /// Parses a calendar, locating errors in the folded `s`.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let from_parsed = crate::Calendar::from(read_calendar(&unfold(s))?);
-        Ok(from_parsed)
+        let unfolded = unfold(s);
+        let (_, components) = components(&unfolded)
+            .finish()
+            .map_err(|e: VerboseError<&str>| ParseError::in_folded(s, 1, &unfolded, &e))?;
+        Ok(crate::Calendar::from(into_calendar(components)))
     }
 }
@@ -59,6 +59,23 @@ impl Component<'_> {
             .iter()
             .find(|prop| prop.name == name.as_ref())
     }
+
+    /// Copies borrowed content so the component outlives the input.
+    pub fn into_owned(self) -> Component<'static> {
+        Component {
+            name: self.name.into_owned(),
+            properties: self
+                .properties
+                .into_iter()
+                .map(Property::into_owned)
+                .collect(),
+            components: self
+                .components
+                .into_iter()
+                .map(Component::into_owned)
+                .collect(),
+        }
+    }
 }
 
 pub(crate) trait LikeComponent<'a> {
@@ -159,22 +176,32 @@ impl From<Component<'_>> for InnerComponent {
 
 impl<'a> From<Component<'a>> for CalendarComponent {
     fn from(component: Component<'a>) -> CalendarComponent {
-        use crate::{Event, Todo, Venue};
-        match component.name.as_ref() {
-            "VEVENT" => Event::from(InnerComponent::from(component)).into(),
-            "VTODO" => Todo::from(InnerComponent::from(component)).into(),
-            "VVENUE" => Venue::from(InnerComponent::from(component)).into(),
-            _ => Other::from((component.name.to_string(), InnerComponent::from(component))).into(),
-        }
+        let name = component.name.to_string();
+        into_calendar_component(name, InnerComponent::from(component))
+    }
+}
+
+/// Wraps `inner` into the kind of component `name` stands for.
+pub(crate) fn into_calendar_component(name: String, inner: InnerComponent) -> CalendarComponent {
+    use crate::{Event, Todo, Venue};
+    match name.as_str() {
+        "VEVENT" => Event::from(inner).into(),
+        "VTODO" => Todo::from(inner).into(),
+        "VVENUE" => Venue::from(inner).into(),
+        _ => Other::from((name, inner)).into(),
     }
 }
 
 impl FromStr for CalendarComponent {
-    type Err = String;
+    type Err = super::ParseError;
 
+    /// Parses a component, locating errors in the folded `s`.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let from_parsed = CalendarComponent::from(read_component(&unfold(s))?);
-        Ok(from_parsed)
+        let unfolded = unfold(s);
+        let (_, component) = component(&unfolded)
+            .finish()
+            .map_err(|e: VerboseError<&str>| super::ParseError::in_folded(s, 1, &unfolded, &e))?;
+        Ok(CalendarComponent::from(component))
     }
 }
 
@@ -238,20 +265,14 @@ enum ComponentChild<'a> {
     Component(Component<'a>),
 }
 
-pub fn read_component(input: &str) -> Result<Component<'_>, String> {
-    component(input)
-        .finish()
-        .map(|(_, component)| component)
-        .map_err(|e: VerboseError<&str>| format!("error: {}", convert_error(input, e.clone())))
-}
-
 pub fn component<'i, E>(input: &'i str) -> IResult<&'i str, Component<'i>, E>
 where
     E: ParseError<&'i str> + ContextError<&'i str>,
 {
     let (input, name) = line("BEGIN:", valid_key_sequence_cow).parse(input)?;
 
-    let (input, (properties, components)) = many_till(
+    // after a BEGIN there is no alternative, report what went wrong inside
+    let (input, (properties, components)) = cut(many_till(
         cut(context(
             "component",
             alt((
@@ -260,7 +281,7 @@ where
             )),
         )),
         line("END:", cut(context("mismatching end", tag(name.as_str())))),
-    )
+    ))
     .map(|(body_elements, _)| {
         let mut properties = Vec::new();
         let mut components = Vec::new();
@@ -436,13 +457,15 @@ fn test_multi_properties() {
                 name: "ATTENDEE".into(),
                 val: "mailto:email@example.com".into(),
                 params: vec![
-                    Parameter {
-                        key: "EMAIL".into(),
-                        val: Some("email@example.com".into()),
-                    },
                     Parameter {
                         key: "CUTYPE".into(),
                         val: Some("INDIVIDUAL".into()),
+                        list: false,
+                    },
+                    Parameter {
+                        key: "EMAIL".into(),
+                        val: Some("email@example.com".into()),
+                        list: false,
                     },
                 ],
             }
@@ -451,13 +474,15 @@ fn test_multi_properties() {
                 name: "ATTENDEE".into(),
                 val: "mailto:dmail@example.com".into(),
                 params: vec![
-                    Parameter {
-                        key: "EMAIL".into(),
-                        val: Some("dmail@example.com".into()),
-                    },
                     Parameter {
                         key: "CUTYPE".into(),
                         val: Some("INDIVIDUAL".into()),
+                        list: false,
+                    },
+                    Parameter {
+                        key: "EMAIL".into(),
+                        val: Some("dmail@example.com".into()),
+                        list: false,
                     },
                 ],
             }
@@ -0,0 +1,336 @@
+use std::{fmt, io};
+
+use nom_language::error::{VerboseError, VerboseErrorKind};
+
+use super::Limit;
+
+/// Where in the input a [`ParseError`] occurred.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Location {
+    /// The line, starting at 1.
+    ///
+    /// When parsing with [`str::parse`] or [`CalendarReader`](super::CalendarReader)
+    /// lines are counted in the input as it was given, before unfolding.
+    pub line: usize,
+    /// The column in characters, starting at 1.
+    pub column: usize,
+    /// The content of the offending line.
+    pub snippet: String,
+}
+
+impl Location {
+    /// Finds the byte `offset` of the unfolded form of `text` in `text` itself,
+    /// `text` starting on line `first_line`.
+    ///
+    /// With `unfolding` disabled, `offset` refers to `text` as it is.
+    pub(crate) fn find(text: &str, first_line: usize, offset: usize, unfolding: bool) -> Self {
+        let mut lines = text.split_inclusive('\n').enumerate().peekable();
+        // the unfolded offset at which the current line starts
+        let mut start = 0;
+        while let Some((index, line)) = lines.next() {
+            let skip = usize::from(unfolding && index > 0 && line.starts_with([' ', '\t']));
+            let continued = unfolding
+                && lines
+                    .peek()
+                    .is_some_and(|(_, next)| next.starts_with([' ', '\t']));
+            let content = line.trim_end_matches(['\r', '\n']);
+            let kept = if continued { content.len() } else { line.len() };
+            let len = kept.saturating_sub(skip);
+
+            if offset < start + len || lines.peek().is_none() {
+                let byte = (skip + offset.saturating_sub(start)).min(content.len());
+                let column = content
+                    .char_indices()
+                    .take_while(|(position, _)| *position < byte)
+                    .count();
+                return Location {
+                    line: first_line + index,
+                    column: column + 1,
+                    snippet: content.to_owned(),
+                };
+            }
+            start += len;
+        }
+        Location {
+            line: first_line,
+            column: 1,
+            snippet: String::new(),
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// Reasons why an iCalendar document can't be read.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// Reading the input failed.
+    Io(io::Error),
+    /// A line is not valid UTF-8.
+    InvalidUtf8 {
+        /// The first invalid byte.
+        location: Location,
+    },
+    /// The input ended before a component was closed.
+    Unterminated {
+        /// The `BEGIN` of the component.
+        location: Location,
+        /// The name of the component.
+        name: String,
+    },
+    /// An `END` without a corresponding `BEGIN`.
+    UnexpectedEnd {
+        /// The `END`.
+        location: Location,
+        /// The name after `END:`.
+        name: String,
+    },
+    /// A component was closed with the name of a different one.
+    MismatchedEnd {
+        /// The name after `END:`.
+        location: Location,
+        /// The name after `BEGIN:`.
+        begin: String,
+        /// The name after `END:`.
+        end: String,
+    },
+    /// A property name contains characters that are not allowed,
+    /// or is not followed by `:`.
+    InvalidPropertyKey {
+        /// The first character that doesn't belong to the name.
+        location: Location,
+    },
+    /// A property parameter could not be read.
+    InvalidParameter {
+        /// The start of the parameter.
+        location: Location,
+    },
+    /// The content does not follow the iCalendar syntax in some other way.
+    Syntax {
+        /// Where the parser gave up.
+        location: Location,
+        /// What the parser expected.
+        message: String,
+    },
+    /// The input exceeds one of the configured [`Limits`](super::Limits).
+    LimitExceeded {
+        /// The line at which the limit was exceeded, unless the input is too large as a whole.
+        location: Option<Location>,
+        /// The exceeded limit.
+        limit: Limit,
+    },
+}
+
+/// The name after `BEGIN:` or `END:` if `line` starts with `prefix`.
+pub(crate) fn strip_marker<'a>(line: &'a str, prefix: &str) -> Option<&'a str> {
+    line.get(..prefix.len())
+        .filter(|start| start.eq_ignore_ascii_case(prefix))
+        .and_then(|_| line.get(prefix.len()..))
+        .map(str::trim)
+}
+
+impl ParseError {
+    /// Where the error occurred, unless reading failed altogether.
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            ParseError::Io(_) => None,
+            ParseError::InvalidUtf8 { location }
+            | ParseError::Unterminated { location, .. }
+            | ParseError::UnexpectedEnd { location, .. }
+            | ParseError::MismatchedEnd { location, .. }
+            | ParseError::InvalidPropertyKey { location }
+            | ParseError::InvalidParameter { location }
+            | ParseError::Syntax { location, .. } => Some(location),
+            ParseError::LimitExceeded { location, .. } => location.as_ref(),
+        }
+    }
+
+    /// Interprets a [`nom`] error that occurred while parsing `text`.
+    ///
+    /// `locate` turns an offset in `text` into a [`Location`].
+    pub(crate) fn from_nom(
+        text: &str,
+        error: &VerboseError<&str>,
+        locate: impl Fn(usize) -> Location,
+    ) -> Self {
+        let remaining = error.errors.first().map_or("", |(remaining, _)| *remaining);
+        let offset = text.len().saturating_sub(remaining.len());
+        let line_start = text
+            .get(..offset)
+            .and_then(|consumed| consumed.rfind('\n'))
+            .map_or(0, |newline| newline + 1);
+        let line = text
+            .get(line_start..)
+            .and_then(|rest| rest.lines().next())
+            .unwrap_or_default();
+
+        // the components that are open at the offending line
+        let mut open = Vec::new();
+        let mut position = 0;
+        for previous in text
+            .get(..line_start)
+            .unwrap_or_default()
+            .split_inclusive('\n')
+        {
+            if let Some(name) = strip_marker(previous, "BEGIN:") {
+                open.push((name, position));
+            } else if strip_marker(previous, "END:").is_some() {
+                open.pop();
+            }
+            position += previous.len();
+        }
+
+        let has_context = |wanted: &str| {
+            error.errors.iter().any(|(_, kind)| {
+                matches!(kind, VerboseErrorKind::Context(context) if *context == wanted)
+            })
+        };
+
+        if has_context("mismatching end") {
+            return ParseError::MismatchedEnd {
+                location: locate(offset),
+                begin: open
+                    .last()
+                    .map(|(name, _)| (*name).to_owned())
+                    .unwrap_or_default(),
+                end: strip_marker(line, "END:").unwrap_or_default().to_owned(),
+            };
+        }
+        if text
+            .get(offset..)
+            .is_some_and(|rest| rest.trim().is_empty())
+            && let Some((name, start)) = open.last()
+        {
+            return ParseError::Unterminated {
+                location: locate(*start),
+                name: (*name).to_owned(),
+            };
+        }
+        if open.is_empty()
+            && let Some(name) = strip_marker(line, "END:")
+        {
+            return ParseError::UnexpectedEnd {
+                location: locate(line_start),
+                name: name.to_owned(),
+            };
+        }
+        // inside a component the parser gets stuck where a line stops making sense
+        let stuck = error.errors.first().is_some_and(|(_, kind)| {
+            matches!(
+                kind,
+                VerboseErrorKind::Nom(
+                    nom::error::ErrorKind::Many0 | nom::error::ErrorKind::ManyTill
+                )
+            )
+        });
+        if has_context("property separator") || (stuck && !open.is_empty()) {
+            return if remaining.starts_with(';') {
+                ParseError::InvalidParameter {
+                    location: locate(offset),
+                }
+            } else {
+                ParseError::InvalidPropertyKey {
+                    location: locate(offset),
+                }
+            };
+        }
+        if open.is_empty() && strip_marker(line, "BEGIN:").is_none() {
+            return ParseError::Syntax {
+                location: locate(line_start),
+                message: "expected BEGIN".into(),
+            };
+        }
+
+        let message = error
+            .errors
+            .iter()
+            .find_map(|(_, kind)| match kind {
+                VerboseErrorKind::Context(context) => Some((*context).to_owned()),
+                _ => None,
+            })
+            .or_else(|| {
+                error.errors.first().map(|(_, kind)| match kind {
+                    VerboseErrorKind::Char(c) => format!("expected '{c}'"),
+                    VerboseErrorKind::Nom(kind) => kind.description().to_owned(),
+                    VerboseErrorKind::Context(context) => (*context).to_owned(),
+                })
+            })
+            .unwrap_or_default();
+        ParseError::Syntax {
+            location: locate(offset),
+            message,
+        }
+    }
+
+    /// Interprets a [`nom`] error for a `text` that was given to the parser as is.
+    pub(crate) fn in_text(text: &str, error: &VerboseError<&str>) -> Self {
+        Self::from_nom(text, error, |offset| Location::find(text, 1, offset, false))
+    }
+
+    /// Interprets a [`nom`] error for the unfolded form of `folded`.
+    pub(crate) fn in_folded(
+        folded: &str,
+        first_line: usize,
+        unfolded: &str,
+        error: &VerboseError<&str>,
+    ) -> Self {
+        Self::from_nom(unfolded, error, |offset| {
+            Location::find(folded, first_line, offset, true)
+        })
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Io(error) => write!(f, "failed to read calendar: {error}"),
+            ParseError::InvalidUtf8 { location } => write!(f, "{location}: invalid UTF-8"),
+            ParseError::Unterminated { location, name } => {
+                write!(f, "{location}: BEGIN:{name} is never closed")
+            }
+            ParseError::UnexpectedEnd { location, name } => {
+                write!(f, "{location}: END:{name} without BEGIN:{name}")
+            }
+            ParseError::MismatchedEnd {
+                location,
+                begin,
+                end,
+            } => write!(f, "{location}: BEGIN:{begin} is closed by END:{end}"),
+            ParseError::InvalidPropertyKey { location } => {
+                write!(f, "{location}: invalid property name")
+            }
+            ParseError::InvalidParameter { location } => {
+                write!(f, "{location}: invalid parameter")
+            }
+            ParseError::Syntax { location, message } => write!(f, "{location}: {message}"),
+            ParseError::LimitExceeded {
+                location: Some(location),
+                limit,
+            } => write!(f, "{location}: {limit}"),
+            ParseError::LimitExceeded {
+                location: None,
+                limit,
+            } => write!(f, "{limit}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ParseError {
+    fn from(error: io::Error) -> Self {
+        ParseError::Io(error)
+    }
+}
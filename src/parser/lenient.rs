@@ -0,0 +1,258 @@
+use std::fmt;
+
+use nom::Finish;
+use nom_language::error::VerboseError;
+
+use super::{
+    Calendar, Component, Location, Parameter, Property,
+    properties::property,
+    reader::{Line, Lines, Marker},
+};
+
+/// Something [`read_calendar_lenient`] or [`read_calendar_bytes`](super::read_calendar_bytes) had to drop or repair.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Warning {
+    /// The affected line.
+    pub location: Location,
+    /// What happened to it.
+    pub kind: WarningKind,
+}
+
+/// The kinds of [`Warning`]s.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WarningKind {
+    /// A line that is neither a property nor `BEGIN` or `END` was dropped.
+    DroppedLine,
+    /// A property outside of any component was dropped.
+    PropertyOutsideComponent,
+    /// An `END` without a matching `BEGIN` was dropped.
+    UnexpectedEnd {
+        /// The name after `END:`.
+        name: String,
+    },
+    /// A component that was still open when the input ended
+    /// or when a component around it was closed has been closed.
+    ClosedImplicitly {
+        /// The name of the component.
+        name: String,
+    },
+    /// A property without `:` was kept with an empty value.
+    MissingValue {
+        /// The name of the property.
+        name: String,
+    },
+    /// `VALUE=DATE` was added to a property that holds dates but defaults to `DATE-TIME`.
+    AddedValueDate {
+        /// The name of the property.
+        name: String,
+    },
+    /// Bytes that are not valid in the charset of the line were replaced with `U+FFFD`.
+    InvalidEncoding {
+        /// The charset the bytes were decoded with.
+        charset: String,
+    },
+    /// A `CHARSET` parameter names a charset that can't be decoded, the line was read as UTF-8.
+    UnsupportedCharset {
+        /// The value of the parameter.
+        charset: String,
+    },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.location)?;
+        match &self.kind {
+            WarningKind::DroppedLine => write!(f, "dropped unreadable line"),
+            WarningKind::PropertyOutsideComponent => {
+                write!(f, "dropped property outside of a component")
+            }
+            WarningKind::UnexpectedEnd { name } => {
+                write!(f, "dropped END:{name} without BEGIN:{name}")
+            }
+            WarningKind::ClosedImplicitly { name } => write!(f, "closed BEGIN:{name}"),
+            WarningKind::MissingValue { name } => write!(f, "{name} has no value"),
+            WarningKind::AddedValueDate { name } => write!(f, "added VALUE=DATE to {name}"),
+            WarningKind::InvalidEncoding { charset } => {
+                write!(f, "replaced bytes that are not valid {charset}")
+            }
+            WarningKind::UnsupportedCharset { charset } => {
+                write!(f, "unsupported CHARSET={charset}, read as UTF-8")
+            }
+        }
+    }
+}
+
+/// Properties that hold a `DATE-TIME` unless `VALUE=DATE` says otherwise.
+const DATE_TIME_PROPERTIES: [&str; 6] = [
+    "DTSTART",
+    "DTEND",
+    "DUE",
+    "RECURRENCE-ID",
+    "EXDATE",
+    "RDATE",
+];
+
+/// Parse iCalendar file content, repairing what can be repaired and dropping what can't.
+///
+/// Unlike [`read_calendar`](super::read_calendar) this expects the content as it was given, still folded,
+/// and never fails: lines that can't be read are skipped, unterminated components are closed,
+/// and every such intervention is reported as [`Warning`] with its location.
+///
+/// ```
+/// # use icalendar::parser::{read_calendar_lenient, WarningKind};
+/// let input = "BEGIN:VCALENDAR\r
+/// BEGIN:VEVENT\r
+/// UID:lenient\r
+/// DTSTART:20050120\r
+/// NOT A PROPERTY\r
+/// END:VCALENDAR\r
+/// ";
+/// let (calendar, warnings) = read_calendar_lenient(input);
+/// assert_eq!(calendar.components[0].properties.len(), 2);
+/// assert_eq!(
+///     warnings.iter().map(|warning| warning.to_string()).collect::<Vec<_>>(),
+///     [
+///         "line 4, column 1: added VALUE=DATE to DTSTART",
+///         "line 5, column 1: dropped unreadable line",
+///         "line 2, column 1: closed BEGIN:VEVENT",
+///     ]
+/// );
+/// ```
+pub fn read_calendar_lenient(input: &str) -> (Calendar<'static>, Vec<Warning>) {
+    let mut lenient = Lenient::default();
+    let mut lines = Lines::new(input.as_bytes());
+    // a `&str` can't contain invalid UTF-8 and reading from a slice can't fail
+    while let Ok(Some(line)) = lines.next_logical() {
+        lenient.line(line);
+    }
+    lenient.finish()
+}
+
+#[derive(Default)]
+struct Lenient {
+    /// The open components and where they begin.
+    open: Vec<(Component<'static>, Location)>,
+    roots: Vec<Component<'static>>,
+    warnings: Vec<Warning>,
+}
+
+impl Lenient {
+    fn warn(&mut self, location: Location, kind: WarningKind) {
+        self.warnings.push(Warning { location, kind });
+    }
+
+    fn line(&mut self, line: Line) {
+        if line.text.trim().is_empty() {
+            return;
+        }
+        match Marker::of(&line.text) {
+            Some(Marker::Begin(name)) if !name.is_empty() => {
+                let component = Component {
+                    name: name.to_owned().into(),
+                    properties: Vec::new(),
+                    components: Vec::new(),
+                };
+                self.open.push((component, line.location()));
+            }
+            Some(Marker::End(name))
+                if self
+                    .open
+                    .iter()
+                    .any(|(component, _)| component.name.as_str().eq_ignore_ascii_case(name)) =>
+            {
+                while let Some((component, location)) = self.open.pop() {
+                    if component.name.as_str().eq_ignore_ascii_case(name) {
+                        self.close(component);
+                        break;
+                    }
+                    let name = component.name.as_str().to_owned();
+                    self.warn(location, WarningKind::ClosedImplicitly { name });
+                    self.close(component);
+                }
+            }
+            Some(Marker::End(name)) => {
+                let name = name.to_owned();
+                self.warn(line.location(), WarningKind::UnexpectedEnd { name });
+            }
+            Some(Marker::Begin(_)) => self.warn(line.location(), WarningKind::DroppedLine),
+            None => self.property(&line),
+        }
+    }
+
+    fn property(&mut self, line: &Line) {
+        let parsed = property::<VerboseError<&str>>(&line.text)
+            .finish()
+            .ok()
+            .filter(|(rest, parsed)| rest.trim().is_empty() && !parsed.name.as_str().is_empty());
+        let Some((_, parsed)) = parsed else {
+            self.warn(line.location(), WarningKind::DroppedLine);
+            return;
+        };
+        let mut parsed = parsed.into_owned();
+
+        if !line.text.contains(':') {
+            let name = parsed.name.as_str().to_owned();
+            self.warn(line.location(), WarningKind::MissingValue { name });
+        }
+        if needs_value_date(&parsed) {
+            parsed.params.push(Parameter {
+                key: "VALUE".into(),
+                val: Some("DATE".into()),
+                list: false,
+            });
+            let name = parsed.name.as_str().to_owned();
+            self.warn(line.location(), WarningKind::AddedValueDate { name });
+        }
+
+        match self.open.last_mut() {
+            Some((component, _)) => component.properties.push(parsed),
+            None => self.warn(line.location(), WarningKind::PropertyOutsideComponent),
+        }
+    }
+
+    fn close(&mut self, component: Component<'static>) {
+        match self.open.last_mut() {
+            Some((parent, _)) => parent.components.push(component),
+            None => self.roots.push(component),
+        }
+    }
+
+    fn finish(mut self) -> (Calendar<'static>, Vec<Warning>) {
+        while let Some((component, location)) = self.open.pop() {
+            let name = component.name.as_str().to_owned();
+            self.warn(location, WarningKind::ClosedImplicitly { name });
+            self.close(component);
+        }
+
+        let mut calendar = Calendar {
+            properties: Vec::new(),
+            components: Vec::new(),
+        };
+        for root in self.roots {
+            if root.name.as_str().eq_ignore_ascii_case("VCALENDAR") {
+                if calendar.properties.is_empty() {
+                    calendar.properties = root.properties;
+                }
+                calendar.components.extend(root.components);
+            } else {
+                calendar.components.push(root);
+            }
+        }
+        (calendar, self.warnings)
+    }
+}
+
+/// A date without `VALUE=DATE` where a `DATE-TIME` is expected.
+fn needs_value_date(property: &Property<'_>) -> bool {
+    DATE_TIME_PROPERTIES.contains(&property.name.as_str())
+        && !property
+            .params
+            .iter()
+            .any(|param| param.key.as_str().eq_ignore_ascii_case("VALUE"))
+        && property
+            .val
+            .as_str()
+            .split(',')
+            .all(|value| value.len() == 8 && value.bytes().all(|byte| byte.is_ascii_digit()))
+}
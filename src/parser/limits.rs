@@ -0,0 +1,238 @@
+use std::fmt;
+
+use super::{Location, ParseError, reader::Marker};
+
+/// Bounds on what the parser accepts, for reading untrusted input.
+///
+/// Every limit is checked before the content is handed to the parser,
+/// so neither deeply nested components nor huge numbers of small items can exhaust the stack or memory.
+///
+/// [`Limits::default()`] is meant for user uploads, [`Limits::none()`] accepts anything.
+///
+/// ```
+/// # use icalendar::parser::{Limit, Limits, ParseError, read_calendar_with_limits};
+/// let limits = Limits::default().max_depth(2);
+/// let input = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nBEGIN:VALARM\nEND:VALARM\nEND:VEVENT\nEND:VCALENDAR\n";
+/// let error = read_calendar_with_limits(input, &limits).unwrap_err();
+/// assert!(matches!(error, ParseError::LimitExceeded { limit: Limit::Depth(2), .. }));
+/// assert_eq!(
+///     error.to_string(),
+///     "line 3, column 1: components are nested deeper than 2 levels"
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limits {
+    input_size: usize,
+    depth: usize,
+    components: usize,
+    properties: usize,
+    parameters: usize,
+    line_length: usize,
+}
+
+impl Default for Limits {
+    /// 16 MiB of input, 16 levels of nesting, 100 000 components,
+    /// a million properties and parameters and content lines of up to 1 MiB.
+    fn default() -> Self {
+        Limits {
+            input_size: 16 << 20,
+            depth: 16,
+            components: 100_000,
+            properties: 1_000_000,
+            parameters: 1_000_000,
+            line_length: 1 << 20,
+        }
+    }
+}
+
+impl Limits {
+    /// No limits at all.
+    pub fn none() -> Self {
+        Limits {
+            input_size: usize::MAX,
+            depth: usize::MAX,
+            components: usize::MAX,
+            properties: usize::MAX,
+            parameters: usize::MAX,
+            line_length: usize::MAX,
+        }
+    }
+
+    /// Sets the maximum size of the input in bytes.
+    pub fn max_input_size(mut self, bytes: usize) -> Self {
+        self.input_size = bytes;
+        self
+    }
+
+    /// Sets how deep `BEGIN`/`END` may be nested, `VCALENDAR` counting as the first level.
+    pub fn max_depth(mut self, levels: usize) -> Self {
+        self.depth = levels;
+        self
+    }
+
+    /// Sets the maximum number of components in the input, `VCALENDAR` included.
+    pub fn max_components(mut self, components: usize) -> Self {
+        self.components = components;
+        self
+    }
+
+    /// Sets the maximum number of properties in the input.
+    pub fn max_properties(mut self, properties: usize) -> Self {
+        self.properties = properties;
+        self
+    }
+
+    /// Sets the maximum number of parameters of all properties together.
+    pub fn max_parameters(mut self, parameters: usize) -> Self {
+        self.parameters = parameters;
+        self
+    }
+
+    /// Sets the maximum length of an unfolded content line in bytes.
+    pub fn max_line_length(mut self, bytes: usize) -> Self {
+        self.line_length = bytes;
+        self
+    }
+
+    pub(crate) fn line_length(&self) -> usize {
+        self.line_length
+    }
+
+    pub(crate) fn check_input_size(&self, size: usize) -> Result<(), Limit> {
+        if size > self.input_size {
+            return Err(Limit::InputSize(self.input_size));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_line_length(&self, length: usize) -> Result<(), Limit> {
+        if length > self.line_length {
+            return Err(Limit::LineLength(self.line_length));
+        }
+        Ok(())
+    }
+
+    /// Checks unfolded `input` line by line, `locate` turns an offset in `input` into a [`Location`].
+    pub(crate) fn check(
+        &self,
+        input: &str,
+        locate: impl Fn(usize) -> Location,
+    ) -> Result<(), ParseError> {
+        self.check_input_size(input.len())
+            .map_err(|limit| ParseError::LimitExceeded {
+                location: None,
+                limit,
+            })?;
+        let mut usage = Usage::new(*self);
+        let mut offset = 0;
+        for line in input.split_inclusive('\n') {
+            usage
+                .line(line.trim_end_matches(['\r', '\n']))
+                .map_err(|limit| ParseError::LimitExceeded {
+                    location: Some(locate(offset)),
+                    limit,
+                })?;
+            offset += line.len();
+        }
+        Ok(())
+    }
+}
+
+/// A limit that was exceeded, holding the maximum that was configured.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Limit {
+    /// See [`Limits::max_input_size`].
+    InputSize(usize),
+    /// See [`Limits::max_depth`].
+    Depth(usize),
+    /// See [`Limits::max_components`].
+    Components(usize),
+    /// See [`Limits::max_properties`].
+    Properties(usize),
+    /// See [`Limits::max_parameters`].
+    Parameters(usize),
+    /// See [`Limits::max_line_length`].
+    LineLength(usize),
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Limit::InputSize(max) => write!(f, "input is larger than {max} bytes"),
+            Limit::Depth(max) => write!(f, "components are nested deeper than {max} levels"),
+            Limit::Components(max) => write!(f, "more than {max} components"),
+            Limit::Properties(max) => write!(f, "more than {max} properties"),
+            Limit::Parameters(max) => write!(f, "more than {max} parameters"),
+            Limit::LineLength(max) => write!(f, "line is longer than {max} bytes"),
+        }
+    }
+}
+
+/// Counts what has been read so far against [`Limits`].
+#[derive(Debug)]
+pub(crate) struct Usage {
+    limits: Limits,
+    depth: usize,
+    components: usize,
+    properties: usize,
+    parameters: usize,
+}
+
+impl Usage {
+    pub fn new(limits: Limits) -> Self {
+        Usage {
+            limits,
+            depth: 0,
+            components: 0,
+            properties: 0,
+            parameters: 0,
+        }
+    }
+
+    /// Accounts for an unfolded content line.
+    pub fn line(&mut self, line: &str) -> Result<(), Limit> {
+        let limits = &self.limits;
+        limits.check_line_length(line.len())?;
+        match Marker::of(line) {
+            Some(Marker::Begin(_)) => {
+                self.depth += 1;
+                self.components += 1;
+                if self.depth > limits.depth {
+                    return Err(Limit::Depth(limits.depth));
+                }
+                if self.components > limits.components {
+                    return Err(Limit::Components(limits.components));
+                }
+            }
+            Some(Marker::End(_)) => self.depth = self.depth.saturating_sub(1),
+            None if line.trim().is_empty() => {}
+            None => {
+                self.properties += 1;
+                self.parameters += count_parameters(line);
+                if self.properties > limits.properties {
+                    return Err(Limit::Properties(limits.properties));
+                }
+                if self.parameters > limits.parameters {
+                    return Err(Limit::Parameters(limits.parameters));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The number of `;` before the value, quoted ones don't count.
+fn count_parameters(line: &str) -> usize {
+    let mut quoted = false;
+    let mut count = 0;
+    for c in line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ':' if !quoted => break,
+            ';' if !quoted => count += 1,
+            _ => {}
+        }
+    }
+    count
+}
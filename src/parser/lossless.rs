@@ -0,0 +1,166 @@
+use nom::Finish;
+use nom_language::error::VerboseError;
+
+use super::{
+    Component, ParseError, Property,
+    components::{components, into_calendar_component},
+    reader::{Lines, Marker},
+    unfold,
+};
+use crate::{
+    Calendar,
+    components::{InnerComponent, Other},
+};
+
+impl Calendar {
+    /// Parses a calendar like [`str::parse`], but so that writing it again changes as little as possible.
+    ///
+    /// Properties keep the order they were read in, including repetitions of properties that may occur only once:
+    /// the first one is what [`Component::properties`](crate::Component::properties) returns,
+    /// the others are kept with the [`multi_properties`](crate::Component::multi_properties).
+    /// A property is written exactly as it was read, folding and parameters included, for as long as it is unchanged.
+    /// Changed properties are written in their original place, added ones after all others,
+    /// and no `DTSTAMP` or `UID` is generated for components that were read.
+    ///
+    /// ```
+    /// # use icalendar::*;
+    /// let input = "BEGIN:VCALENDAR\r
+    /// VERSION:2.0\r
+    /// PRODID:lossless\r
+    /// BEGIN:VEVENT\r
+    /// UID:lossless\r
+    /// SUMMARY:Meeting\r
+    /// DTSTAMP:20240301T100000Z\r
+    /// LOCATION;X-ROOM=\"4\":Room 4\\, second floor\r
+    /// SUMMARY:Repeated\r
+    /// DESCRIPTION:folded in an\r
+    ///   unusual place\r
+    /// END:VEVENT\r
+    /// END:VCALENDAR\r
+    /// ";
+    /// let mut calendar = Calendar::from_str_lossless(input).unwrap();
+    /// assert_eq!(calendar.to_string(), input);
+    ///
+    /// if let CalendarComponent::Event(event) = &mut calendar.components[0] {
+    ///     event.summary("Renamed");
+    /// }
+    /// assert_eq!(
+    ///     calendar.to_string(),
+    ///     input.replace("SUMMARY:Meeting", "SUMMARY:Renamed")
+    /// );
+    /// ```
+    pub fn from_str_lossless(s: &str) -> Result<Self, ParseError> {
+        let unfolded = unfold(s);
+        let (_, mut roots) = components(&unfolded)
+            .finish()
+            .map_err(|e: VerboseError<&str>| ParseError::in_folded(s, 1, &unfolded, &e))?;
+        let mut raw = raw_lines(s).into_iter();
+
+        // like `into_calendar`, which ignores anything after a `VCALENDAR` root
+        if roots.first().is_some_and(|root| root.name == "VCALENDAR") {
+            let root = roots.swap_remove(0);
+            let properties = read_properties(root.properties, raw.next());
+            let components = root
+                .components
+                .into_iter()
+                .map(|component| {
+                    let name = component.name.to_string();
+                    into_calendar_component(name, read_component(component, &mut raw))
+                })
+                .collect();
+            Ok(Calendar {
+                properties,
+                components,
+            })
+        } else {
+            let components = roots
+                .into_iter()
+                .map(|component| {
+                    let name = component.name.to_string();
+                    into_calendar_component(name, read_component(component, &mut raw))
+                })
+                .collect();
+            Ok(Calendar {
+                properties: Vec::new(),
+                components,
+            })
+        }
+    }
+}
+
+/// The folded property lines of each component, in the order the components begin.
+fn raw_lines(input: &str) -> Vec<Vec<String>> {
+    let mut lines = Lines::new(input.as_bytes());
+    let mut read: Vec<Vec<String>> = Vec::new();
+    let mut open = Vec::new();
+    // a `&str` can't contain invalid UTF-8 and reading from a slice can't fail
+    while let Ok(Some(line)) = lines.next_logical() {
+        match Marker::of(&line.text) {
+            _ if line.text.trim().is_empty() => {}
+            Some(Marker::Begin(_)) => {
+                open.push(read.len());
+                read.push(Vec::new());
+            }
+            Some(Marker::End(_)) => {
+                open.pop();
+            }
+            None => {
+                if let Some(properties) = open.last().and_then(|index| read.get_mut(*index)) {
+                    properties.push(line.raw);
+                }
+            }
+        }
+    }
+    read
+}
+
+/// Converts `parsed`, remembering the `raw` line of each unless they don't match up.
+fn read_properties(parsed: Vec<Property<'_>>, raw: Option<Vec<String>>) -> Vec<crate::Property> {
+    let raw = raw
+        .filter(|raw| raw.len() == parsed.len())
+        .unwrap_or_default();
+    let mut raw = raw.into_iter();
+    parsed
+        .into_iter()
+        .enumerate()
+        .map(|(position, parsed)| {
+            let mut property = crate::Property::from(parsed);
+            if let Some(raw) = raw.next() {
+                property.read_from(position, raw);
+            }
+            property
+        })
+        .collect()
+}
+
+/// Converts `component` and its children, which take their raw lines from `raw` in the order they begin.
+fn read_component(
+    component: Component<'_>,
+    raw: &mut impl Iterator<Item = Vec<String>>,
+) -> InnerComponent {
+    let multi = component
+        .properties
+        .iter()
+        .map(Property::is_multi_property)
+        .collect::<Vec<_>>();
+    let mut inner = InnerComponent::default();
+    for (property, multi) in read_properties(component.properties, raw.next())
+        .into_iter()
+        .zip(multi)
+    {
+        if multi || inner.properties.contains_key(property.key()) {
+            inner.insert_multi(property);
+        } else {
+            inner.properties.insert(property.key().to_owned(), property);
+        }
+    }
+    inner.components = component
+        .components
+        .into_iter()
+        .map(|child| {
+            let name = child.name.to_string();
+            Other::from((name, read_component(child, raw)))
+        })
+        .collect();
+    inner
+}
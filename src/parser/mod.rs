@@ -9,7 +9,12 @@
 //! In this module you find the following functions to parser iCalendar document.
 //! [`unfold()`] will unfold the iCal content and turn it into the nice machine-readable format it ought to be.
 //! [`read_calendar_simple()`] returns a Vector of [`Component`]s
-//! [`read_calendar()`] does the same thing but produces nicer parsing errors with line numbers (referencing the normalized content).
+//! [`read_calendar()`] does the same thing but produces a [`ParseError`] that tells what went wrong where (referencing the normalized content).
+//! [`CalendarReader`] unfolds on the fly while reading from a [`std::io::BufRead`] and yields one component at a time.
+//! [`read_calendar_with_limits()`] and [`CalendarReader::with_limits`] refuse input that exceeds the given [`Limits`].
+//! [`read_calendar_bytes()`] takes raw bytes, unfolds them before decoding and repairs invalid UTF-8.
+//! [`read_calendar_lenient()`] takes folded content, repairs or skips what it can't read and reports each such [`Warning`].
+//! [`crate::Calendar::from_str_lossless()`] remembers what it read, so that writing the calendar again changes as little as possible.
 //!
 //! You don't have to use `normalize()` on your document if your calendar does not obey the folding rules specified in [rfc5545 3.1].
 //! If it unexpectedly does, the errors might be a tad confusing.
@@ -25,21 +30,32 @@
 #![allow(missing_docs)]
 use nom::Finish;
 
+mod bytes;
 mod calendar;
 pub(crate) mod components;
+mod error;
+mod lenient;
+mod limits;
+mod lossless;
 mod parameters;
 mod parsed_string;
 mod properties;
+mod reader;
 #[cfg(test)]
 mod tests;
 mod utils;
 
+pub use bytes::read_calendar_bytes;
 pub use calendar::Calendar;
 pub use components::Component;
-use nom_language::error::{VerboseError, convert_error};
+pub use error::{Location, ParseError};
+pub use lenient::{Warning, WarningKind, read_calendar_lenient};
+pub use limits::{Limit, Limits};
+use nom_language::error::VerboseError;
 pub use parameters::Parameter;
 pub use parsed_string::ParseString;
 pub use properties::Property;
+pub use reader::CalendarReader;
 
 use components::*;
 
@@ -52,34 +68,50 @@ pub fn read_calendar_simple(input: &str) -> Result<Vec<Component<'_>>, nom::erro
     components(input).finish().map(|(_, components)| components)
 }
 
-/// Parse iCalendar file content into an array of [`Component`]s
+/// Parse iCalendar file content into a [`Calendar`]
 ///
-/// This version produces nice and readable errors with line numbers thanks the the awesomeness of [`nom`].
-/// Line numbers are in regard to the normalized/unfolded version of the input, so better keep those around for reference.
+/// This version produces a [`ParseError`] with the line and column of the problem.
+/// Locations are in regard to `input`, which is usually the normalized/unfolded version of the original.
+/// Use [`str::parse`] or [`CalendarReader`] to locate problems in content that is still folded.
 ///
-pub fn read_calendar(input: &str) -> Result<Calendar<'_>, String> {
+pub fn read_calendar(input: &str) -> Result<Calendar<'_>, ParseError> {
     components(input)
         .finish()
-        .map(|(_, mut components)| {
-            let root_is_calendar = components
-                .first()
-                .map(|first_root| first_root.name == "VCALENDAR")
-                .unwrap_or(false);
+        .map(|(_, components)| into_calendar(components))
+        .map_err(|e: VerboseError<&str>| ParseError::in_text(input, &e))
+}
+
+/// Parse iCalendar file content into a [`Calendar`], refusing input that exceeds `limits`.
+///
+/// Apart from the limits this behaves like [`read_calendar`].
+/// Use [`CalendarReader::with_limits`] to enforce limits while reading from a stream.
+pub fn read_calendar_with_limits<'a>(
+    input: &'a str,
+    limits: &Limits,
+) -> Result<Calendar<'a>, ParseError> {
+    limits.check(input, |offset| Location::find(input, 1, offset, false))?;
+    read_calendar(input)
+}
+
+/// Treats a single `VCALENDAR` root as the calendar, anything else as its components.
+pub(crate) fn into_calendar(mut components: Vec<Component<'_>>) -> Calendar<'_> {
+    let root_is_calendar = components
+        .first()
+        .map(|first_root| first_root.name == "VCALENDAR")
+        .unwrap_or(false);
 
-            if root_is_calendar {
-                let root = components.swap_remove(0);
-                Calendar {
-                    properties: root.properties,
-                    components: root.components,
-                }
-            } else {
-                Calendar {
-                    components,
-                    properties: Vec::new(),
-                }
-            }
-        })
-        .map_err(|e: VerboseError<&str>| format!("error: {}", convert_error(input, e.clone())))
+    if root_is_calendar {
+        let root = components.swap_remove(0);
+        Calendar {
+            properties: root.properties,
+            components: root.components,
+        }
+    } else {
+        Calendar {
+            components,
+            properties: Vec::new(),
+        }
+    }
 }
 
 #[test]
@@ -89,12 +121,12 @@ fn begin_crash() {
 
 /// Parse iCalendar file content into an array of [`Component`]s
 ///
-/// This version produces nice and readable errors with line numbers thanks the the awesomeness of [`nom`].
-/// Line numbers are in regard to the normalized/unfolded version of the input, so better keep those around for reference.
+/// This version produces a [`ParseError`] with the line and column of the problem.
+/// Locations are in regard to `input`, which is usually the normalized/unfolded version of the original.
 ///
-pub fn read_components(input: &str) -> Result<Vec<Component<'_>>, String> {
+pub fn read_components(input: &str) -> Result<Vec<Component<'_>>, ParseError> {
     components(input)
         .finish()
         .map(|(_, components)| components)
-        .map_err(|e: VerboseError<&str>| format!("error: {}", convert_error(input, e.clone())))
+        .map_err(|e: VerboseError<&str>| ParseError::in_text(input, &e))
 }
@@ -1,11 +1,11 @@
 use nom::{
     Finish, IResult, Parser,
     branch::alt,
-    bytes::complete::{is_not, tag, take_till1},
+    bytes::complete::{is_not, tag, take_till},
     character::complete::space0,
-    combinator::{eof, opt},
+    combinator::{consumed, opt},
     error::{ContextError, ParseError},
-    multi::many0,
+    multi::{many0, separated_list1},
     sequence::{delimited, preceded, separated_pair, tuple},
 };
 
@@ -14,6 +14,7 @@ use nom::error::ErrorKind;
 use nom_language::error::{VerboseError, convert_error};
 
 use super::{parsed_string::ParseString, utils::valid_key_sequence_cow};
+use crate::properties::is_single_valued;
 
 /// Zero-copy version of [`crate::properties::Parameter`]
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -21,6 +22,12 @@ use super::{parsed_string::ParseString, utils::valid_key_sequence_cow};
 pub struct Parameter<'a> {
     pub key: ParseString<'a>,
     pub val: Option<ParseString<'a>>,
+    /// Whether `val` is a list as it is written, e.g. `"mailto:a@example.com","mailto:b@example.com"`.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "std::ops::Not::not")
+    )]
+    pub list: bool,
 }
 
 impl<'a> Parameter<'a> {
@@ -28,6 +35,27 @@ impl<'a> Parameter<'a> {
         Parameter {
             key: key.into(),
             val: val.map(Into::into),
+            list: false,
+        }
+    }
+
+    /// A parameter holding a list as it is written.
+    pub fn list_ref(key: &'a str, val: &'a str) -> Parameter<'a> {
+        Parameter {
+            key: key.into(),
+            val: Some(val.into()),
+            list: true,
+        }
+    }
+}
+
+impl Parameter<'_> {
+    /// Copies borrowed content so the parameter outlives the input.
+    pub fn into_owned(self) -> Parameter<'static> {
+        Parameter {
+            key: self.key.into_owned(),
+            val: self.val.map(ParseString::into_owned),
+            list: self.list,
         }
     }
 }
@@ -45,10 +73,12 @@ impl<'i> TryFrom<&'i str> for Parameter<'i> {
 
 impl From<Parameter<'_>> for crate::properties::Parameter {
     fn from(parameter: Parameter<'_>) -> crate::properties::Parameter {
-        crate::properties::Parameter::new(
-            parameter.key.as_ref(),
-            parameter.val.as_ref().map(AsRef::as_ref).unwrap_or(""),
-        )
+        let val = parameter.val.as_ref().map(AsRef::as_ref).unwrap_or("");
+        if parameter.list {
+            crate::properties::Parameter::written_list(parameter.key.as_ref(), val)
+        } else {
+            crate::properties::Parameter::new(parameter.key.as_ref(), val)
+        }
     }
 }
 
@@ -57,6 +87,7 @@ impl From<crate::properties::Parameter> for Parameter<'static> {
         Parameter {
             key: parameter.key().to_owned().into(),
             val: Some(parameter.value().to_owned().into()),
+            list: parameter.is_list(),
         }
     }
 }
@@ -95,6 +126,7 @@ fn test_parameter() {
         Parameter {
             key: "KEY".into(),
             val: None,
+            list: false,
         }
     );
 
@@ -105,6 +137,44 @@ fn test_parameter() {
     );
 }
 
+#[test]
+fn test_parameter_caret_encoding() {
+    assert_parser!(
+        parameter,
+        ";CN=\"^'Bob^' ^^ Co^nLtd ^x\"",
+        Parameter::new_ref("CN", Some("\"Bob\" ^ Co\nLtd ^x"))
+    );
+
+    // lists are kept as they are written, their elements are decoded one by one
+    assert_parser!(
+        parameter,
+        ";X-LIST=\"a^'\",b",
+        Parameter::list_ref("X-LIST", "\"a^'\",b")
+    );
+    let list = crate::Parameter::from(parameter::<()>(";X-LIST=\"a^'\",b").unwrap().1);
+    assert_eq!(list.values(), ["a\"", "b"]);
+
+    // a single quoted value is no list, even with a comma in it
+    assert_parser!(
+        parameter,
+        ";X-NOTE=\"Smith, John\"",
+        Parameter::new_ref("X-NOTE", Some("Smith, John"))
+    );
+    assert_parser!(
+        parameter,
+        ";X-ADDRESS=\"Apple Inc.^n1 Infinite Loop^nCupertino, CA 95014\"",
+        Parameter::new_ref(
+            "X-ADDRESS",
+            Some("Apple Inc.\n1 Infinite Loop\nCupertino, CA 95014")
+        )
+    );
+    assert_parser!(
+        parameter,
+        ";CN=\"Smith, John\"",
+        Parameter::new_ref("CN", Some("Smith, John"))
+    );
+}
+
 #[test]
 fn test_parameter_with_dash() {
     assert_parser!(
@@ -119,13 +189,6 @@ fn test_quirky_parameter() {
     assert_parser!(parameter, ";KEY=", Parameter::new_ref("KEY", None));
 }
 
-fn remove_empty_string(input: Option<&str>) -> Option<&str> {
-    if let Some(input) = input {
-        return if input.is_empty() { None } else { Some(input) };
-    }
-    None
-}
-
 fn remove_empty_string_parsed(input: Option<ParseString<'_>>) -> Option<ParseString<'_>> {
     if let Some(input) = input {
         return if input.as_ref().is_empty() {
@@ -137,6 +200,49 @@ fn remove_empty_string_parsed(input: Option<ParseString<'_>>) -> Option<ParseStr
     None
 }
 
+/// A parameter value, possibly a comma separated list of values that may each be quoted,
+/// as it is written and split into its values.
+fn param_value<'i, E>(input: &'i str) -> IResult<&'i str, (&'i str, Vec<&'i str>), E>
+where
+    E: ParseError<&'i str> + ContextError<&'i str>,
+{
+    consumed(separated_list1(
+        tag(","),
+        alt((
+            delimited(tag("\""), is_not("\""), tag("\"")),
+            take_till(|x| x == ',' || x == ';' || x == ':'),
+        )),
+    ))
+    .parse(input)
+}
+
+/// The parameter named `key` with the value `written`.
+///
+/// A single value is stored without its quotes and with its [RFC 6868](https://datatracker.ietf.org/doc/html/rfc6868)
+/// escapes decoded, a list is stored as it is written, e.g. `"mailto:a@example.com","mailto:b@example.com"`.
+/// Parameters that RFC 5545 defines with a single value, like `CN`, are never lists.
+fn decoded_parameter<'i>(
+    key: ParseString<'i>,
+    written: Option<(&'i str, Vec<&'i str>)>,
+) -> Parameter<'i> {
+    let (val, list) = match written {
+        None => (None, false),
+        Some((_, values)) if values.len() == 1 => {
+            let single = values.into_iter().next().map(ParseString::from);
+            (single.map(ParseString::decode_caret), false)
+        }
+        Some((written, _)) if is_single_valued(key.as_ref()) => {
+            (Some(ParseString::from(written).decode_caret()), false)
+        }
+        Some((written, _)) => (Some(ParseString::from(written)), true),
+    };
+    Parameter {
+        key,
+        val: remove_empty_string_parsed(val),
+        list,
+    }
+}
+
 fn parameter<'i, E>(input: &'i str) -> IResult<&'i str, Parameter<'i>, E>
 where
     E: ParseError<&'i str> + ContextError<&'i str>,
@@ -153,18 +259,10 @@ where
         separated_pair(
             valid_key_sequence_cow, //key
             tag("="),
-            opt(alt((
-                eof,
-                delimited(tag("\""), is_not("\""), tag("\"")),
-                take_till1(|x| x == ';' || x == ':'),
-            )))
-            .map(remove_empty_string),
+            opt(param_value),
         ),
     )
-    .map(|(key, val)| Parameter {
-        key,
-        val: val.map(ParseString::from),
-    })
+    .map(|(key, written)| decoded_parameter(key, written))
     .parse(input)
 }
 
@@ -177,18 +275,9 @@ where
             tuple((tag(";"), space0)),
             valid_key_sequence_cow, //key
         ),
-        opt(preceded(
-            tag("="),
-            alt((
-                eof,
-                delimited(tag("\""), is_not("\""), tag("\"")),
-                take_till1(|x| x == ';' || x == ':'),
-            ))
-            .map(ParseString::from),
-        ))
-        .map(remove_empty_string_parsed),
+        opt(preceded(tag("="), param_value)),
     ))
-    .map(|(key, val)| Parameter { key, val })
+    .map(|(key, written)| decoded_parameter(key, written))
     .parse(input)
 }
 
@@ -223,6 +312,18 @@ pub fn parse_parameter_list() {
         ";TEXT=\"quoted text with \\;\"",
         vec![Parameter::new_ref("TEXT", Some("quoted text with \\;")),]
     );
+    assert_parser!(
+        parameters,
+        ";MEMBER=\"mailto:a@example.com\",\"mailto:b@example.com\";X-TAG=one,two;X-TAG=three",
+        vec![
+            Parameter::list_ref(
+                "MEMBER",
+                "\"mailto:a@example.com\",\"mailto:b@example.com\""
+            ),
+            Parameter::list_ref("X-TAG", "one,two"),
+            Parameter::new_ref("X-TAG", Some("three")),
+        ]
+    );
 }
 
 pub fn parameters<'i, E>(input: &'i str) -> IResult<&'i str, Vec<Parameter<'i>>, E>
@@ -35,6 +35,15 @@ impl<'a> ParseString<'a> {
         }
     }
 
+    /// Decodes the [RFC 6868](https://datatracker.ietf.org/doc/html/rfc6868) escapes of a parameter value:
+    /// `^n` is a line break, `^'` a double quote and `^^` a caret, any other `^` is kept.
+    pub fn decode_caret(self) -> ParseString<'a> {
+        match crate::properties::decode_caret(&self.0) {
+            Cow::Owned(decoded) => ParseString(Cow::Owned(decoded)),
+            Cow::Borrowed(_) => self,
+        }
+    }
+
     /// Reverses `Property::escape_text`.
     ///
     /// Single left-to-right pass so it is the exact inverse of the escaper:
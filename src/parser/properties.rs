@@ -3,7 +3,11 @@ use std::{
     str::FromStr,
 };
 
-use crate::{parser::utils::valid_key_sequence_cow, properties::fold_line, value_types::ValueType};
+use crate::{
+    parser::utils::valid_key_sequence_cow,
+    properties::{MULTIS, encode_param_value, fold_line},
+    value_types::ValueType,
+};
 
 use super::{
     parameters::{Parameter, parameters},
@@ -24,25 +28,6 @@ use nom::{
 use nom::error::ErrorKind;
 use nom_language::error::{VerboseError, convert_error};
 
-/// [RFC-5545](https://datatracker.ietf.org/doc/html/rfc5545) states that the following
-/// "MAY occur more than once" in a VEVENT, VTODO, VJOURNAL, and VFREEBUSY.
-/// Note: A VJOURNAL can also contain multiple DECRIPTION but this is not covered here.
-const MULTIS: [&str; 13] = [
-    "ATTACH",
-    "ATTENDEE",
-    "CATEGORIES",
-    "COMMENT",
-    "CONTACT",
-    "EXDATE",
-    "FREEBUSY",
-    "IANA-PROP",
-    "RDATE",
-    "RELATED",
-    "RESOURCES",
-    "RSTATUS",
-    "X-PROP",
-];
-
 /// Zero-copy version of [`crate::properties::Property`]
 #[derive(PartialEq, Eq, Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -63,14 +48,24 @@ impl<'a> Property<'a> {
 }
 
 impl Property<'_> {
+    /// Copies borrowed content so the property outlives the input.
+    pub fn into_owned(self) -> Property<'static> {
+        Property {
+            name: self.name.into_owned(),
+            val: self.val.into_owned(),
+            params: self.params.into_iter().map(Parameter::into_owned).collect(),
+        }
+    }
+
     pub(crate) fn fmt_write<W: Write>(&self, out: &mut W) -> Result<(), fmt::Error> {
         // A nice starting capacity for the majority of content lines
         let mut line = String::with_capacity(150);
 
         write!(line, "{}", self.name.as_str())?;
-        for Parameter { key, val } in &self.params {
+        for Parameter { key, val, list } in &self.params {
             if let Some(val) = val {
-                write!(line, ";{}={}", key.as_str(), val.as_str())?;
+                let val = encode_param_value(val.as_str(), *list);
+                write!(line, ";{}={}", key.as_str(), val)?;
             } else {
                 write!(line, ";{}", key.as_str())?;
             }
@@ -107,11 +102,8 @@ impl From<Property<'_>> for crate::Property {
         Self {
             key: parsed.name.as_ref().to_owned(),
             val: parsed.val.as_ref().to_owned(),
-            params: parsed
-                .params
-                .into_iter()
-                .map(|p| (p.key.as_ref().to_owned(), p.into()))
-                .collect(),
+            params: parsed.params.into_iter().map(Into::into).collect(),
+            source: None,
         }
     }
 }
@@ -121,12 +113,7 @@ impl From<crate::Property> for Property<'static> {
         Property {
             name: property.key().to_owned().into(),
             val: property.value().to_owned().into(),
-            params: property
-                .params()
-                .values()
-                .cloned()
-                .map(Into::into)
-                .collect(),
+            params: property.params().iter().cloned().map(Into::into).collect(),
         }
     }
 }
@@ -339,7 +326,7 @@ fn parse_properties_from_rfc() {
         Property {
             name: "home.tel".into(),
             val: "+49 3581 123456".into(),
-            params: vec![Parameter::new_ref("type", Some("fax,voice,msg"),)]
+            params: vec![Parameter::list_ref("type", "fax,voice,msg")]
         }
     );
 
@@ -404,6 +391,7 @@ fn parse_property_with_colon() {
         params: vec![Parameter {
             key: "RELTYPE".into(),
             val: None,
+            list: false,
         }],
     };
 
@@ -0,0 +1,365 @@
+use std::io::{BufRead, Read};
+
+use nom::Finish;
+use nom_language::error::VerboseError;
+
+use super::{
+    Limits, ParseError,
+    components::component,
+    error::{Location, strip_marker},
+    limits::Usage,
+    properties::property,
+};
+use crate::{Property, calendar::CalendarComponent};
+
+/// Reads components one at a time from a [`BufRead`],
+/// unfolding lines as they come in.
+///
+/// Only the component that is currently being read is held in memory,
+/// which makes this suitable for archives too large for [`read_calendar`](super::read_calendar).
+/// The properties of the surrounding `VCALENDAR` are collected on the way
+/// and are available through [`CalendarReader::properties`].
+///
+/// A component with a syntax error is reported and skipped, reading continues after it.
+/// Once the input fails, exceeds the [`Limits`] or ends in the middle of a component no further items are produced.
+///
+/// ```
+/// # use icalendar::{*, parser::CalendarReader};
+/// let input = "BEGIN:VCALENDAR\r
+/// VERSION:2.0\r
+/// BEGIN:VEVENT\r
+/// UID:first\r
+/// SUMMARY:a summary that is long enough to be folded\r
+///  onto a second line\r
+/// END:VEVENT\r
+/// BEGIN:VTODO\r
+/// UID:second\r
+/// END:VTODO\r
+/// END:VCALENDAR\r
+/// ";
+///
+/// let mut reader = CalendarReader::new(input.as_bytes());
+/// let event = reader.next().unwrap().unwrap();
+/// assert_eq!(
+///     event.as_event().unwrap().get_summary(),
+///     Some("a summary that is long enough to be foldedonto a second line")
+/// );
+/// assert_eq!(reader.properties()[0].value(), "2.0");
+/// assert!(reader.next().unwrap().unwrap().as_todo().is_some());
+/// assert!(reader.next().is_none());
+/// ```
+#[derive(Debug)]
+pub struct CalendarReader<R> {
+    lines: Lines<R>,
+    usage: Usage,
+    properties: Vec<Property>,
+    /// The `BEGIN:VCALENDAR` that is currently open.
+    calendar: Option<Location>,
+    finished: bool,
+}
+
+/// Unfolds content lines while reading them.
+#[derive(Debug)]
+pub(crate) struct Lines<R> {
+    input: R,
+    limits: Limits,
+    /// Number of bytes read so far.
+    size: usize,
+    /// Number of lines read so far.
+    line: usize,
+    /// A line read ahead of time to see whether it continues the previous one.
+    peeked: Option<(usize, String)>,
+}
+
+/// An unfolded content line.
+pub(crate) struct Line {
+    /// Where the line starts in the input.
+    pub number: usize,
+    /// The unfolded content.
+    pub text: String,
+    /// The folded content, physical lines separated by `\n`.
+    pub raw: String,
+}
+
+impl Line {
+    /// The start of the line.
+    pub fn location(&self) -> Location {
+        Location::find(&self.raw, self.number, 0, true)
+    }
+}
+
+pub(crate) enum Marker<'a> {
+    Begin(&'a str),
+    End(&'a str),
+}
+
+impl<'a> Marker<'a> {
+    pub fn of(line: &'a str) -> Option<Self> {
+        strip_marker(line, "BEGIN:")
+            .map(Marker::Begin)
+            .or_else(|| strip_marker(line, "END:").map(Marker::End))
+    }
+}
+
+impl<R: BufRead> Lines<R> {
+    pub fn new(input: R) -> Self {
+        Self::with_limits(input, Limits::none())
+    }
+
+    /// Enforces the input size and line length of `limits`.
+    pub fn with_limits(input: R, limits: Limits) -> Self {
+        Lines {
+            input,
+            limits,
+            size: 0,
+            line: 0,
+            peeked: None,
+        }
+    }
+
+    fn next_physical(&mut self) -> Result<Option<(usize, String)>, ParseError> {
+        if let Some(peeked) = self.peeked.take() {
+            return Ok(Some(peeked));
+        }
+        let mut bytes = Vec::new();
+        // never buffer more than the longest allowed line and its line break
+        let max = self.limits.line_length().saturating_add(2);
+        let read = (&mut self.input)
+            .take(u64::try_from(max).unwrap_or(u64::MAX))
+            .read_until(b'\n', &mut bytes)?;
+        if read == 0 {
+            return Ok(None);
+        }
+        self.line += 1;
+        self.size = self.size.saturating_add(read);
+        self.limits
+            .check_input_size(self.size)
+            .map_err(|limit| ParseError::LimitExceeded {
+                location: None,
+                limit,
+            })?;
+        if bytes.last() == Some(&b'\n') {
+            bytes.pop();
+            if bytes.last() == Some(&b'\r') {
+                bytes.pop();
+            }
+        }
+        self.limits
+            .check_line_length(bytes.len())
+            .map_err(|limit| ParseError::LimitExceeded {
+                location: Some(Location {
+                    line: self.line,
+                    column: 1,
+                    snippet: String::from_utf8_lossy(&bytes).into_owned(),
+                }),
+                limit,
+            })?;
+        String::from_utf8(bytes)
+            .map(|line| Some((self.line, line)))
+            .map_err(|error| {
+                let valid = error.utf8_error().valid_up_to();
+                let snippet = String::from_utf8_lossy(error.as_bytes()).into_owned();
+                let column = error
+                    .as_bytes()
+                    .get(..valid)
+                    .map_or(0, |valid| String::from_utf8_lossy(valid).chars().count());
+                ParseError::InvalidUtf8 {
+                    location: Location {
+                        line: self.line,
+                        column: column + 1,
+                        snippet,
+                    },
+                }
+            })
+    }
+
+    /// The next unfolded content line.
+    pub fn next_logical(&mut self) -> Result<Option<Line>, ParseError> {
+        let Some((number, text)) = self.next_physical()? else {
+            return Ok(None);
+        };
+        let mut line = Line {
+            number,
+            raw: text.clone(),
+            text,
+        };
+        loop {
+            match self.next_physical()? {
+                Some((_, next)) if next.starts_with([' ', '\t']) => {
+                    let mut chars = next.chars();
+                    chars.next();
+                    line.text.push_str(chars.as_str());
+                    line.raw.push('\n');
+                    line.raw.push_str(&next);
+                    self.limits
+                        .check_line_length(line.text.len())
+                        .map_err(|limit| ParseError::LimitExceeded {
+                            location: Some(line.location()),
+                            limit,
+                        })?;
+                }
+                next => {
+                    self.peeked = next;
+                    return Ok(Some(line));
+                }
+            }
+        }
+    }
+}
+
+impl<R: BufRead> CalendarReader<R> {
+    /// Reads from `input`.
+    pub fn new(input: R) -> Self {
+        Self::with_limits(input, Limits::none())
+    }
+
+    /// Reads from `input`, failing with [`ParseError::LimitExceeded`] once it exceeds `limits`.
+    ///
+    /// ```
+    /// # use icalendar::parser::{CalendarReader, Limit, Limits, ParseError};
+    /// let input = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nUID:1\nEND:VEVENT\nBEGIN:VEVENT\nUID:2\nEND:VEVENT\nEND:VCALENDAR\n";
+    /// let mut reader = CalendarReader::with_limits(input.as_bytes(), Limits::default().max_components(2));
+    /// assert!(reader.next().unwrap().is_ok());
+    /// assert!(matches!(
+    ///     reader.next().unwrap(),
+    ///     Err(ParseError::LimitExceeded { limit: Limit::Components(2), .. })
+    /// ));
+    /// assert!(reader.next().is_none());
+    /// ```
+    pub fn with_limits(input: R, limits: Limits) -> Self {
+        CalendarReader {
+            lines: Lines::with_limits(input, limits),
+            usage: Usage::new(limits),
+            properties: Vec::new(),
+            calendar: None,
+            finished: false,
+        }
+    }
+
+    /// The properties of the `VCALENDAR` read so far.
+    ///
+    /// These usually precede all components,
+    /// so they are complete once the first component has been read.
+    pub fn properties(&self) -> &[Property] {
+        &self.properties
+    }
+
+    /// The next unfolded content line, accounted for in the limits.
+    fn next_line(&mut self) -> Result<Option<Line>, ParseError> {
+        let line = self.lines.next_logical()?;
+        if let Some(line) = &line {
+            self.usage
+                .line(&line.text)
+                .map_err(|limit| ParseError::LimitExceeded {
+                    location: Some(line.location()),
+                    limit,
+                })?;
+        }
+        Ok(line)
+    }
+
+    /// Collects the lines up to the `END` matching the `BEGIN` line `begin` and parses them.
+    fn read_component(
+        &mut self,
+        name: String,
+        begin: Line,
+    ) -> Result<CalendarComponent, ParseError> {
+        let number = begin.number;
+        let location = begin.location();
+        let Line {
+            mut text, mut raw, ..
+        } = begin;
+        text.push('\n');
+        raw.push('\n');
+        let mut depth = 1_usize;
+
+        while depth > 0 {
+            let Some(line) = self.next_line()? else {
+                return Err(ParseError::Unterminated { location, name });
+            };
+            match Marker::of(&line.text) {
+                Some(Marker::Begin(_)) => depth += 1,
+                Some(Marker::End(_)) => depth -= 1,
+                None => {}
+            }
+            text.push_str(&line.text);
+            text.push('\n');
+            raw.push_str(&line.raw);
+            raw.push('\n');
+        }
+
+        component::<VerboseError<&str>>(&text)
+            .finish()
+            .map(|(_, component)| component.into())
+            .map_err(|error| ParseError::in_folded(&raw, number, &text, &error))
+    }
+
+    fn read_next(&mut self) -> Result<Option<CalendarComponent>, ParseError> {
+        while let Some(line) = self.next_line()? {
+            match Marker::of(&line.text) {
+                _ if line.text.trim().is_empty() => {}
+                Some(Marker::Begin(name))
+                    if self.calendar.is_none() && name.eq_ignore_ascii_case("VCALENDAR") =>
+                {
+                    self.calendar = Some(line.location());
+                    self.properties.clear();
+                }
+                Some(Marker::End(name))
+                    if self.calendar.is_some() && name.eq_ignore_ascii_case("VCALENDAR") =>
+                {
+                    self.calendar = None;
+                }
+                Some(Marker::Begin(name)) => {
+                    let name = name.to_owned();
+                    return self.read_component(name, line).map(Some);
+                }
+                Some(Marker::End(name)) => {
+                    return Err(ParseError::UnexpectedEnd {
+                        location: line.location(),
+                        name: name.to_owned(),
+                    });
+                }
+                None if self.calendar.is_some() => {
+                    let parsed = property::<VerboseError<&str>>(&line.text)
+                        .finish()
+                        .map_err(|error| {
+                            ParseError::in_folded(&line.raw, line.number, &line.text, &error)
+                        })?;
+                    self.properties.push(parsed.1.into());
+                }
+                None => {
+                    return Err(ParseError::Syntax {
+                        location: line.location(),
+                        message: "property outside of a component".into(),
+                    });
+                }
+            }
+        }
+        if let Some(location) = self.calendar.take() {
+            return Err(ParseError::Unterminated {
+                location,
+                name: "VCALENDAR".into(),
+            });
+        }
+        Ok(None)
+    }
+}
+
+impl<R: BufRead> Iterator for CalendarReader<R> {
+    type Item = Result<CalendarComponent, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let next = self.read_next().transpose();
+        if let Some(Err(
+            ParseError::Io(_) | ParseError::Unterminated { .. } | ParseError::LimitExceeded { .. },
+        ))
+        | None = next
+        {
+            self.finished = true;
+        }
+        next
+    }
+}
@@ -1,7 +1,7 @@
 use std::{
-    collections::BTreeMap,
+    borrow::Cow,
     fmt::{self, Write},
-    mem,
+    io, mem,
     str::FromStr,
 };
 
@@ -14,6 +14,8 @@ pub mod attendee;
 pub struct Parameter {
     key: String,
     val: String,
+    /// Whether `val` is a list in its written form, see [`Parameter::list`].
+    list: bool,
 }
 
 impl Parameter {
@@ -22,6 +24,42 @@ impl Parameter {
         Parameter {
             key: key.to_owned(),
             val: val.to_owned(),
+            list: false,
+        }
+    }
+
+    /// Creates a `Parameter` holding a list of values, like `MEMBER="mailto:a@example.com","mailto:b@example.com"`.
+    ///
+    /// Each value is quoted and escaped following [RFC 6868](https://datatracker.ietf.org/doc/html/rfc6868).
+    ///
+    /// ```
+    /// # use icalendar::Parameter;
+    /// let member = Parameter::list("MEMBER", ["mailto:a@example.com", "mailto:b@example.com"]);
+    /// assert_eq!(member.value(), r#""mailto:a@example.com","mailto:b@example.com""#);
+    /// assert_eq!(member.values(), ["mailto:a@example.com", "mailto:b@example.com"]);
+    /// ```
+    pub fn list<V: AsRef<str>>(key: &str, values: impl IntoIterator<Item = V>) -> Self {
+        let values = values
+            .into_iter()
+            .map(|value| format!("\"{}\"", encode_caret(value.as_ref())))
+            .collect::<Vec<_>>();
+        Parameter::written_list(key, &values.join(","))
+    }
+
+    /// A list in the form it is written in, e.g. `"a^'b",c`.
+    pub(crate) fn written_list(key: &str, val: &str) -> Self {
+        Parameter {
+            key: key.to_owned(),
+            val: val.to_owned(),
+            list: true,
+        }
+    }
+
+    /// The same parameter named `key`.
+    pub(crate) fn renamed(self, key: &str) -> Self {
+        Parameter {
+            key: key.to_owned(),
+            ..self
         }
     }
 
@@ -31,9 +69,168 @@ impl Parameter {
     }
 
     /// Returns a reference to the value field.
+    ///
+    /// For a [list](Parameter::list) this is the list as it is written, see [`values`](Parameter::values).
     pub fn value(&self) -> &str {
         &self.val
     }
+
+    /// Whether the parameter holds a [list](Parameter::list) of values.
+    pub fn is_list(&self) -> bool {
+        self.list
+    }
+
+    /// The individual values, for a [list](Parameter::list) like `"mailto:a@example.com","mailto:b@example.com"`
+    /// each element without its quotes and with its [RFC 6868](https://datatracker.ietf.org/doc/html/rfc6868) escapes decoded.
+    pub fn values(&self) -> Vec<Cow<'_, str>> {
+        if !self.list {
+            return match self.val.as_str() {
+                "" => Vec::new(),
+                value => vec![Cow::Borrowed(value)],
+            };
+        }
+        split_list(&self.val)
+            .into_iter()
+            .map(|value| unquote(value.trim()))
+            .filter(|value| !value.is_empty())
+            .map(decode_caret)
+            .collect()
+    }
+
+    /// The value the way it appears in a content line.
+    pub(crate) fn encoded_value(&self) -> Cow<'_, str> {
+        encode_param_value(&self.val, self.list)
+    }
+}
+
+/// Splits a parameter value at the commas that are not quoted.
+fn split_list(value: &str) -> Vec<&str> {
+    let mut values = Vec::new();
+    let mut quoted = false;
+    let mut start = 0;
+    for (index, c) in value.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                values.extend(value.get(start..index));
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    values.extend(value.get(start..));
+    values
+}
+
+/// Writes a parameter value the way it appears in a content line.
+///
+/// A list is in its written form already, a single value is escaped following
+/// [RFC 6868](https://datatracker.ietf.org/doc/html/rfc6868) and quoted where necessary.
+pub(crate) fn encode_param_value(value: &str, list: bool) -> Cow<'_, str> {
+    if list {
+        return Cow::Borrowed(value);
+    }
+    let mut encoded = encode_caret(value);
+    // a comma in a single value must not read as a list separator
+    if encoded.contains([':', ';', ',']) {
+        encoded.insert(0, '"');
+        encoded.push('"');
+    }
+    Cow::Owned(encoded)
+}
+
+/// Escapes carets, double quotes and line breaks following [RFC 6868](https://datatracker.ietf.org/doc/html/rfc6868).
+fn encode_caret(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len() + 2);
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '^' => encoded.push_str("^^"),
+            '"' => encoded.push_str("^'"),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\r' | '\n' => encoded.push_str("^n"),
+            c => encoded.push(c),
+        }
+    }
+    encoded
+}
+
+/// Decodes the [RFC 6868](https://datatracker.ietf.org/doc/html/rfc6868) escapes of a parameter value:
+/// `^n` is a line break, `^'` a double quote and `^^` a caret, any other `^` is kept.
+pub(crate) fn decode_caret(value: &str) -> Cow<'_, str> {
+    if !value.contains('^') {
+        return Cow::Borrowed(value);
+    }
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '^' {
+            out.push(c);
+            continue;
+        }
+        match chars.peek() {
+            Some('n') => out.push('\n'),
+            Some('\'') => out.push('"'),
+            Some('^') => out.push('^'),
+            _ => {
+                out.push('^');
+                continue;
+            }
+        }
+        chars.next();
+    }
+    Cow::Owned(out)
+}
+
+/// Strips one pair of surrounding double quotes.
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// [RFC-5545](https://datatracker.ietf.org/doc/html/rfc5545) states that the following
+/// "MAY occur more than once" in a VEVENT, VTODO, VJOURNAL, and VFREEBUSY.
+/// Note: A VJOURNAL can also contain multiple DECRIPTION but this is not covered here.
+pub(crate) const MULTIS: [&str; 13] = [
+    "ATTACH",
+    "ATTENDEE",
+    "CATEGORIES",
+    "COMMENT",
+    "CONTACT",
+    "EXDATE",
+    "FREEBUSY",
+    "IANA-PROP",
+    "RDATE",
+    "RELATED",
+    "RESOURCES",
+    "RSTATUS",
+    "X-PROP",
+];
+
+/// Parameters that RFC 5545 defines with a single value, a comma in them doesn't separate values.
+pub(crate) fn is_single_valued(key: &str) -> bool {
+    matches!(
+        key.to_ascii_uppercase().as_str(),
+        "ALTREP"
+            | "CN"
+            | "CUTYPE"
+            | "DIR"
+            | "ENCODING"
+            | "FBTYPE"
+            | "FMTTYPE"
+            | "LANGUAGE"
+            | "PARTSTAT"
+            | "RANGE"
+            | "RELATED"
+            | "RELTYPE"
+            | "ROLE"
+            | "RSVP"
+            | "SENT-BY"
+            | "TZID"
+            | "VALUE"
+    )
 }
 
 impl From<(&str, &str)> for Parameter {
@@ -42,16 +239,160 @@ impl From<(&str, &str)> for Parameter {
     }
 }
 
-pub type EntryParameters = BTreeMap<String, Parameter>;
+/// The parameters of a [`Property`] in the order they were added.
+///
+/// Unlike properties of a component, parameters may repeat, e.g. `X-` parameters given twice.
+/// [`get`](Parameters::get) returns the first one with a name.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Parameters(Vec<Parameter>);
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+impl Parameters {
+    /// The first parameter named `key`.
+    pub fn get(&self, key: &str) -> Option<&Parameter> {
+        self.0.iter().find(|param| param.key == key)
+    }
+
+    /// All parameters named `key`, in order.
+    pub fn get_all<'p>(&'p self, key: &'p str) -> impl Iterator<Item = &'p Parameter> {
+        self.0.iter().filter(move |param| param.key == key)
+    }
+
+    /// Whether there is a parameter named `key`.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// The number of parameters, repeated ones counting each time.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether there are no parameters.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterates over the parameters in order.
+    pub fn iter(&self) -> std::slice::Iter<'_, Parameter> {
+        self.0.iter()
+    }
+
+    /// The names of the parameters in order, like [`BTreeMap::keys`](std::collections::BTreeMap::keys) used to give.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(Parameter::key)
+    }
+
+    /// The parameters in order, like [`BTreeMap::values`](std::collections::BTreeMap::values) used to give.
+    pub fn values(&self) -> std::slice::Iter<'_, Parameter> {
+        self.0.iter()
+    }
+
+    /// Replaces the first parameter with the same name and drops the others, or appends it.
+    pub(crate) fn insert(&mut self, parameter: Parameter) {
+        let key = parameter.key.clone();
+        let mut replacement = Some(parameter);
+        self.0.retain_mut(|param| {
+            if param.key != key {
+                return true;
+            }
+            match replacement.take() {
+                Some(replacement) => {
+                    *param = replacement;
+                    true
+                }
+                None => false,
+            }
+        });
+        if let Some(parameter) = replacement {
+            self.0.push(parameter);
+        }
+    }
+
+    /// Appends `parameter`, keeping those with the same name.
+    pub(crate) fn push(&mut self, parameter: Parameter) {
+        self.0.push(parameter);
+    }
+
+    /// Removes all parameters named `key`.
+    pub(crate) fn remove(&mut self, key: &str) {
+        self.0.retain(|param| param.key != key);
+    }
+
+    /// Sorts the parameters by name and then by value.
+    pub(crate) fn sort(&mut self) {
+        self.0
+            .sort_by(|a, b| a.key.cmp(&b.key).then_with(|| a.val.cmp(&b.val)));
+    }
+}
+
+impl std::ops::Index<&str> for Parameters {
+    type Output = Parameter;
+
+    /// The first parameter named `key`.
+    ///
+    /// # Panics
+    /// if there is none.
+    fn index(&self, key: &str) -> &Parameter {
+        self.get(key)
+            .unwrap_or_else(|| panic!("no parameter named {key:?}"))
+    }
+}
+
+impl<'p> IntoIterator for &'p Parameters {
+    type Item = &'p Parameter;
+    type IntoIter = std::slice::Iter<'p, Parameter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl IntoIterator for Parameters {
+    type Item = Parameter;
+    type IntoIter = std::vec::IntoIter<Parameter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl FromIterator<Parameter> for Parameters {
+    fn from_iter<T: IntoIterator<Item = Parameter>>(iter: T) -> Self {
+        Parameters(iter.into_iter().collect())
+    }
+}
+
+#[derive(Clone, Debug)]
 /// key-value pairs inside of `Component`s
 pub struct Property {
     pub(crate) key: String,
     pub(crate) val: String,
-    pub(crate) params: EntryParameters,
+    pub(crate) params: Parameters,
+    /// Set when read by [`Calendar::from_str_lossless`](crate::Calendar::from_str_lossless).
+    pub(crate) source: Option<Box<Source>>,
+}
+
+/// The content line a [`Property`] was read from.
+#[derive(Clone, Debug)]
+pub(crate) struct Source {
+    /// Position among the properties of its component.
+    pub position: usize,
+    /// The content line as read, physical lines separated by `\n`.
+    pub raw: String,
+    /// What [`Property::to_line`] gave right after reading,
+    /// the raw line is only written as long as that hasn't changed.
+    pub line: String,
 }
 
+/// Ignores where a property was read from.
+impl PartialEq for Property {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key && self.val == other.val && self.params == other.params
+    }
+}
+
+impl Eq for Property {}
+
 impl From<(&str, &str)> for Property {
     fn from((key, val): (&str, &str)) -> Self {
         Property::new(key, val)
@@ -71,6 +412,7 @@ impl Property {
             key: key.into(),
             val: val.into(),
             params: Default::default(),
+            source: None,
         }
     }
 
@@ -83,6 +425,7 @@ impl Property {
             key,
             val,
             params: Default::default(),
+            source: None,
         }
     }
 
@@ -97,7 +440,7 @@ impl Property {
     }
 
     /// Returns a reference to the parameters.
-    pub fn params(&self) -> &EntryParameters {
+    pub fn params(&self) -> &Parameters {
         &self.params
     }
 
@@ -140,10 +483,38 @@ impl Property {
         self.params.get(key).and_then(|param| converter(&param.val))
     }
 
-    /// Appends a new parameter.
+    /// Returns the values of all parameters named `key`, see [`Parameter::values`].
+    ///
+    /// ```
+    /// # use icalendar::{Parameter, Property};
+    /// let attendee = Property::new("ATTENDEE", "mailto:c@example.com")
+    ///     .append_parameter(Parameter::list("MEMBER", ["mailto:a@example.com", "mailto:b@example.com"]))
+    ///     .append_multi_parameter(("X-TAG", "one"))
+    ///     .append_multi_parameter(Parameter::list("X-TAG", ["two", "three"]))
+    ///     .done();
+    /// assert_eq!(
+    ///     attendee.param_values("MEMBER"),
+    ///     ["mailto:a@example.com", "mailto:b@example.com"]
+    /// );
+    /// assert_eq!(attendee.param_values("X-TAG"), ["one", "two", "three"]);
+    /// ```
+    pub fn param_values(&self, key: &str) -> Vec<Cow<'_, str>> {
+        self.params
+            .iter()
+            .filter(|param| param.key == key)
+            .flat_map(Parameter::values)
+            .collect()
+    }
+
+    /// Appends a new parameter, replacing any with the same name.
     pub fn append_parameter<I: Into<Parameter>>(&mut self, into_parameter: I) -> &mut Self {
-        let parameter = into_parameter.into();
-        self.params.insert(parameter.key.clone(), parameter);
+        self.params.insert(into_parameter.into());
+        self
+    }
+
+    /// Appends a new parameter, keeping those with the same name.
+    pub fn append_multi_parameter<I: Into<Parameter>>(&mut self, into_parameter: I) -> &mut Self {
+        self.params.push(into_parameter.into());
         self
     }
 
@@ -159,6 +530,7 @@ impl Property {
             key: mem::take(&mut self.key),
             val: mem::take(&mut self.val),
             params: mem::take(&mut self.params),
+            source: self.source.take(),
         }
     }
 
@@ -171,32 +543,14 @@ impl Property {
             .replace('\n', r#"\n"#)
     }
 
-    fn quote_if_contains_colon(input: &str) -> String {
-        // Already quoted (e.g. pre-quoted multi-value URI lists like
-        // `"mailto:a@b.com","mailto:c@d.com"`): emit as-is to avoid
-        // wrapping in an extra outer pair of double-quotes.
-        if input.starts_with('"') && input.ends_with('"') {
-            return input.to_string();
-        }
-        if input.contains([':', ';']) {
-            let mut quoted = String::with_capacity(input.len() + 2);
-            quoted.push('"');
-            quoted.push_str(input);
-            quoted.push('"');
-            quoted
-        } else {
-            input.to_string()
-        }
-    }
-
     /// Writes this Property to `out`
     pub(crate) fn to_line(&self) -> Result<String, fmt::Error> {
         // A nice starting capacity for the majority of content lines
         let mut line = String::with_capacity(150);
 
         write!(line, "{}", self.key)?;
-        for Parameter { key, val } in self.params.values() {
-            write!(line, ";{}={}", key, Self::quote_if_contains_colon(val))?;
+        for param in &self.params {
+            write!(line, ";{}={}", param.key, param.encoded_value())?;
         }
         let value_type = self.value_type();
         match value_type {
@@ -207,11 +561,50 @@ impl Property {
     }
 
     /// Writes this Property to `out`
+    ///
+    /// A property that was read losslessly and hasn't changed since is written as it was read, folding included.
     pub(crate) fn fmt_write<W: Write>(&self, out: &mut W) -> Result<(), fmt::Error> {
         let line = self.to_line()?;
-        write_crlf!(out, "{}", fold_line(&line))?;
+        match &self.source {
+            Some(source) if source.line == line => {
+                for physical in source.raw.split('\n') {
+                    write_crlf!(out, "{}", physical)?;
+                }
+            }
+            _ => write_crlf!(out, "{}", fold_line(&line))?,
+        }
         Ok(())
     }
+
+    /// Remembers `raw` as the content line this property was read from.
+    #[cfg(feature = "parser")]
+    pub(crate) fn read_from(&mut self, position: usize, raw: String) {
+        if let Ok(line) = self.to_line() {
+            self.source = Some(Box::new(Source {
+                position,
+                raw,
+                line,
+            }));
+        }
+    }
+
+    /// Takes the place of `replaced` when written losslessly.
+    pub(crate) fn replacing(mut self, replaced: &Property) -> Self {
+        if self.source.is_none() {
+            self.source.clone_from(&replaced.source);
+        }
+        self
+    }
+
+    /// The position among the properties of its component, if it was read losslessly.
+    pub(crate) fn position(&self) -> Option<usize> {
+        self.source.as_ref().map(|source| source.position)
+    }
+
+    /// Writes this Property as folded content line into an [`io::Write`].
+    pub fn write_to<W: io::Write>(&self, out: &mut W) -> io::Result<()> {
+        crate::calendar::writer::write_io(out, |out| self.fmt_write(out))
+    }
 }
 
 impl TryInto<String> for Property {
@@ -257,8 +650,73 @@ impl From<Class> for Property {
                 Class::Confidential => "CONFIDENTIAL",
             }),
             params: Default::default(),
+            source: None,
+        }
+    }
+}
+
+/// The iTIP method of a scheduling message, see [RFC 5546, Section 1.4](https://datatracker.ietf.org/doc/html/rfc5546#section-1.4).
+/// [RFC 5545, Section 3.7.2](https://datatracker.ietf.org/doc/html/rfc5545#section-3.7.2)
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Method {
+    /// Publishes a calendar without expecting interaction.
+    Publish,
+    /// Invites attendees or updates an existing event.
+    Request,
+    /// An attendee's answer to a request.
+    Reply,
+    /// Adds instances to an existing recurring event.
+    Add,
+    /// Cancels an event or some of its instances.
+    Cancel,
+    /// An attendee asks for the latest version of an event.
+    Refresh,
+    /// An attendee proposes changes to an event.
+    Counter,
+    /// The organizer rejects a counter proposal.
+    DeclineCounter,
+}
+
+impl Method {
+    pub(crate) fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "PUBLISH" => Some(Self::Publish),
+            "REQUEST" => Some(Self::Request),
+            "REPLY" => Some(Self::Reply),
+            "ADD" => Some(Self::Add),
+            "CANCEL" => Some(Self::Cancel),
+            "REFRESH" => Some(Self::Refresh),
+            "COUNTER" => Some(Self::Counter),
+            "DECLINECOUNTER" => Some(Self::DeclineCounter),
+            _ => None,
         }
     }
+
+    /// The value of the `METHOD` property.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Method::Publish => "PUBLISH",
+            Method::Request => "REQUEST",
+            Method::Reply => "REPLY",
+            Method::Add => "ADD",
+            Method::Cancel => "CANCEL",
+            Method::Refresh => "REFRESH",
+            Method::Counter => "COUNTER",
+            Method::DeclineCounter => "DECLINECOUNTER",
+        }
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<Method> for Property {
+    fn from(val: Method) -> Self {
+        Property::new("METHOD", val.as_str())
+    }
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -329,6 +787,7 @@ impl From<EventStatus> for Property {
                 EventStatus::Cancelled => "CANCELLED",
             }),
             params: Default::default(),
+            source: None,
         }
     }
 }
@@ -354,6 +813,7 @@ impl From<ValueType> for Parameter {
                 ValueType::Uri => "URI",
                 ValueType::UtcOffset => "UTC-OFFSET",
             }),
+            list: false,
         }
     }
 }
@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use super::{Parameter, Property};
 
 /// [RFC 5545, Section 3.2.3](https://datatracker.ietf.org/doc/html/rfc5545#section-3.2.3)
@@ -136,41 +138,6 @@ impl From<PartStat> for Parameter {
     }
 }
 
-/// Encode a list of CAL-ADDRESS URIs as a comma-separated list of individually
-/// quoted strings, as required by RFC 5545 §3.2.4, §3.2.5, and §3.2.11:
-///
-/// > The individual calendar address parameter values MUST each be
-/// > specified in a quoted-string.
-///
-/// Example output: `"mailto:a@example.com","mailto:b@example.com"`
-fn encode_cal_address_list(addrs: &[String]) -> String {
-    addrs
-        .iter()
-        .map(|a| format!("\"{}\"", a))
-        .collect::<Vec<_>>()
-        .join(",")
-}
-
-/// Decode a comma-separated list of individually quoted CAL-ADDRESS URIs back
-/// into plain address strings.  Handles both quoted (`"mailto:a@b"`) and
-/// unquoted (`mailto:a@b`) entries so the decoder is tolerant of both forms.
-fn decode_cal_address_list(s: &str) -> Vec<String> {
-    // Split on `","` boundaries first (the RFC-correct form), then fall back
-    // to a simple comma split for unquoted values produced by other clients.
-    s.split(',')
-        .map(|part| {
-            let trimmed = part.trim();
-            // Strip surrounding double-quotes if present.
-            if trimmed.starts_with('"') && trimmed.ends_with('"') && trimmed.len() >= 2 {
-                trimmed[1..trimmed.len() - 1].to_string()
-            } else {
-                trimmed.to_string()
-            }
-        })
-        .filter(|s| !s.is_empty())
-        .collect()
-}
-
 /// [RFC 5545, Section 3.8.4.1](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.4.1)
 /// Attendee (ATTENDEE)
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
@@ -296,11 +263,10 @@ impl From<Attendee> for Property {
         if let Some(cutype) = attendee.cutype {
             prop.append_parameter(cutype);
         }
-        // RFC 5545 §3.2.11: each MEMBER address MUST be in a quoted-string.
-        // We store the pre-quoted comma-separated list so that
-        // `quote_if_contains_colon` in `to_line()` does not double-quote it.
+        // RFC 5545 §3.2.11: each MEMBER address MUST be in a quoted-string,
+        // which `Parameter::list` takes care of.
         if !attendee.member.is_empty() {
-            prop.add_parameter("MEMBER", &encode_cal_address_list(&attendee.member));
+            prop.append_parameter(Parameter::list("MEMBER", &attendee.member));
         }
         if let Some(role) = attendee.role {
             prop.append_parameter(role);
@@ -313,17 +279,11 @@ impl From<Attendee> for Property {
         }
         // RFC 5545 §3.2.5: each DELEGATED-TO address MUST be in a quoted-string.
         if !attendee.delegated_to.is_empty() {
-            prop.add_parameter(
-                "DELEGATED-TO",
-                &encode_cal_address_list(&attendee.delegated_to),
-            );
+            prop.append_parameter(Parameter::list("DELEGATED-TO", &attendee.delegated_to));
         }
         // RFC 5545 §3.2.4: each DELEGATED-FROM address MUST be in a quoted-string.
         if !attendee.delegated_from.is_empty() {
-            prop.add_parameter(
-                "DELEGATED-FROM",
-                &encode_cal_address_list(&attendee.delegated_from),
-            );
+            prop.append_parameter(Parameter::list("DELEGATED-FROM", &attendee.delegated_from));
         }
         if let Some(sentby) = attendee.sent_by {
             prop.add_parameter("SENT-BY", &sentby);
@@ -342,6 +302,23 @@ impl From<Attendee> for Property {
     }
 }
 
+/// The addresses of all parameters named `key`, which may each hold a list.
+///
+/// Addresses are always a list, so a value built with [`Property::add_parameter`]
+/// like `"mailto:a@example.com","mailto:b@example.com"` is read as one too.
+fn cal_addresses(prop: &Property, key: &str) -> Vec<String> {
+    prop.params()
+        .get_all(key)
+        .flat_map(|param| {
+            Parameter::written_list(key, param.value())
+                .values()
+                .into_iter()
+                .map(Cow::into_owned)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
 impl TryFrom<&Property> for Attendee {
     type Error = ();
 
@@ -352,9 +329,7 @@ impl TryFrom<&Property> for Attendee {
         let value = prop.value().to_string();
 
         let cutype = prop.get_param_as("CUTYPE", CUType::from_str);
-        let member = prop
-            .get_param_as("MEMBER", |s| Some(decode_cal_address_list(s)))
-            .unwrap_or_default();
+        let member = cal_addresses(prop, "MEMBER");
         let role = prop.get_param_as("ROLE", Role::from_str);
         let partstat = prop.get_param_as("PARTSTAT", PartStat::from_str);
         let rsvp = prop.get_param_as("RSVP", |s| match s.to_uppercase().as_str() {
@@ -362,12 +337,8 @@ impl TryFrom<&Property> for Attendee {
             "FALSE" => Some(false),
             _ => None,
         });
-        let delegated_to = prop
-            .get_param_as("DELEGATED-TO", |s| Some(decode_cal_address_list(s)))
-            .unwrap_or_default();
-        let delegated_from = prop
-            .get_param_as("DELEGATED-FROM", |s| Some(decode_cal_address_list(s)))
-            .unwrap_or_default();
+        let delegated_to = cal_addresses(prop, "DELEGATED-TO");
+        let delegated_from = cal_addresses(prop, "DELEGATED-FROM");
         let sentby = prop.get_param_as("SENT-BY", |s| Some(s.to_string()));
         let cn = prop.get_param_as("CN", |s| Some(s.to_string()));
         let dir = prop.get_param_as("DIR", |s| Some(s.to_string()));
@@ -0,0 +1,553 @@
+//! Time-range queries following [RFC 4791, Section 9.9](https://datatracker.ietf.org/doc/html/rfc4791#section-9.9).
+//!
+//! `CalDAV` defines for each component type when it "overlaps" a given time range.
+//! [`TimeRange`] implements these rules for `VEVENT`, `VTODO`, `VJOURNAL`, `VFREEBUSY` and `VALARM`,
+//! expanding recurring components with the crate's recurrence support.
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, TimeZone as _, Utc};
+
+use crate::{
+    Calendar, CalendarComponent, Component,
+    components::{
+        Other,
+        alarm::{Related, Trigger},
+        build_recurrence_set,
+        date_time::{CalendarDateTime, DatePerhapsTime, parse_duration, parse_utc_date_time},
+    },
+};
+
+/// Upper bound for the number of instances a single recurring component is expanded into.
+const EXPANSION_LIMIT: u16 = u16::MAX;
+
+/// A time range as used by `CalDAV` `calendar-query` reports.
+///
+/// The range is half-open: it includes `start` but excludes `end`.
+/// Either bound may be left open, in which case it extends infinitely into the past or future.
+///
+/// Floating date-times and `DATE` values have no absolute position in time,
+/// they are evaluated in the range's [reference timezone](TimeRange::with_timezone).
+/// If none is set, [`Calendar::occurrences_in_range`] uses the calendar's `X-WR-TIMEZONE`,
+/// everything else falls back to UTC.
+///
+/// ```
+/// # use icalendar::*;
+/// # use chrono::*;
+/// let event = Event::new()
+///     .starts(Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap())
+///     .ends(Utc.with_ymd_and_hms(2026, 3, 2, 10, 0, 0).unwrap())
+///     .done();
+///
+/// let morning = TimeRange::new(
+///     Utc.with_ymd_and_hms(2026, 3, 2, 8, 0, 0).unwrap(),
+///     Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap(),
+/// );
+/// assert!(morning.matches(&event));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeRange {
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    timezone: Option<chrono_tz::Tz>,
+}
+
+/// A single instance of a component that overlaps a [`TimeRange`].
+///
+/// Obtained from [`Calendar::occurrences_in_range`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Occurrence<'a> {
+    /// The component this instance belongs to.
+    ///
+    /// For an instance that was overridden by a component with a matching `RECURRENCE-ID`
+    /// this is the overriding component, not the master.
+    pub component: &'a CalendarComponent,
+    /// The effective start of this instance.
+    pub start: Option<DatePerhapsTime>,
+    /// The effective end of this instance, derived from `DTEND`, `DURATION` or `DUE`.
+    pub end: Option<DatePerhapsTime>,
+    /// Identifies the instance within its recurrence set, `None` for non-recurring components.
+    pub recurrence_id: Option<DatePerhapsTime>,
+}
+
+/// The properties RFC 4791 consults for one instance of a component.
+#[derive(Clone, Debug, Default)]
+struct Span {
+    dtstart: Option<DatePerhapsTime>,
+    dtend: Option<DatePerhapsTime>,
+    duration: Option<Duration>,
+    due: Option<DatePerhapsTime>,
+    completed: Option<DateTime<Utc>>,
+    created: Option<DateTime<Utc>>,
+}
+
+impl Span {
+    fn of<C: Component + ?Sized>(component: &C) -> Self {
+        let date_property = |key| DatePerhapsTime::from_property(component.properties().get(key)?);
+        Span {
+            dtstart: date_property("DTSTART"),
+            dtend: date_property("DTEND"),
+            duration: component
+                .property_value("DURATION")
+                .and_then(parse_duration),
+            due: date_property("DUE"),
+            completed: component
+                .property_value("COMPLETED")
+                .and_then(parse_utc_date_time),
+            created: component
+                .property_value("CREATED")
+                .and_then(parse_utc_date_time),
+        }
+    }
+
+    /// Moves this span to a different start, keeping `DTEND` and `DUE` at the same wall-clock distance.
+    fn moved_to(&self, start: DatePerhapsTime) -> Self {
+        let delta = self
+            .dtstart
+            .as_ref()
+            .map(|old| start.wall_clock() - old.wall_clock())
+            .unwrap_or_else(Duration::zero);
+        Span {
+            dtstart: Some(start),
+            dtend: self.dtend.as_ref().map(|dt| dt.shifted(delta)),
+            due: self.due.as_ref().map(|dt| dt.shifted(delta)),
+            ..self.clone()
+        }
+    }
+
+    /// The end of this span as it would be reported to a user.
+    fn effective_end(&self) -> Option<DatePerhapsTime> {
+        self.dtend.clone().or_else(|| self.due.clone()).or_else(|| {
+            self.dtstart
+                .as_ref()
+                .zip(self.duration)
+                .map(|(start, duration)| start.shifted(duration))
+        })
+    }
+}
+
+fn to_rrule_tz(dt: DateTime<Utc>) -> DateTime<rrule::Tz> {
+    rrule::Tz::UTC.from_utc_datetime(&dt.naive_utc())
+}
+
+/// Parses a `PERIOD` value, either `start/end` or `start/duration`.
+fn parse_period(period: &str) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let (start, end) = period.split_once('/')?;
+    let start = parse_utc_date_time(start)?;
+    let end = parse_utc_date_time(end).or_else(|| Some(start + parse_duration(end)?))?;
+    Some((start, end))
+}
+
+impl TimeRange {
+    /// Creates a range from `start` (inclusive) to `end` (exclusive).
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        TimeRange {
+            start: Some(start),
+            end: Some(end),
+            timezone: None,
+        }
+    }
+
+    /// Creates a range that starts at `start` and never ends.
+    pub fn starting_at(start: DateTime<Utc>) -> Self {
+        TimeRange {
+            start: Some(start),
+            end: None,
+            timezone: None,
+        }
+    }
+
+    /// Creates a range that reaches infinitely into the past and ends at `end`.
+    pub fn ending_at(end: DateTime<Utc>) -> Self {
+        TimeRange {
+            start: None,
+            end: Some(end),
+            timezone: None,
+        }
+    }
+
+    /// Sets the timezone in which floating date-times and `DATE` values are evaluated.
+    ///
+    /// This corresponds to the `CALDAV:calendar-timezone` of a collection.
+    pub fn with_timezone(mut self, timezone: chrono_tz::Tz) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+
+    /// The inclusive start of the range, if bounded.
+    pub fn start(&self) -> Option<DateTime<Utc>> {
+        self.start
+    }
+
+    /// The exclusive end of the range, if bounded.
+    pub fn end(&self) -> Option<DateTime<Utc>> {
+        self.end
+    }
+
+    /// The reference timezone for floating date-times, if one was set.
+    pub fn timezone(&self) -> Option<chrono_tz::Tz> {
+        self.timezone
+    }
+
+    /// Returns `true` if the interval `[start, end)` overlaps this range.
+    pub fn overlaps(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+        self.start_lt(start.max(end)) && self.end_gt(start)
+    }
+
+    /// Returns `true` if `instant` lies within this range.
+    pub fn contains(&self, instant: DateTime<Utc>) -> bool {
+        self.start_le(instant) && self.end_gt(instant)
+    }
+
+    /// Returns `true` if any instance of `component` overlaps this range.
+    ///
+    /// `VEVENT`, `VTODO`, `VJOURNAL` and `VFREEBUSY` components are supported, everything else
+    /// never matches. `VALARM`s need their parent component, use [`TimeRange::matches_alarm`].
+    pub fn matches<C: Component + ?Sized>(&self, component: &C) -> bool {
+        self.instances(component, &HashSet::new())
+            .iter()
+            .any(|span| self.span_overlaps(component, span))
+    }
+
+    /// Returns `true` if `alarm` triggers within this range for any instance of `parent`.
+    ///
+    /// Relative triggers are resolved against the start or end of each instance of `parent`,
+    /// repetitions given by `REPEAT` and `DURATION` are taken into account.
+    pub fn matches_alarm<A, C>(&self, alarm: &A, parent: &C) -> bool
+    where
+        A: Component + ?Sized,
+        C: Component + ?Sized,
+    {
+        let is_todo = parent.component_kind() == "VTODO";
+        self.instances(parent, &HashSet::new())
+            .iter()
+            .flat_map(|span| self.trigger_times(alarm, span, is_todo))
+            .any(|trigger| self.contains(trigger))
+    }
+
+    /// Returns `true` if the date or date-time value `dt` lies within this range.
+    #[cfg(feature = "parser")]
+    pub(crate) fn contains_value(&self, dt: &DatePerhapsTime) -> bool {
+        self.resolve(dt)
+            .is_some_and(|instant| self.contains(instant))
+    }
+
+    /// Falls back to `timezone` (usually a calendar's `X-WR-TIMEZONE`) if no reference timezone was set.
+    pub(crate) fn or_timezone(self, timezone: Option<&str>) -> Self {
+        match (self.timezone, timezone.and_then(|tz| tz.parse().ok())) {
+            (None, Some(tz)) => self.with_timezone(tz),
+            _ => self,
+        }
+    }
+
+    fn start_lt(&self, instant: DateTime<Utc>) -> bool {
+        self.start.is_none_or(|start| start < instant)
+    }
+
+    fn start_le(&self, instant: DateTime<Utc>) -> bool {
+        self.start.is_none_or(|start| start <= instant)
+    }
+
+    fn end_gt(&self, instant: DateTime<Utc>) -> bool {
+        self.end.is_none_or(|end| end > instant)
+    }
+
+    fn end_ge(&self, instant: DateTime<Utc>) -> bool {
+        self.end.is_none_or(|end| end >= instant)
+    }
+
+    fn reference_timezone(&self) -> chrono_tz::Tz {
+        self.timezone.unwrap_or(chrono_tz::UTC)
+    }
+
+    /// Pins a value to an absolute instant.
+    ///
+    /// Unknown `TZID`s are treated like floating times since we can't resolve `VTIMEZONE`s.
+    fn resolve(&self, dt: &DatePerhapsTime) -> Option<DateTime<Utc>> {
+        dt.resolve(self.reference_timezone())
+    }
+
+    /// Expands `component` into the spans of its instances.
+    ///
+    /// Instances whose start is listed in `overridden` are skipped.
+    fn instances<C: Component + ?Sized>(
+        &self,
+        component: &C,
+        overridden: &HashSet<DateTime<Utc>>,
+    ) -> Vec<Span> {
+        let master = Span::of(component);
+        let is_recurring = component.property_value("RRULE").is_some()
+            || component.multi_properties().contains_key("RDATE");
+
+        let Some(dtstart) = master.dtstart.clone().filter(|_| is_recurring) else {
+            return vec![master];
+        };
+        let Ok(mut recurrence) = build_recurrence_set(component, None) else {
+            return vec![master];
+        };
+
+        // instances starting slightly outside of the range may still reach into it
+        let margin = self.expansion_margin(component, &master);
+        if let Some(start) = self.start {
+            recurrence = recurrence.after(to_rrule_tz(start - margin));
+        }
+        if let Some(end) = self.end {
+            recurrence = recurrence.before(to_rrule_tz(end + margin));
+        }
+
+        recurrence
+            .all(EXPANSION_LIMIT)
+            .dates
+            .iter()
+            .map(|occurrence| {
+                let start = CalendarDateTime::from(occurrence);
+                match dtstart {
+                    DatePerhapsTime::Date(_) => {
+                        DatePerhapsTime::Date(DatePerhapsTime::from(start).wall_clock().date())
+                    }
+                    DatePerhapsTime::DateTime(_) => start.into(),
+                }
+            })
+            .filter(|start| {
+                self.resolve(start)
+                    .is_none_or(|instant| !overridden.contains(&instant))
+            })
+            .map(|start| master.moved_to(start))
+            .collect()
+    }
+
+    /// How far before the range an instance may start and still be relevant.
+    fn expansion_margin<C: Component + ?Sized>(&self, component: &C, master: &Span) -> Duration {
+        let length = master
+            .dtstart
+            .as_ref()
+            .zip(master.effective_end())
+            .map(|(start, end)| (end.wall_clock() - start.wall_clock()).abs())
+            .unwrap_or_else(Duration::zero);
+
+        let alarm_reach = component
+            .components()
+            .iter()
+            .filter(|child| child.component_kind() == "VALARM")
+            .filter_map(|alarm| {
+                let offset = match Trigger::try_from(alarm.properties().get("TRIGGER")?).ok()? {
+                    Trigger::Duration(offset, _) => offset.abs(),
+                    Trigger::DateTime(_) => Duration::zero(),
+                };
+                Some(offset + repetition_span(alarm))
+            })
+            .max()
+            .unwrap_or_else(Duration::zero);
+
+        length + alarm_reach + Duration::days(1)
+    }
+
+    fn span_overlaps<C: Component + ?Sized>(&self, component: &C, span: &Span) -> bool {
+        match component.component_kind().as_str() {
+            "VEVENT" => self.event_overlaps(span),
+            "VTODO" => self.todo_overlaps(span),
+            "VJOURNAL" => self.journal_overlaps(span),
+            "VFREEBUSY" => self.free_busy_overlaps(component, span),
+            _ => false,
+        }
+    }
+
+    fn event_overlaps(&self, span: &Span) -> bool {
+        let Some(dtstart) = span.dtstart.as_ref() else {
+            return false;
+        };
+        let Some(start) = self.resolve(dtstart) else {
+            return false;
+        };
+
+        if let Some(dtend) = span.dtend.as_ref().and_then(|end| self.resolve(end)) {
+            self.start_lt(dtend) && self.end_gt(start)
+        } else if let Some(duration) = span.duration {
+            if duration > Duration::zero() {
+                self.start_lt(start + duration) && self.end_gt(start)
+            } else {
+                self.start_le(start) && self.end_gt(start)
+            }
+        } else if let DatePerhapsTime::Date(_) = dtstart {
+            self.resolve(&dtstart.shifted(Duration::days(1)))
+                .is_some_and(|next_day| self.start_lt(next_day) && self.end_gt(start))
+        } else {
+            self.start_le(start) && self.end_gt(start)
+        }
+    }
+
+    fn todo_overlaps(&self, span: &Span) -> bool {
+        let dtstart = span.dtstart.as_ref().and_then(|dt| self.resolve(dt));
+        let due = span.due.as_ref().and_then(|dt| self.resolve(dt));
+
+        match (dtstart, span.duration, due) {
+            (Some(dtstart), Some(duration), _) => {
+                let end = dtstart + duration;
+                self.start_le(end) && (self.end_gt(dtstart) || self.end_ge(end))
+            }
+            (Some(dtstart), None, Some(due)) => {
+                (self.start_lt(due) || self.start_le(dtstart))
+                    && (self.end_gt(dtstart) || self.end_ge(due))
+            }
+            (Some(dtstart), None, None) => self.start_le(dtstart) && self.end_gt(dtstart),
+            (None, _, Some(due)) => self.start_lt(due) && self.end_ge(due),
+            (None, _, None) => match (span.completed, span.created) {
+                (Some(completed), Some(created)) => {
+                    (self.start_le(created) || self.start_le(completed))
+                        && (self.end_ge(created) || self.end_ge(completed))
+                }
+                (Some(completed), None) => self.start_le(completed) && self.end_ge(completed),
+                (None, Some(created)) => self.end_gt(created),
+                (None, None) => true,
+            },
+        }
+    }
+
+    fn journal_overlaps(&self, span: &Span) -> bool {
+        let Some(dtstart) = span.dtstart.as_ref() else {
+            return false;
+        };
+        let Some(start) = self.resolve(dtstart) else {
+            return false;
+        };
+        match dtstart {
+            DatePerhapsTime::DateTime(_) => self.start_le(start) && self.end_gt(start),
+            DatePerhapsTime::Date(_) => self
+                .resolve(&dtstart.shifted(Duration::days(1)))
+                .is_some_and(|next_day| self.start_lt(next_day) && self.end_gt(start)),
+        }
+    }
+
+    fn free_busy_overlaps<C: Component + ?Sized>(&self, component: &C, span: &Span) -> bool {
+        let dtstart = span.dtstart.as_ref().and_then(|dt| self.resolve(dt));
+        let dtend = span.dtend.as_ref().and_then(|dt| self.resolve(dt));
+        if let (Some(start), Some(end)) = (dtstart, dtend) {
+            return self.start_le(end) && self.end_gt(start);
+        }
+
+        component
+            .multi_properties()
+            .get("FREEBUSY")
+            .into_iter()
+            .flatten()
+            .flat_map(|property| property.value().split(','))
+            .filter_map(parse_period)
+            .any(|(start, end)| self.start_lt(end) && self.end_gt(start))
+    }
+
+    /// All instants at which `alarm` triggers for the instance described by `span`.
+    fn trigger_times<A: Component + ?Sized>(
+        &self,
+        alarm: &A,
+        span: &Span,
+        parent_is_todo: bool,
+    ) -> Vec<DateTime<Utc>> {
+        let Some(trigger) = alarm
+            .properties()
+            .get("TRIGGER")
+            .and_then(|property| Trigger::try_from(property).ok())
+        else {
+            return Vec::new();
+        };
+
+        let first = match trigger {
+            Trigger::DateTime(date_time) => self.resolve(&date_time.into()),
+            Trigger::Duration(offset, Some(Related::End)) => {
+                let end = if parent_is_todo {
+                    span.due.clone()
+                } else {
+                    span.effective_end()
+                };
+                end.and_then(|end| self.resolve(&end))
+                    .map(|end| end + offset)
+            }
+            Trigger::Duration(offset, _) => span
+                .dtstart
+                .as_ref()
+                .and_then(|start| self.resolve(start))
+                .map(|start| start + offset),
+        };
+        let Some(first) = first else {
+            return Vec::new();
+        };
+
+        let repeat = alarm
+            .property_value("REPEAT")
+            .and_then(|repeat| repeat.parse::<i32>().ok())
+            .unwrap_or(0);
+        let interval = alarm
+            .property_value("DURATION")
+            .and_then(parse_duration)
+            .unwrap_or_else(Duration::zero);
+
+        (0..=repeat.max(0)).map(|n| first + interval * n).collect()
+    }
+}
+
+/// The time between the first and the last repetition of an alarm.
+fn repetition_span(alarm: &Other) -> Duration {
+    let repeat = alarm
+        .property_value("REPEAT")
+        .and_then(|repeat| repeat.parse::<i32>().ok())
+        .unwrap_or(0);
+    alarm
+        .property_value("DURATION")
+        .and_then(parse_duration)
+        .map(|interval| interval * repeat.max(0))
+        .unwrap_or_else(Duration::zero)
+}
+
+/// Shared guts of [`Calendar::occurrences_in_range`].
+pub(crate) fn occurrences_in_range<'a>(
+    calendar: &'a Calendar,
+    range: &TimeRange,
+) -> Vec<Occurrence<'a>> {
+    let range = range.or_timezone(calendar.get_timezone());
+
+    // instances replaced by an override must not be reported twice
+    let overridden: HashSet<(&str, DateTime<Utc>)> = calendar
+        .components
+        .iter()
+        .filter_map(|component| {
+            let uid = component.get_uid()?;
+            let recurrence_id = range.resolve(&component.get_recurrence_id()?)?;
+            Some((uid, recurrence_id))
+        })
+        .collect();
+
+    let mut occurrences = Vec::new();
+    for component in &calendar.components {
+        let recurrence_id = component.get_recurrence_id();
+
+        let skip: HashSet<DateTime<Utc>> = match (component.get_uid(), &recurrence_id) {
+            (Some(uid), None) => overridden
+                .iter()
+                .filter(|(overridden_uid, _)| *overridden_uid == uid)
+                .map(|(_, instant)| *instant)
+                .collect(),
+            _ => HashSet::new(),
+        };
+        let is_recurring = recurrence_id.is_none()
+            && (component.property_value("RRULE").is_some()
+                || component.multi_properties().contains_key("RDATE"));
+
+        for span in range.instances(component, &skip) {
+            if !range.span_overlaps(component, &span) {
+                continue;
+            }
+            occurrences.push(Occurrence {
+                component,
+                start: span.dtstart.clone(),
+                end: span.effective_end(),
+                recurrence_id: if is_recurring {
+                    span.dtstart.clone()
+                } else {
+                    recurrence_id.clone()
+                },
+            });
+        }
+    }
+
+    occurrences
+        .sort_by_key(|occurrence| occurrence.start.as_ref().and_then(|dt| range.resolve(dt)));
+    occurrences
+}
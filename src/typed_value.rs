@@ -0,0 +1,388 @@
+//! Typed property values shared by the [jCal](crate::jcal) and [xCal](crate::xcal) representations.
+//!
+//! A [`TypedProperty`] is a [`Property`] of the text format with its [`ValueType`] resolved and its value
+//! split into typed parts: multiple values of `EXDATE` or `CATEGORIES`, numbers, booleans, the parts of a
+//! `RRULE`, and dates and times in the extended format both representations use.
+//! Converting back yields the text format again, so all three formats stay consistent.
+use std::{borrow::Cow, str::FromStr};
+
+use crate::{
+    ValueType,
+    parser::{Parameter, Property},
+};
+
+/// Properties whose text value is a list.
+const LIST_TEXT_PROPERTIES: [&str; 2] = ["CATEGORIES", "RESOURCES"];
+
+/// Properties whose value is a list of dates, times or periods.
+const LIST_TIME_PROPERTIES: [&str; 3] = ["EXDATE", "RDATE", "FREEBUSY"];
+
+/// Recurrence rule parts with integer values.
+const NUMERIC_RULE_PARTS: [&str; 10] = [
+    "COUNT",
+    "INTERVAL",
+    "BYSECOND",
+    "BYMINUTE",
+    "BYHOUR",
+    "BYMONTHDAY",
+    "BYYEARDAY",
+    "BYWEEKNO",
+    "BYMONTH",
+    "BYSETPOS",
+];
+
+/// The order of rule parts in RFC 5545, neither JSON objects nor XML readers have to keep it.
+const RULE_PART_ORDER: [&str; 14] = [
+    "FREQ",
+    "UNTIL",
+    "COUNT",
+    "INTERVAL",
+    "BYSECOND",
+    "BYMINUTE",
+    "BYHOUR",
+    "BYDAY",
+    "BYMONTHDAY",
+    "BYYEARDAY",
+    "BYWEEKNO",
+    "BYMONTH",
+    "BYSETPOS",
+    "WKST",
+];
+
+/// One value of a property.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum TypedValue {
+    Boolean(bool),
+    Integer(i64),
+    Float(f64),
+    /// Text, URIs, durations and dates or times in the extended format.
+    Text(String),
+    /// A `PERIOD`, its end is a date-time or a duration.
+    Period {
+        start: String,
+        end: String,
+    },
+    /// The parts of a `RECUR` in order, with lowercase names.
+    Recur(Vec<(String, Vec<TypedValue>)>),
+    /// The parts of `GEO` or `REQUEST-STATUS`.
+    Structured(Vec<TypedValue>),
+}
+
+impl TypedValue {
+    /// The value in the text format, without escaping.
+    pub(crate) fn to_text(&self, value_type: Option<ValueType>) -> String {
+        match self {
+            TypedValue::Boolean(true) => "TRUE".into(),
+            TypedValue::Boolean(false) => "FALSE".into(),
+            TypedValue::Integer(integer) => integer.to_string(),
+            TypedValue::Float(float) => float.to_string(),
+            TypedValue::Text(text) => match value_type {
+                Some(ValueType::Date | ValueType::DateTime | ValueType::Time) => basic_format(text),
+                Some(ValueType::UtcOffset) => match text.split_at_checked(1) {
+                    Some((sign, rest)) => format!("{sign}{}", rest.replace(':', "")),
+                    None => text.clone(),
+                },
+                _ => text.clone(),
+            },
+            TypedValue::Period { start, end } => {
+                let end = if is_duration(end) {
+                    end.clone()
+                } else {
+                    basic_format(end)
+                };
+                format!("{}/{end}", basic_format(start))
+            }
+            TypedValue::Recur(parts) => {
+                let mut parts = parts.iter().collect::<Vec<_>>();
+                parts.sort_by_key(|(name, _)| {
+                    RULE_PART_ORDER
+                        .iter()
+                        .position(|part| part.eq_ignore_ascii_case(name))
+                        .unwrap_or(RULE_PART_ORDER.len())
+                });
+                parts
+                    .into_iter()
+                    .map(|(name, values)| {
+                        let name = name.to_ascii_uppercase();
+                        let value_type = (name == "UNTIL").then_some(ValueType::DateTime);
+                        let values = values
+                            .iter()
+                            .map(|value| value.to_text(value_type))
+                            .collect::<Vec<_>>();
+                        format!("{name}={}", values.join(","))
+                    })
+                    .collect::<Vec<_>>()
+                    .join(";")
+            }
+            TypedValue::Structured(parts) => parts
+                .iter()
+                .map(|part| part.to_text(None))
+                .collect::<Vec<_>>()
+                .join(";"),
+        }
+    }
+
+    fn from_text(value_type: ValueType, value: &str) -> Self {
+        match value_type {
+            ValueType::Boolean if value.eq_ignore_ascii_case("TRUE") => TypedValue::Boolean(true),
+            ValueType::Boolean if value.eq_ignore_ascii_case("FALSE") => TypedValue::Boolean(false),
+            ValueType::Integer => value
+                .parse()
+                .map_or_else(|_| TypedValue::Text(value.into()), TypedValue::Integer),
+            ValueType::Float => value
+                .parse::<f64>()
+                .ok()
+                .filter(|float| float.is_finite())
+                .map_or_else(|| TypedValue::Text(value.into()), TypedValue::Float),
+            ValueType::Date => TypedValue::Text(extended_date(value)),
+            ValueType::DateTime => TypedValue::Text(extended_date_time(value)),
+            ValueType::Time => TypedValue::Text(extended_time(value)),
+            ValueType::UtcOffset => TypedValue::Text(extended_utc_offset(value)),
+            ValueType::Period => match value.split_once('/') {
+                Some((start, end)) => TypedValue::Period {
+                    start: extended_date_time(start),
+                    end: if is_duration(end) {
+                        end.into()
+                    } else {
+                        extended_date_time(end)
+                    },
+                },
+                None => TypedValue::Text(value.into()),
+            },
+            ValueType::Recur => TypedValue::Recur(
+                value
+                    .split(';')
+                    .filter(|part| !part.is_empty())
+                    .map(|part| {
+                        let (name, values) = part.split_once('=').unwrap_or((part, ""));
+                        let name = name.to_ascii_uppercase();
+                        let values = values
+                            .split(',')
+                            .map(|item| match name.as_str() {
+                                "UNTIL" => TypedValue::Text(extended_date_time(item)),
+                                name if NUMERIC_RULE_PARTS.contains(&name) => {
+                                    item.parse().map_or_else(
+                                        |_| TypedValue::Text(item.into()),
+                                        TypedValue::Integer,
+                                    )
+                                }
+                                _ => TypedValue::Text(item.into()),
+                            })
+                            .collect();
+                        (name.to_ascii_lowercase(), values)
+                    })
+                    .collect(),
+            ),
+            _ => TypedValue::Text(value.into()),
+        }
+    }
+}
+
+/// A property with resolved type and typed values.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct TypedProperty {
+    /// The uppercase name.
+    pub name: String,
+    /// Parameters with uppercase names, `VALUE` only if the type is unknown.
+    pub params: Vec<(String, Vec<String>)>,
+    /// `None` for unknown types, e.g. of `X-` properties.
+    pub value_type: Option<ValueType>,
+    pub values: Vec<TypedValue>,
+}
+
+impl TypedProperty {
+    pub(crate) fn from_property(property: &Property<'_>) -> Self {
+        let name = property.name.as_str().to_ascii_uppercase();
+        let value_type = value_type(property);
+
+        // both representations key parameters by name, so repeated ones are merged into one list
+        let mut params: Vec<(String, Vec<String>)> = Vec::new();
+        for param in &property.params {
+            let key = param.key.as_str().to_ascii_uppercase();
+            // the type replaces VALUE, unless the type is unknown
+            if key == "VALUE" && value_type.is_some() {
+                continue;
+            }
+            let param = crate::Parameter::from(param.clone());
+            let values = param.values().into_iter().map(Cow::into_owned);
+            match params.iter_mut().find(|(known, _)| *known == key) {
+                Some((_, known)) => known.extend(values),
+                None => params.push((key, values.collect())),
+            }
+        }
+
+        let value = property.val.as_str();
+        let values = match (name.as_str(), value_type) {
+            ("GEO", _) => vec![TypedValue::Structured(
+                value
+                    .split(';')
+                    .map(|part| TypedValue::from_text(ValueType::Float, part))
+                    .collect(),
+            )],
+            ("REQUEST-STATUS", _) => vec![TypedValue::Structured(
+                value
+                    .split(';')
+                    .map(|part| TypedValue::Text(part.into()))
+                    .collect(),
+            )],
+            (name, Some(ValueType::Text)) if LIST_TEXT_PROPERTIES.contains(&name) => value
+                .split(',')
+                .map(|item| TypedValue::Text(item.into()))
+                .collect(),
+            (
+                name,
+                Some(value_type @ (ValueType::Date | ValueType::DateTime | ValueType::Period)),
+            ) if LIST_TIME_PROPERTIES.contains(&name) => value
+                .split(',')
+                .map(|item| TypedValue::from_text(value_type, item))
+                .collect(),
+            (_, Some(value_type)) => vec![TypedValue::from_text(value_type, value)],
+            (_, None) => vec![TypedValue::Text(value.into())],
+        };
+
+        TypedProperty {
+            name,
+            params,
+            value_type,
+            values,
+        }
+    }
+
+    pub(crate) fn into_property(self) -> Property<'static> {
+        let mut params = self
+            .params
+            .into_iter()
+            .map(|(key, mut values)| {
+                let key = key.to_ascii_uppercase();
+                match values.len() {
+                    0 => Parameter {
+                        key: key.into(),
+                        val: None,
+                        list: false,
+                    },
+                    1 => Parameter {
+                        key: key.into(),
+                        val: values.pop().map(Into::into),
+                        list: false,
+                    },
+                    _ => crate::Parameter::list(&key, &values).into(),
+                }
+            })
+            .collect::<Vec<_>>();
+        if let Some(value_type) = self.value_type
+            && ValueType::by_name(&self.name) != Some(value_type)
+        {
+            params.push(Parameter {
+                key: "VALUE".to_owned().into(),
+                val: Some(value_type.as_str().to_owned().into()),
+                list: false,
+            });
+        }
+
+        let value = self
+            .values
+            .iter()
+            .map(|value| value.to_text(self.value_type))
+            .collect::<Vec<_>>()
+            .join(",");
+        Property {
+            name: self.name.into(),
+            val: value.into(),
+            params,
+        }
+    }
+}
+
+/// The value type of a property: explicit, by name, or guessed where the default allows alternatives.
+fn value_type(property: &Property<'_>) -> Option<ValueType> {
+    let explicit = property
+        .params
+        .iter()
+        .find(|param| param.key == "VALUE")
+        .and_then(|param| param.val.as_ref());
+    if let Some(explicit) = explicit {
+        return ValueType::from_str(&explicit.as_str().to_ascii_uppercase()).ok();
+    }
+
+    let name = property.name.as_str();
+    let value = property.val.as_str();
+    match ValueType::by_name(name) {
+        _ if name.starts_with("X-") => None,
+        Some(ValueType::DateTime) if name == "RDATE" && value.contains('/') => {
+            Some(ValueType::Period)
+        }
+        Some(ValueType::DateTime) if !value.is_empty() && !value.contains('T') => {
+            Some(ValueType::Date)
+        }
+        Some(ValueType::Duration) if value.contains('T') && !value.contains('P') => {
+            Some(ValueType::DateTime)
+        }
+        default => default,
+    }
+}
+
+fn is_duration(value: &str) -> bool {
+    value.starts_with(['P', '+', '-'])
+}
+
+/// Inserts `separator` at the byte positions in `at`, leaving anything that isn't ASCII as it is.
+fn insert_separators(value: &str, separator: char, at: &[usize]) -> String {
+    if !value.is_ascii() || value.len() < at.last().copied().unwrap_or(0) {
+        return value.to_owned();
+    }
+    let mut out = String::with_capacity(value.len() + at.len());
+    for (index, c) in value.char_indices() {
+        if at.contains(&index) {
+            out.push(separator);
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// `20260302` to `2026-03-02`
+fn extended_date(value: &str) -> String {
+    if value.len() == 8 && value.bytes().all(|b| b.is_ascii_digit()) {
+        insert_separators(value, '-', &[4, 6])
+    } else {
+        value.to_owned()
+    }
+}
+
+/// `090000Z` to `09:00:00Z`
+fn extended_time(value: &str) -> String {
+    let digits = value.trim_end_matches('Z');
+    if digits.len() == 6 && digits.bytes().all(|b| b.is_ascii_digit()) {
+        insert_separators(value, ':', &[2, 4])
+    } else {
+        value.to_owned()
+    }
+}
+
+/// `20260302T090000Z` to `2026-03-02T09:00:00Z`
+fn extended_date_time(value: &str) -> String {
+    match value.split_once('T') {
+        Some((date, time)) => format!("{}T{}", extended_date(date), extended_time(time)),
+        None => extended_date(value),
+    }
+}
+
+/// `+0130` to `+01:30`
+fn extended_utc_offset(value: &str) -> String {
+    match value.get(..1) {
+        Some(sign @ ("+" | "-")) => {
+            let digits = value.get(1..).unwrap_or_default();
+            if matches!(digits.len(), 4 | 6) && digits.bytes().all(|b| b.is_ascii_digit()) {
+                format!("{sign}{}", insert_separators(digits, ':', &[2, 4]))
+            } else {
+                value.to_owned()
+            }
+        }
+        _ => value.to_owned(),
+    }
+}
+
+/// Removes the separators of the extended format again.
+fn basic_format(value: &str) -> String {
+    value.replace(['-', ':'], "")
+}
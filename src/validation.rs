@@ -0,0 +1,498 @@
+//! Conformance checks following [RFC 5545, Section 3.6](https://datatracker.ietf.org/doc/html/rfc5545#section-3.6).
+//!
+//! The builders don't stop anyone from leaving out required properties or combining properties that exclude each other.
+//! [`Calendar::validate`] and [`Component::validate`] report such problems as [`Violation`]s,
+//! each with a [`Severity`] that tells whether the result is merely unusual or actually invalid.
+//! Text to be checked is best read with `Calendar::from_str_lossless`, see [`Calendar::validate`].
+use std::{collections::BTreeSet, fmt};
+
+use chrono::NaiveDate;
+
+use crate::{
+    Calendar, Component, DatePerhapsTime, Parameter, Property, ValueType,
+    components::date_time::{parse_duration, parse_naive_date_time, parse_utc_date_time},
+};
+
+/// How serious a [`Violation`] is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Not conforming, but generated when serializing or tolerated by most consumers.
+    Warning,
+    /// Breaks a MUST of RFC 5545, consumers may reject the calendar.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// A problem found by [`Calendar::validate`] or [`Component::validate`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    /// The affected component, e.g. `VCALENDAR/VEVENT[2]/VALARM[0]`,
+    /// indices counting the components of the parent.
+    pub path: String,
+    /// How serious the problem is.
+    pub severity: Severity,
+    /// What is wrong.
+    pub kind: ViolationKind,
+}
+
+/// The kinds of [`Violation`]s.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ViolationKind {
+    /// A required property is missing.
+    MissingProperty {
+        /// The name of the property.
+        name: String,
+    },
+    /// A property that may occur at most once occurs more often.
+    RepeatedProperty {
+        /// The name of the property.
+        name: String,
+        /// How often it occurs.
+        count: usize,
+    },
+    /// Two properties that exclude each other are both present, like `DTEND` and `DURATION`.
+    ExclusiveProperties {
+        /// The property that takes precedence in the RFC's grammar.
+        first: String,
+        /// The property that may not occur together with `first`.
+        second: String,
+    },
+    /// A property is present without another one it requires, like `REPEAT` without `DURATION`.
+    MissingCompanion {
+        /// The property that is present.
+        present: String,
+        /// The property that is missing.
+        missing: String,
+    },
+    /// A property is a `DATE` where another one is a `DATE-TIME` or vice versa.
+    ValueTypeMismatch {
+        /// The property with the mismatching value type.
+        name: String,
+        /// The property whose value type it has to match, usually `DTSTART`.
+        reference: String,
+    },
+    /// The `VALUE` parameter names a type the property does not allow.
+    DisallowedValueType {
+        /// The name of the property.
+        name: String,
+        /// The value of the `VALUE` parameter.
+        value_type: String,
+    },
+    /// The value can't be read as the type of the property.
+    InvalidValue {
+        /// The name of the property.
+        name: String,
+        /// The type given by the `VALUE` parameter or the default type of the property.
+        value_type: ValueType,
+    },
+    /// A `TZID` parameter refers to a `VTIMEZONE` the calendar does not contain.
+    UnknownTimezone {
+        /// The name of the property.
+        name: String,
+        /// The value of the `TZID` parameter.
+        tzid: String,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.path)?;
+        match &self.kind {
+            ViolationKind::MissingProperty { name } => write!(f, "missing {name}"),
+            ViolationKind::RepeatedProperty { name, count } => {
+                write!(f, "{name} occurs {count} times but is allowed once")
+            }
+            ViolationKind::ExclusiveProperties { first, second } => {
+                write!(f, "{first} and {second} exclude each other")
+            }
+            ViolationKind::MissingCompanion { present, missing } => {
+                write!(f, "{present} requires {missing}")
+            }
+            ViolationKind::ValueTypeMismatch { name, reference } => {
+                write!(f, "{name} must have the same value type as {reference}")
+            }
+            ViolationKind::DisallowedValueType { name, value_type } => {
+                write!(f, "{name} can't be VALUE={value_type}")
+            }
+            ViolationKind::InvalidValue { name, value_type } => {
+                write!(f, "{name} is not a valid {}", value_type.as_str())
+            }
+            ViolationKind::UnknownTimezone { name, tzid } => {
+                write!(f, "{name} refers to missing VTIMEZONE {tzid:?}")
+            }
+        }
+    }
+}
+
+/// Properties required in a component.
+pub(crate) fn required(kind: &str) -> &'static [&'static str] {
+    match kind {
+        "VCALENDAR" => &["PRODID", "VERSION"],
+        "VEVENT" | "VTODO" | "VJOURNAL" | "VFREEBUSY" => &["DTSTAMP", "UID"],
+        "VALARM" => &["ACTION", "TRIGGER"],
+        "VTIMEZONE" => &["TZID"],
+        "STANDARD" | "DAYLIGHT" => &["DTSTART", "TZOFFSETTO", "TZOFFSETFROM"],
+        _ => &[],
+    }
+}
+
+/// Properties that must not occur more than once in a component.
+pub(crate) fn at_most_once(kind: &str) -> &'static [&'static str] {
+    match kind {
+        "VCALENDAR" => &["PRODID", "VERSION", "CALSCALE", "METHOD"],
+        "VEVENT" => &[
+            "DTSTAMP",
+            "UID",
+            "DTSTART",
+            "CLASS",
+            "CREATED",
+            "DESCRIPTION",
+            "GEO",
+            "LAST-MODIFIED",
+            "LOCATION",
+            "ORGANIZER",
+            "PRIORITY",
+            "SEQUENCE",
+            "STATUS",
+            "SUMMARY",
+            "TRANSP",
+            "URL",
+            "RECURRENCE-ID",
+            "DTEND",
+            "DURATION",
+        ],
+        "VTODO" => &[
+            "DTSTAMP",
+            "UID",
+            "CLASS",
+            "COMPLETED",
+            "CREATED",
+            "DESCRIPTION",
+            "DTSTART",
+            "GEO",
+            "LAST-MODIFIED",
+            "LOCATION",
+            "ORGANIZER",
+            "PERCENT-COMPLETE",
+            "PRIORITY",
+            "RECURRENCE-ID",
+            "SEQUENCE",
+            "STATUS",
+            "SUMMARY",
+            "URL",
+            "DUE",
+            "DURATION",
+        ],
+        "VJOURNAL" => &[
+            "DTSTAMP",
+            "UID",
+            "CLASS",
+            "CREATED",
+            "DTSTART",
+            "LAST-MODIFIED",
+            "ORGANIZER",
+            "RECURRENCE-ID",
+            "SEQUENCE",
+            "STATUS",
+            "SUMMARY",
+            "URL",
+        ],
+        "VFREEBUSY" => &[
+            "DTSTAMP",
+            "UID",
+            "CONTACT",
+            "DTSTART",
+            "DTEND",
+            "ORGANIZER",
+            "URL",
+        ],
+        "VALARM" => &[
+            "ACTION",
+            "TRIGGER",
+            "DURATION",
+            "REPEAT",
+            "DESCRIPTION",
+            "SUMMARY",
+        ],
+        "VTIMEZONE" => &["TZID", "LAST-MODIFIED", "TZURL"],
+        "STANDARD" | "DAYLIGHT" => &["DTSTART", "TZOFFSETTO", "TZOFFSETFROM"],
+        _ => &[],
+    }
+}
+
+/// The value types a property allows, the default first.
+fn value_types(name: &str) -> &'static [ValueType] {
+    use ValueType::*;
+    match name {
+        "DTSTART" | "DTEND" | "DUE" | "RECURRENCE-ID" | "EXDATE" => &[DateTime, Date],
+        "RDATE" => &[DateTime, Date, Period],
+        "DTSTAMP" | "CREATED" | "LAST-MODIFIED" | "COMPLETED" => &[DateTime],
+        "DURATION" => &[Duration],
+        "TRIGGER" => &[Duration, DateTime],
+        "PRIORITY" | "SEQUENCE" | "PERCENT-COMPLETE" | "REPEAT" => &[Integer],
+        "TZOFFSETFROM" | "TZOFFSETTO" => &[UtcOffset],
+        _ => &[],
+    }
+}
+
+/// Whether `value` can be read as `value_type`, types without a check are accepted.
+fn is_valid(value_type: ValueType, value: &str) -> bool {
+    match value_type {
+        ValueType::Date => NaiveDate::parse_from_str(value, "%Y%m%d").is_ok() && value.len() == 8,
+        ValueType::DateTime => {
+            parse_utc_date_time(value).is_some() || parse_naive_date_time(value).is_some()
+        }
+        ValueType::Duration => parse_duration(value).is_some(),
+        ValueType::Period => value.split_once('/').is_some_and(|(start, end)| {
+            is_valid(ValueType::DateTime, start)
+                && (is_valid(ValueType::DateTime, end) || is_valid(ValueType::Duration, end))
+        }),
+        ValueType::Integer => value.parse::<i32>().is_ok(),
+        ValueType::UtcOffset => {
+            let digits = value.get(1..).unwrap_or_default();
+            value.starts_with(['+', '-'])
+                && (digits.len() == 4 || digits.len() == 6)
+                && digits.bytes().all(|byte| byte.is_ascii_digit())
+        }
+        _ => true,
+    }
+}
+
+/// Properties whose values are lists.
+fn is_list(name: &str) -> bool {
+    matches!(name, "EXDATE" | "RDATE")
+}
+
+struct Validator {
+    /// The `TZID`s of the calendar's `VTIMEZONE`s, unknown when validating a single component.
+    timezones: Option<BTreeSet<String>>,
+    /// Without `METHOD` events need a `DTSTART`, unknown when validating a single component.
+    without_method: bool,
+    violations: Vec<Violation>,
+}
+
+impl Validator {
+    fn report(&mut self, path: &str, severity: Severity, kind: ViolationKind) {
+        self.violations.push(Violation {
+            path: path.to_owned(),
+            severity,
+            kind,
+        });
+    }
+
+    fn component<C: Component + ?Sized>(&mut self, component: &C, path: &str) {
+        let kind = component.component_kind();
+        let properties = component
+            .properties()
+            .values()
+            .chain(component.multi_properties().values().flatten())
+            .collect::<Vec<_>>();
+        self.properties(&kind, &properties, path);
+
+        for (index, child) in component.components().iter().enumerate() {
+            let child_path = format!("{path}/{}[{index}]", child.component_kind());
+            self.component(child, &child_path);
+        }
+    }
+
+    fn properties(&mut self, kind: &str, properties: &[&Property], path: &str) {
+        let count = |name: &str| {
+            properties
+                .iter()
+                .filter(|property| property.key() == name)
+                .count()
+        };
+        let find = |name: &str| properties.iter().find(|property| property.key() == name);
+
+        let mut required = required(kind).to_vec();
+        if kind == "VEVENT" && self.without_method {
+            required.push("DTSTART");
+        }
+        if kind == "VALARM" {
+            match find("ACTION").map(|action| action.value()) {
+                Some("DISPLAY") => required.push("DESCRIPTION"),
+                Some("EMAIL") => required.extend(["DESCRIPTION", "SUMMARY", "ATTENDEE"]),
+                _ => {}
+            }
+        }
+        // the serializer fills these in, unless the component was read losslessly
+        let generated = kind != "VCALENDAR"
+            && properties
+                .iter()
+                .all(|property| property.position().is_none());
+        for name in required {
+            if count(name) == 0 {
+                let severity = if matches!(name, "DTSTAMP" | "UID") && generated {
+                    Severity::Warning
+                } else {
+                    Severity::Error
+                };
+                let name = name.to_owned();
+                self.report(path, severity, ViolationKind::MissingProperty { name });
+            }
+        }
+
+        for name in at_most_once(kind) {
+            let count = count(name);
+            if count > 1 {
+                let name = (*name).to_owned();
+                self.report(
+                    path,
+                    Severity::Error,
+                    ViolationKind::RepeatedProperty { name, count },
+                );
+            }
+        }
+
+        let exclusive: &[(&str, &str)] = match kind {
+            "VEVENT" => &[("DTEND", "DURATION")],
+            "VTODO" => &[("DUE", "DURATION")],
+            _ => &[],
+        };
+        for (first, second) in exclusive {
+            if count(first) > 0 && count(second) > 0 {
+                let (first, second) = ((*first).to_owned(), (*second).to_owned());
+                self.report(
+                    path,
+                    Severity::Error,
+                    ViolationKind::ExclusiveProperties { first, second },
+                );
+            }
+        }
+
+        let companions: &[(&str, &str)] = match kind {
+            "VALARM" => &[("DURATION", "REPEAT"), ("REPEAT", "DURATION")],
+            "VTODO" => &[("DURATION", "DTSTART")],
+            _ => &[],
+        };
+        for (present, missing) in companions {
+            if count(present) > 0 && count(missing) == 0 {
+                let (present, missing) = ((*present).to_owned(), (*missing).to_owned());
+                self.report(
+                    path,
+                    Severity::Error,
+                    ViolationKind::MissingCompanion { present, missing },
+                );
+            }
+        }
+
+        if let Some(start) = find("DTSTART").and_then(|start| DatePerhapsTime::from_property(start))
+        {
+            for name in ["DTEND", "DUE", "RECURRENCE-ID"] {
+                let other = find(name).and_then(|other| DatePerhapsTime::from_property(other));
+                if other.is_some_and(|other| {
+                    matches!(other, DatePerhapsTime::Date(_))
+                        != matches!(start, DatePerhapsTime::Date(_))
+                }) {
+                    let (name, reference) = (name.to_owned(), "DTSTART".to_owned());
+                    self.report(
+                        path,
+                        Severity::Error,
+                        ViolationKind::ValueTypeMismatch { name, reference },
+                    );
+                }
+            }
+        }
+
+        for property in properties {
+            self.value(property, path);
+        }
+    }
+
+    fn value(&mut self, property: &Property, path: &str) {
+        let name = property.key();
+        if let (Some(timezones), Some(tzid)) = (&self.timezones, property.params().get("TZID"))
+            && !timezones.contains(tzid.value())
+        {
+            let (name, tzid) = (name.to_owned(), tzid.value().to_owned());
+            self.report(
+                path,
+                Severity::Error,
+                ViolationKind::UnknownTimezone { name, tzid },
+            );
+        }
+
+        let allowed = value_types(name);
+        let Some(default) = allowed.first() else {
+            return;
+        };
+        let value_type = match property.params().get("VALUE").map(Parameter::value) {
+            None => *default,
+            Some(extension) if extension.starts_with("X-") => return,
+            Some(value_type) => match value_type.parse::<ValueType>() {
+                Ok(value_type) if allowed.contains(&value_type) => value_type,
+                _ => {
+                    let (name, value_type) = (name.to_owned(), value_type.to_owned());
+                    self.report(
+                        path,
+                        Severity::Error,
+                        ViolationKind::DisallowedValueType { name, value_type },
+                    );
+                    return;
+                }
+            },
+        };
+
+        let values = if is_list(name) {
+            property.value().split(',').collect()
+        } else {
+            vec![property.value()]
+        };
+        if values.iter().any(|value| !is_valid(value_type, value)) {
+            let name = name.to_owned();
+            self.report(
+                path,
+                Severity::Error,
+                ViolationKind::InvalidValue { name, value_type },
+            );
+        }
+    }
+}
+
+/// Validates a single component and its children.
+pub(crate) fn validate_component<C: Component + ?Sized>(component: &C) -> Vec<Violation> {
+    let mut validator = Validator {
+        timezones: None,
+        without_method: false,
+        violations: Vec::new(),
+    };
+    validator.component(component, &component.component_kind());
+    validator.violations
+}
+
+/// Validates a whole calendar, including references between its components.
+pub(crate) fn validate_calendar(calendar: &Calendar) -> Vec<Violation> {
+    let timezones = calendar
+        .components
+        .iter()
+        .filter(|component| component.component_kind() == "VTIMEZONE")
+        .filter_map(|timezone| timezone.property_value("TZID"))
+        .map(ToOwned::to_owned)
+        .collect();
+    let mut validator = Validator {
+        timezones: Some(timezones),
+        without_method: !calendar
+            .properties
+            .iter()
+            .any(|property| property.key() == "METHOD"),
+        violations: Vec::new(),
+    };
+
+    let path = "VCALENDAR";
+    let properties = calendar.properties.iter().collect::<Vec<_>>();
+    validator.properties(path, &properties, path);
+    for (index, component) in calendar.components.iter().enumerate() {
+        let component_path = format!("{path}/{}[{index}]", component.component_kind());
+        validator.component(component, &component_path);
+    }
+    validator.violations
+}
@@ -36,6 +36,26 @@ pub enum ValueType {
 }
 
 impl ValueType {
+    /// The name used in the `VALUE` parameter, e.g. `DATE-TIME`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Binary => "BINARY",
+            Self::Boolean => "BOOLEAN",
+            Self::CalAddress => "CAL-ADDRESS",
+            Self::Date => "DATE",
+            Self::DateTime => "DATE-TIME",
+            Self::Duration => "DURATION",
+            Self::Float => "FLOAT",
+            Self::Integer => "INTEGER",
+            Self::Period => "PERIOD",
+            Self::Recur => "RECUR",
+            Self::Text => "TEXT",
+            Self::Time => "TIME",
+            Self::Uri => "URI",
+            Self::UtcOffset => "UTC-OFFSET",
+        }
+    }
+
     pub(crate) fn by_name(name: &str) -> Option<Self> {
         if name.chars().any(char::is_lowercase) {
             // eprintln!("property_name must be uppercase");
@@ -0,0 +1,448 @@
+//! # vdir storage
+//!
+//! A [vdir](https://vdirsyncer.pimutils.org/en/stable/vdir.html) is a directory with one `.ics` file per item,
+//! the way vdirsyncer and tools like khal store calendars.
+//! Each file holds the components of one `UID`, i.e. [`Calendar::split_by_uid`] produces the contents of the files.
+//!
+//! Files are replaced atomically by writing a hidden temporary file next to them and renaming it.
+//! Every file has an [`Etag`] derived from its modification time and size,
+//! updates and deletions only go through if the file still has the expected one.
+//!
+//! ```
+//! # use icalendar::{*, vdir::Vdir};
+//! # let path = std::env::temp_dir().join(format!("icalendar-vdir-doc-{}", std::process::id()));
+//! let vdir = Vdir::create(&path).unwrap();
+//!
+//! let mut item = Calendar::new();
+//! item.push(Event::with_uid("standup").summary("Standup").done());
+//! let stored = vdir.create_item(&item).unwrap();
+//! assert_eq!(stored.href, "standup.ics");
+//!
+//! let (_, mut item) = vdir.get("standup").unwrap().unwrap();
+//! item.events_mut().next().unwrap().summary("Daily standup");
+//! vdir.update("standup", &item, &stored.etag).unwrap();
+//!
+//! let calendar = vdir.load().unwrap();
+//! assert_eq!(calendar.events().next().unwrap().get_summary(), Some("Daily standup"));
+//! # std::fs::remove_dir_all(&path).unwrap();
+//! ```
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use uuid::Uuid;
+
+use crate::{Calendar, CalendarComponent, Component, diff, parser::ParseError};
+
+/// The extension of item files.
+const EXTENSION: &str = "ics";
+
+/// File names longer than this are replaced by a random one.
+const MAX_HREF_LENGTH: usize = 200;
+
+/// Identifies a version of a file, derived from its modification time and size.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Etag(String);
+
+impl Etag {
+    fn of(metadata: &fs::Metadata) -> io::Result<Self> {
+        let modified = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        Ok(Etag(format!(
+            "{}.{:09}-{}",
+            modified.as_secs(),
+            modified.subsec_nanos(),
+            metadata.len()
+        )))
+    }
+
+    /// The etag as text.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Etag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A file of a [`Vdir`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Item {
+    /// The file name, relative to the directory.
+    pub href: String,
+    /// The version of the file.
+    pub etag: Etag,
+}
+
+/// How the files of a [`Vdir`] differ from a previous listing, see [`Vdir::changes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ItemChange {
+    /// The file is new.
+    Added(Item),
+    /// The file has a different etag.
+    Modified(Item),
+    /// The file with this href is gone.
+    Removed(String),
+}
+
+/// Errors of [`Vdir`] operations.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum VdirError {
+    /// Accessing the file system failed.
+    Io(io::Error),
+    /// A file isn't a valid calendar.
+    Parse {
+        /// The file name.
+        href: String,
+        /// What is wrong with it.
+        error: ParseError,
+    },
+    /// An item to be stored has a component without `UID`.
+    MissingUid,
+    /// An item to be stored contains more than one `UID`.
+    MultipleUids,
+    /// An item with the `UID` exists already.
+    Exists(String),
+    /// There is no item with the `UID`.
+    NotFound(String),
+    /// The item with the `UID` was modified since the given etag.
+    Conflict(String),
+}
+
+impl fmt::Display for VdirError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VdirError::Io(error) => write!(f, "failed to access vdir: {error}"),
+            VdirError::Parse { href, error } => write!(f, "{href}: {error}"),
+            VdirError::MissingUid => write!(f, "item has a component without UID"),
+            VdirError::MultipleUids => write!(f, "item contains more than one UID"),
+            VdirError::Exists(uid) => write!(f, "item {uid} exists already"),
+            VdirError::NotFound(uid) => write!(f, "item {uid} does not exist"),
+            VdirError::Conflict(uid) => write!(f, "item {uid} was modified in the meantime"),
+        }
+    }
+}
+
+impl std::error::Error for VdirError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            VdirError::Io(error) => Some(error),
+            VdirError::Parse { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for VdirError {
+    fn from(error: io::Error) -> Self {
+        VdirError::Io(error)
+    }
+}
+
+/// A directory of `.ics` files, one per `UID`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Vdir {
+    path: PathBuf,
+}
+
+/// The file name for items with `uid`, the `UID` itself with anything but safe characters percent-encoded.
+fn href_for(uid: &str) -> String {
+    let mut href = String::with_capacity(uid.len() + EXTENSION.len() + 1);
+    for (index, byte) in uid.bytes().enumerate() {
+        let safe = byte.is_ascii_alphanumeric()
+            || matches!(byte, b'-' | b'_' | b'+' | b'@')
+            || (byte == b'.' && index > 0);
+        if safe {
+            href.push(char::from(byte));
+        } else {
+            href.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    if href.is_empty() || href.len() > MAX_HREF_LENGTH {
+        href = Uuid::new_v4().to_string();
+    }
+    href.push('.');
+    href.push_str(EXTENSION);
+    href
+}
+
+/// The only `UID` of `item`, ignoring its `VTIMEZONE`s.
+fn single_uid(item: &Calendar) -> Result<&str, VdirError> {
+    let mut uids = item
+        .components
+        .iter()
+        .filter(|component| component.component_kind() != "VTIMEZONE")
+        .map(CalendarComponent::get_uid);
+    let uid = uids.next().flatten().ok_or(VdirError::MissingUid)?;
+    for other in uids {
+        match other {
+            None => return Err(VdirError::MissingUid),
+            Some(other) if other != uid => return Err(VdirError::MultipleUids),
+            Some(_) => {}
+        }
+    }
+    Ok(uid)
+}
+
+impl Vdir {
+    /// Opens the existing directory at `path`.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, VdirError> {
+        let path = path.into();
+        if !fs::metadata(&path)?.is_dir() {
+            let message = format!("{} is not a directory", path.display());
+            return Err(io::Error::new(io::ErrorKind::NotADirectory, message).into());
+        }
+        Ok(Vdir { path })
+    }
+
+    /// Opens the directory at `path`, creating it if necessary.
+    pub fn create(path: impl Into<PathBuf>) -> Result<Self, VdirError> {
+        let path = path.into();
+        fs::create_dir_all(&path)?;
+        Self::open(path)
+    }
+
+    /// The directory.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// All items, ordered by their file names.
+    ///
+    /// Only reads the metadata of the files, hidden files and files without `.ics` extension are ignored.
+    pub fn items(&self) -> Result<Vec<Item>, VdirError> {
+        let mut items = Vec::new();
+        for entry in fs::read_dir(&self.path)? {
+            let entry = entry?;
+            let Ok(href) = entry.file_name().into_string() else {
+                continue;
+            };
+            let is_item = !href.starts_with('.')
+                && Path::new(&href)
+                    .extension()
+                    .is_some_and(|extension| extension == EXTENSION);
+            let metadata = entry.metadata()?;
+            if is_item && metadata.is_file() {
+                let etag = Etag::of(&metadata)?;
+                items.push(Item { href, etag });
+            }
+        }
+        items.sort_by(|a, b| a.href.cmp(&b.href));
+        Ok(items)
+    }
+
+    /// Compares the items to those of a previous call to [`items`](Vdir::items).
+    pub fn changes(&self, known: &[Item]) -> Result<Vec<ItemChange>, VdirError> {
+        let known = known
+            .iter()
+            .map(|item| (item.href.as_str(), &item.etag))
+            .collect::<BTreeMap<_, _>>();
+        let items = self.items()?;
+        let mut changes = Vec::new();
+        for item in &items {
+            match known.get(item.href.as_str()) {
+                None => changes.push(ItemChange::Added(item.clone())),
+                Some(etag) if **etag != item.etag => {
+                    changes.push(ItemChange::Modified(item.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+        for href in known.keys() {
+            if !items.iter().any(|item| item.href == *href) {
+                changes.push(ItemChange::Removed((*href).to_owned()));
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Reads the file `href`.
+    pub fn read(&self, href: &str) -> Result<Calendar, VdirError> {
+        let content = fs::read_to_string(self.path.join(href))?;
+        content.parse().map_err(|error| VdirError::Parse {
+            href: href.to_owned(),
+            error,
+        })
+    }
+
+    /// Reads all items into one calendar, see [`Calendar::join`].
+    pub fn load(&self) -> Result<Calendar, VdirError> {
+        let items = self
+            .items()?
+            .iter()
+            .map(|item| self.read(&item.href))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Calendar::join(items))
+    }
+
+    /// The item with the `UID` `uid` and its content.
+    ///
+    /// Looks at the file named after the `UID` first, then at all other files.
+    pub fn get(&self, uid: &str) -> Result<Option<(Item, Calendar)>, VdirError> {
+        let href = href_for(uid);
+        let items = self.items()?;
+        let (named, others): (Vec<_>, Vec<_>) =
+            items.into_iter().partition(|item| item.href == href);
+        for item in named.into_iter().chain(others) {
+            let content = self.read(&item.href)?;
+            if content.find_by_uid(uid).next().is_some() {
+                return Ok(Some((item, content)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Stores a new item, which must contain exactly one `UID`, in a file named after it.
+    pub fn create_item(&self, item: &Calendar) -> Result<Item, VdirError> {
+        let uid = single_uid(item)?;
+        if self.get(uid)?.is_some() {
+            return Err(VdirError::Exists(uid.to_owned()));
+        }
+        let mut href = href_for(uid);
+        if self.path.join(&href).exists() {
+            // taken by an item with another UID
+            href = href_for("");
+        }
+        self.write_new(&href, item)
+            .map_err(|error| match error.kind() {
+                // created by someone else since we looked
+                io::ErrorKind::AlreadyExists => VdirError::Exists(uid.to_owned()),
+                _ => error.into(),
+            })
+    }
+
+    /// Replaces the item with the `UID` `uid`, unless it was modified since `etag`.
+    pub fn update(&self, uid: &str, item: &Calendar, etag: &Etag) -> Result<Item, VdirError> {
+        if single_uid(item)? != uid {
+            return Err(VdirError::MultipleUids);
+        }
+        let (existing, _) = self
+            .get(uid)?
+            .ok_or_else(|| VdirError::NotFound(uid.to_owned()))?;
+        if existing.etag != *etag {
+            return Err(VdirError::Conflict(uid.to_owned()));
+        }
+        self.write(&existing.href, item)
+    }
+
+    /// Removes the item with the `UID` `uid`, unless it was modified since `etag`.
+    pub fn delete(&self, uid: &str, etag: &Etag) -> Result<(), VdirError> {
+        let (existing, _) = self
+            .get(uid)?
+            .ok_or_else(|| VdirError::NotFound(uid.to_owned()))?;
+        if existing.etag != *etag {
+            return Err(VdirError::Conflict(uid.to_owned()));
+        }
+        fs::remove_file(self.path.join(existing.href))?;
+        Ok(())
+    }
+
+    /// Makes the directory hold the items of `calendar`, see [`Calendar::split_by_uid`].
+    ///
+    /// Only items whose content changed are written, items with a `UID` the calendar doesn't contain are removed.
+    /// Files holding several `UID`s are split into one file per item, they are only removed once all of them are written.
+    /// Components without `UID` can't be stored.
+    pub fn store(&self, calendar: &Calendar) -> Result<Vec<ItemChange>, VdirError> {
+        let resources = calendar.split_by_uid();
+        let mut uids = Vec::with_capacity(resources.len());
+        for resource in &resources {
+            uids.push(single_uid(resource)?);
+        }
+
+        // the files that hold exactly one UID, only those are updated in place
+        let mut files = BTreeSet::new();
+        let mut in_place = BTreeMap::new();
+        for item in self.items()? {
+            let content = self.read(&item.href)?;
+            let mut held = content
+                .components
+                .iter()
+                .filter_map(CalendarComponent::get_uid)
+                .map(ToOwned::to_owned)
+                .collect::<Vec<_>>();
+            held.sort();
+            held.dedup();
+            if !held.is_empty() {
+                files.insert(item.href.clone());
+            }
+            if let [uid] = held.as_slice() {
+                in_place.entry(uid.clone()).or_insert((item, content));
+            }
+        }
+
+        let mut changes = Vec::new();
+        let mut kept = BTreeSet::new();
+        for (uid, resource) in uids.iter().zip(&resources) {
+            if let Some((item, content)) = in_place.remove(*uid) {
+                if !diff::diff(&content, resource).is_empty() {
+                    changes.push(ItemChange::Modified(self.write(&item.href, resource)?));
+                }
+                kept.insert(item.href);
+                continue;
+            }
+            let mut href = href_for(uid);
+            if files.contains(&href) || self.path.join(&href).exists() {
+                // taken by another item, or by a file that is split up
+                href = href_for("");
+            }
+            let item = self.write(&href, resource)?;
+            kept.insert(item.href.clone());
+            changes.push(ItemChange::Added(item));
+        }
+
+        // every item is written by now, so the other files hold nothing that is kept
+        for href in files {
+            if !kept.contains(&href) {
+                fs::remove_file(self.path.join(&href))?;
+                changes.push(ItemChange::Removed(href));
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Replaces the file `href` with `item` by renaming a temporary file onto it.
+    fn write(&self, href: &str, item: &Calendar) -> Result<Item, VdirError> {
+        Ok(self.place(href, item, true)?)
+    }
+
+    /// Creates the file `href` with `item` by linking a temporary file to it,
+    /// which fails with [`io::ErrorKind::AlreadyExists`] instead of replacing a file created meanwhile.
+    fn write_new(&self, href: &str, item: &Calendar) -> io::Result<Item> {
+        self.place(href, item, false)
+    }
+
+    fn place(&self, href: &str, item: &Calendar, replace: bool) -> io::Result<Item> {
+        let temporary = self.path.join(format!(".{href}.{}.tmp", Uuid::new_v4()));
+        let target = self.path.join(href);
+        let written = (|| {
+            let mut file = fs::File::create_new(&temporary)?;
+            item.write_to(&mut file)?;
+            file.flush()?;
+            file.sync_all()?;
+            if replace {
+                fs::rename(&temporary, &target)
+            } else {
+                fs::hard_link(&temporary, &target)
+            }
+        })();
+        if written.is_err() || !replace {
+            let _ = fs::remove_file(&temporary);
+        }
+        written?;
+        let etag = Etag::of(&fs::metadata(&target)?)?;
+        Ok(Item {
+            href: href.to_owned(),
+            etag,
+        })
+    }
+}
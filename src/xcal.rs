@@ -0,0 +1,433 @@
+//! # xCal
+//!
+//! Converts calendars to and from [xCal (RFC 6321)](https://datatracker.ietf.org/doc/html/rfc6321),
+//! the XML representation of iCalendar.
+//!
+//! Components, properties and parameters become elements with lowercase names in the
+//! `urn:ietf:params:xml:ns:icalendar-2.0` namespace, and every value is wrapped in an element naming its type,
+//! like `<date-time>`, `<text>` or `<recur>`. Values are typed the same way as in [`jcal`](crate::jcal),
+//! so the text format, jCal and xCal all describe the same calendar.
+//!
+//! ```
+//! # use icalendar::{*, xcal};
+//! let calendar = Calendar::new()
+//!     .push(
+//!         Event::with_uid("weekly@example.com")
+//!             .summary("Standup & Coffee")
+//!             .add_property("RRULE", "FREQ=WEEKLY;BYDAY=MO,WE")
+//!             .done(),
+//!     )
+//!     .done();
+//!
+//! let xml = xcal::to_string(&calendar);
+//! assert!(xml.contains("<summary><text>Standup &amp; Coffee</text></summary>"));
+//! assert!(xml.contains("<recur><freq>WEEKLY</freq><byday>MO</byday><byday>WE</byday></recur>"));
+//!
+//! assert_eq!(xcal::from_str(&xml).unwrap(), calendar);
+//! ```
+use std::{fmt, str::FromStr};
+
+use quick_xml::{
+    NsReader,
+    escape::escape,
+    events::Event,
+    name::{Namespace, ResolveResult},
+};
+
+use crate::{
+    Calendar, ValueType,
+    parser::{self, Property, components::LikeComponent},
+    typed_value::{TypedProperty, TypedValue},
+};
+
+/// The XML namespace of xCal elements.
+pub const NAMESPACE: &str = "urn:ietf:params:xml:ns:icalendar-2.0";
+
+/// Reasons why an xCal document can't be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum XcalError {
+    /// The input is not well-formed XML.
+    Xml(String),
+    /// The XML does not have the shape of an xCal document.
+    Malformed(&'static str),
+}
+
+impl fmt::Display for XcalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XcalError::Xml(reason) => write!(f, "invalid XML: {reason}"),
+            XcalError::Malformed(reason) => write!(f, "invalid xCal: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for XcalError {}
+
+/// Converts a calendar into an xCal document.
+///
+/// Unlike the text form, missing `DTSTAMP`s and `UID`s are not filled in.
+pub fn to_string(calendar: &Calendar) -> String {
+    encode(&parser::Calendar::from(calendar.clone()))
+}
+
+/// Reads a calendar from an xCal document.
+pub fn from_str(xml: &str) -> Result<Calendar, XcalError> {
+    decode(xml).map(Into::into)
+}
+
+/// Converts a parsed calendar into an xCal document, keeping the order of everything.
+pub fn encode(calendar: &parser::Calendar<'_>) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>");
+    out.push_str(&format!("<icalendar xmlns=\"{NAMESPACE}\">"));
+    encode_component(&mut out, calendar);
+    out.push_str("</icalendar>");
+    out
+}
+
+/// Reads an xCal document into a calendar that can be written in the text format.
+///
+/// Elements of other namespaces are ignored, only the first `vcalendar` is read.
+pub fn decode(xml: &str) -> Result<parser::Calendar<'static>, XcalError> {
+    let root = Element::parse(xml)?;
+    if root.name != "icalendar" {
+        return Err(XcalError::Malformed("the root element must be icalendar"));
+    }
+    let calendar = root
+        .children()
+        .find(|child| child.name == "vcalendar")
+        .ok_or(XcalError::Malformed("icalendar contains no vcalendar"))?;
+    let (properties, components) = decode_component(calendar)?;
+    Ok(parser::Calendar {
+        properties,
+        components,
+    })
+}
+
+/// The type of a parameter value, most are text.
+fn parameter_type(name: &str) -> &'static str {
+    match name {
+        "ALTREP" | "DIR" => "uri",
+        "DELEGATED-FROM" | "DELEGATED-TO" | "MEMBER" | "SENT-BY" => "cal-address",
+        _ => "text",
+    }
+}
+
+/// Names of the parts of structured values.
+fn structured_parts(property: &str) -> &'static [&'static str] {
+    match property {
+        "GEO" => &["latitude", "longitude"],
+        "REQUEST-STATUS" => &["code", "description", "data"],
+        _ => &[],
+    }
+}
+
+fn element(out: &mut String, name: &str, content: impl FnOnce(&mut String)) {
+    out.push('<');
+    out.push_str(name);
+    out.push('>');
+    content(out);
+    out.push_str("</");
+    out.push_str(name);
+    out.push('>');
+}
+
+fn text_element(out: &mut String, name: &str, text: &str) {
+    element(out, name, |out| out.push_str(&escape(text)));
+}
+
+fn encode_component<'a>(out: &mut String, component: &impl LikeComponent<'a>) {
+    element(out, &component.name().to_ascii_lowercase(), |out| {
+        if !component.properties().is_empty() {
+            element(out, "properties", |out| {
+                for property in component.properties() {
+                    encode_property(out, TypedProperty::from_property(property));
+                }
+            });
+        }
+        if !component.components().is_empty() {
+            element(out, "components", |out| {
+                for child in component.components() {
+                    encode_component(out, child);
+                }
+            });
+        }
+    });
+}
+
+fn encode_property(out: &mut String, property: TypedProperty) {
+    let value_type = property.value_type.map_or("unknown".into(), |value_type| {
+        value_type.as_str().to_ascii_lowercase()
+    });
+    let parts = structured_parts(&property.name);
+
+    element(out, &property.name.to_ascii_lowercase(), |out| {
+        if !property.params.is_empty() {
+            element(out, "parameters", |out| {
+                for (key, values) in &property.params {
+                    element(out, &key.to_ascii_lowercase(), |out| {
+                        for value in values {
+                            text_element(out, parameter_type(key), value);
+                        }
+                    });
+                }
+            });
+        }
+        for value in &property.values {
+            encode_value(out, &value_type, parts, value);
+        }
+    });
+}
+
+fn encode_value(out: &mut String, value_type: &str, parts: &[&str], value: &TypedValue) {
+    match value {
+        TypedValue::Boolean(boolean) => text_element(out, value_type, &boolean.to_string()),
+        TypedValue::Integer(integer) => text_element(out, value_type, &integer.to_string()),
+        TypedValue::Float(float) => text_element(out, value_type, &float.to_string()),
+        TypedValue::Text(text) => text_element(out, value_type, text),
+        TypedValue::Period { start, end } => element(out, "period", |out| {
+            text_element(out, "start", start);
+            if end.starts_with(['P', '+', '-']) {
+                text_element(out, "duration", end);
+            } else {
+                text_element(out, "end", end);
+            }
+        }),
+        TypedValue::Recur(rule_parts) => element(out, "recur", |out| {
+            for (name, values) in rule_parts {
+                for value in values {
+                    text_element(out, name, &value.to_text(None));
+                }
+            }
+        }),
+        TypedValue::Structured(values) => {
+            if parts.is_empty() {
+                for value in values {
+                    encode_value(out, value_type, parts, value);
+                }
+            } else {
+                for (part, value) in parts.iter().zip(values) {
+                    text_element(out, part, &value.to_text(None));
+                }
+            }
+        }
+    }
+}
+
+/// A minimal XML tree with the elements of the xCal namespace.
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    fn parse(xml: &str) -> Result<Self, XcalError> {
+        let xml_error = |error: quick_xml::Error| XcalError::Xml(error.to_string());
+        let mut reader = NsReader::from_str(xml);
+        // open elements, `None` for those of other namespaces which are skipped with their content
+        let mut stack: Vec<Option<Element>> = Vec::new();
+        let mut root = None;
+
+        loop {
+            let (namespace, event) = reader.read_resolved_event().map_err(xml_error)?;
+            let in_namespace = matches!(namespace, ResolveResult::Bound(Namespace(ns)) if ns == NAMESPACE.as_bytes());
+            let skipping = stack.iter().any(Option::is_none);
+            match event {
+                Event::Start(start) => {
+                    let element = (in_namespace && !skipping).then(|| Element {
+                        name: String::from_utf8_lossy(start.local_name().as_ref()).into_owned(),
+                        ..Default::default()
+                    });
+                    stack.push(element);
+                }
+                Event::Empty(empty) if in_namespace && !skipping => {
+                    let element = Element {
+                        name: String::from_utf8_lossy(empty.local_name().as_ref()).into_owned(),
+                        ..Default::default()
+                    };
+                    match stack.last_mut() {
+                        Some(Some(parent)) => parent.children.push(element),
+                        _ => root = root.or(Some(element)),
+                    }
+                }
+                Event::End(_) => {
+                    let Some(closed) = stack.pop() else {
+                        return Err(XcalError::Malformed("unbalanced elements"));
+                    };
+                    if let Some(element) = closed {
+                        match stack.last_mut() {
+                            Some(Some(parent)) => parent.children.push(element),
+                            Some(None) => {}
+                            None => root = root.or(Some(element)),
+                        }
+                    }
+                }
+                Event::Text(text) => {
+                    if let Some(Some(element)) = stack.last_mut() {
+                        let text = text
+                            .decode()
+                            .map_err(|error| XcalError::Xml(error.to_string()))?;
+                        element.text.push_str(&text);
+                    }
+                }
+                Event::CData(data) => {
+                    if let Some(Some(element)) = stack.last_mut() {
+                        let data = data
+                            .decode()
+                            .map_err(|error| XcalError::Xml(error.to_string()))?;
+                        element.text.push_str(&data);
+                    }
+                }
+                Event::GeneralRef(reference) => {
+                    if let Some(Some(element)) = stack.last_mut() {
+                        let resolved = match reference.resolve_char_ref().map_err(xml_error)? {
+                            Some(c) => c,
+                            None => match reference.as_ref() {
+                                b"amp" => '&',
+                                b"lt" => '<',
+                                b"gt" => '>',
+                                b"quot" => '"',
+                                b"apos" => '\'',
+                                _ => return Err(XcalError::Malformed("unknown entity reference")),
+                            },
+                        };
+                        element.text.push(resolved);
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        root.ok_or(XcalError::Malformed("no element of the xCal namespace"))
+    }
+
+    fn children(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter()
+    }
+
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children().find(|child| child.name == name)
+    }
+}
+
+type DecodedComponent = (Vec<Property<'static>>, Vec<parser::Component<'static>>);
+
+fn decode_component(element: &Element) -> Result<DecodedComponent, XcalError> {
+    let properties = element
+        .child("properties")
+        .into_iter()
+        .flat_map(Element::children)
+        .map(|property| decode_property(property).map(TypedProperty::into_property))
+        .collect::<Result<_, _>>()?;
+    let components = element
+        .child("components")
+        .into_iter()
+        .flat_map(Element::children)
+        .map(|component| {
+            let (properties, components) = decode_component(component)?;
+            Ok(parser::Component {
+                name: component.name.to_ascii_uppercase().into(),
+                properties,
+                components,
+            })
+        })
+        .collect::<Result<_, XcalError>>()?;
+    Ok((properties, components))
+}
+
+fn decode_property(element: &Element) -> Result<TypedProperty, XcalError> {
+    let name = element.name.to_ascii_uppercase();
+
+    let params = element
+        .child("parameters")
+        .into_iter()
+        .flat_map(Element::children)
+        .map(|param| {
+            let values = param
+                .children()
+                .map(|value| value.text.clone())
+                .collect::<Vec<_>>();
+            (param.name.to_ascii_uppercase(), values)
+        })
+        .collect();
+
+    let value_elements = element
+        .children()
+        .filter(|child| child.name != "parameters")
+        .collect::<Vec<_>>();
+    let parts = structured_parts(&name);
+
+    let (value_type, values) = if !parts.is_empty() {
+        let value_type = ValueType::by_name(&name);
+        let structured = value_elements
+            .iter()
+            .map(|part| match value_type {
+                Some(ValueType::Float) => part
+                    .text
+                    .parse()
+                    .map_or_else(|_| TypedValue::Text(part.text.clone()), TypedValue::Float),
+                _ => TypedValue::Text(part.text.clone()),
+            })
+            .collect();
+        (value_type, vec![TypedValue::Structured(structured)])
+    } else {
+        let first = value_elements
+            .first()
+            .ok_or(XcalError::Malformed("a property needs a value"))?;
+        let value_type = ValueType::from_str(&first.name.to_ascii_uppercase()).ok();
+        let values = value_elements
+            .iter()
+            .map(|value| decode_value(value_type, value))
+            .collect();
+        (value_type, values)
+    };
+
+    Ok(TypedProperty {
+        name,
+        params,
+        value_type,
+        values,
+    })
+}
+
+fn decode_value(value_type: Option<ValueType>, element: &Element) -> TypedValue {
+    let text = || element.text.clone();
+    match value_type {
+        Some(ValueType::Boolean) => match element.text.trim() {
+            "true" | "1" => TypedValue::Boolean(true),
+            "false" | "0" => TypedValue::Boolean(false),
+            _ => TypedValue::Text(text()),
+        },
+        Some(ValueType::Integer) => element
+            .text
+            .trim()
+            .parse()
+            .map_or_else(|_| TypedValue::Text(text()), TypedValue::Integer),
+        Some(ValueType::Float) => element
+            .text
+            .trim()
+            .parse()
+            .map_or_else(|_| TypedValue::Text(text()), TypedValue::Float),
+        Some(ValueType::Period) => {
+            let part = |name| element.child(name).map(|part| part.text.clone());
+            match (part("start"), part("end").or_else(|| part("duration"))) {
+                (Some(start), Some(end)) => TypedValue::Period { start, end },
+                _ => TypedValue::Text(text()),
+            }
+        }
+        Some(ValueType::Recur) => TypedValue::Recur(element.children().fold(
+            Vec::<(String, Vec<TypedValue>)>::new(),
+            |mut parts, part| {
+                let value = TypedValue::Text(part.text.clone());
+                match parts.iter_mut().find(|(name, _)| *name == part.name) {
+                    Some((_, values)) => values.push(value),
+                    None => parts.push((part.name.clone(), vec![value])),
+                }
+                parts
+            },
+        )),
+        _ => TypedValue::Text(text()),
+    }
+}
@@ -0,0 +1,296 @@
+#![cfg(all(feature = "recurrence", feature = "parser"))]
+use chrono::{DateTime, TimeZone, Utc};
+use icalendar::{
+    TimeRange,
+    caldav::{
+        Collation, CompFilter, CompSelector, FilterError, ParamFilter, PropFilter, TextMatch,
+    },
+    parser::{Calendar, read_calendar, unfold},
+};
+
+fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+}
+
+/// A weekly meeting with a reminder, organised by Bob with Alice attending.
+const MEETING: &str = r#"BEGIN:VCALENDAR
+VERSION:2.0
+PRODID:-//test//test//EN
+BEGIN:VEVENT
+UID:meeting@example.com
+DTSTAMP:20260301T120000Z
+DTSTART:20260302T090000Z
+DTEND:20260302T100000Z
+RRULE:FREQ=WEEKLY;COUNT=4
+SUMMARY:Straßenfest Planning
+CATEGORIES:WORK
+ORGANIZER;CN=Bob:mailto:bob@example.com
+ATTENDEE;PARTSTAT=ACCEPTED;CN=Alice:mailto:alice@example.com
+BEGIN:VALARM
+ACTION:DISPLAY
+DESCRIPTION:Reminder
+TRIGGER:-PT10M
+END:VALARM
+END:VEVENT
+END:VCALENDAR
+"#;
+
+/// A todo without any dates.
+const CHORE: &str = r#"BEGIN:VCALENDAR
+VERSION:2.0
+PRODID:-//test//test//EN
+BEGIN:VTODO
+UID:chore@example.com
+DTSTAMP:20260301T120000Z
+SUMMARY:Take out the trash
+STATUS:NEEDS-ACTION
+END:VTODO
+END:VCALENDAR
+"#;
+
+fn with_calendar<T>(ics: &str, f: impl FnOnce(&Calendar) -> T) -> T {
+    let unfolded = unfold(ics);
+    f(&read_calendar(&unfolded).unwrap())
+}
+
+fn events() -> CompFilter {
+    CompFilter::new("VCALENDAR").comp_filter(CompFilter::new("VEVENT"))
+}
+
+#[test]
+fn comp_filter_by_name() {
+    with_calendar(MEETING, |calendar| {
+        assert!(CompFilter::new("VCALENDAR").matches(calendar));
+        assert!(events().matches(calendar));
+        assert!(
+            !CompFilter::new("VCALENDAR")
+                .comp_filter(CompFilter::new("VTODO"))
+                .matches(calendar)
+        );
+        assert!(
+            CompFilter::new("VCALENDAR")
+                .comp_filter(CompFilter::new("VTODO").is_not_defined())
+                .matches(calendar)
+        );
+        // the outermost filter has to describe the calendar
+        assert!(!CompFilter::new("VEVENT").matches(calendar));
+    });
+    with_calendar(CHORE, |calendar| assert!(!events().matches(calendar)));
+}
+
+#[test]
+fn comp_filter_time_range_expands_recurrences() {
+    with_calendar(MEETING, |calendar| {
+        let in_range = |range| {
+            CompFilter::new("VCALENDAR")
+                .comp_filter(CompFilter::new("VEVENT").time_range(range))
+                .matches(calendar)
+        };
+
+        // third instance
+        assert!(in_range(TimeRange::new(
+            utc(2026, 3, 16, 9, 30),
+            utc(2026, 3, 16, 9, 45)
+        )));
+        // between two instances
+        assert!(!in_range(TimeRange::new(
+            utc(2026, 3, 10, 0, 0),
+            utc(2026, 3, 11, 0, 0)
+        )));
+        // after the last instance
+        assert!(!in_range(TimeRange::starting_at(utc(2026, 3, 23, 10, 0))));
+    });
+}
+
+#[test]
+fn alarm_time_range_uses_parent() {
+    with_calendar(MEETING, |calendar| {
+        let alarm_in = |range| {
+            CompFilter::new("VCALENDAR")
+                .comp_filter(
+                    CompFilter::new("VEVENT")
+                        .comp_filter(CompFilter::new("VALARM").time_range(range)),
+                )
+                .matches(calendar)
+        };
+
+        assert!(alarm_in(TimeRange::new(
+            utc(2026, 3, 9, 8, 45),
+            utc(2026, 3, 9, 8, 55)
+        )));
+        assert!(!alarm_in(TimeRange::new(
+            utc(2026, 3, 9, 8, 51),
+            utc(2026, 3, 9, 9, 0)
+        )));
+    });
+}
+
+#[test]
+fn prop_filter_text_match() {
+    with_calendar(MEETING, |calendar| {
+        let summary = |text_match| {
+            CompFilter::new("VCALENDAR")
+                .comp_filter(
+                    CompFilter::new("VEVENT")
+                        .prop_filter(PropFilter::new("SUMMARY").text_match(text_match)),
+                )
+                .matches(calendar)
+        };
+
+        assert!(summary(TextMatch::new("planning")));
+        assert!(!summary(
+            TextMatch::new("planning").collation(Collation::Octet)
+        ));
+        assert!(summary(
+            TextMatch::new("Planning").collation(Collation::Octet)
+        ));
+        assert!(!summary(TextMatch::new("planning").negate()));
+        assert!(summary(TextMatch::new("retro").negate()));
+
+        // only the unicode collation folds non-ASCII letters
+        assert!(!summary(TextMatch::new("STRASSENFEST")));
+        assert!(summary(
+            TextMatch::new("STRASSENFEST").collation(Collation::UnicodeCasemap)
+        ));
+    });
+}
+
+#[test]
+fn prop_filter_defined_and_not_defined() {
+    with_calendar(CHORE, |calendar| {
+        let todo = |filter| {
+            CompFilter::new("VCALENDAR")
+                .comp_filter(CompFilter::new("VTODO").prop_filter(filter))
+                .matches(calendar)
+        };
+
+        assert!(todo(PropFilter::new("STATUS")));
+        assert!(todo(PropFilter::new("status")));
+        assert!(!todo(PropFilter::new("COMPLETED")));
+        assert!(todo(PropFilter::new("COMPLETED").is_not_defined()));
+        assert!(!todo(PropFilter::new("STATUS").is_not_defined()));
+    });
+}
+
+#[test]
+fn prop_filter_time_range() {
+    with_calendar(MEETING, |calendar| {
+        let stamped = |range| {
+            CompFilter::new("VCALENDAR")
+                .comp_filter(
+                    CompFilter::new("VEVENT")
+                        .prop_filter(PropFilter::new("DTSTAMP").time_range(range)),
+                )
+                .matches(calendar)
+        };
+
+        assert!(stamped(TimeRange::new(
+            utc(2026, 3, 1, 0, 0),
+            utc(2026, 3, 2, 0, 0)
+        )));
+        assert!(!stamped(TimeRange::starting_at(utc(2026, 3, 1, 12, 1))));
+    });
+}
+
+#[test]
+fn param_filter() {
+    with_calendar(MEETING, |calendar| {
+        let attendee = |filter| {
+            CompFilter::new("VCALENDAR")
+                .comp_filter(
+                    CompFilter::new("VEVENT")
+                        .prop_filter(PropFilter::new("ATTENDEE").param_filter(filter)),
+                )
+                .matches(calendar)
+        };
+
+        assert!(attendee(ParamFilter::new("PARTSTAT")));
+        assert!(attendee(
+            ParamFilter::new("PARTSTAT").text_match(TextMatch::new("accepted"))
+        ));
+        assert!(!attendee(
+            ParamFilter::new("PARTSTAT").text_match(TextMatch::new("declined"))
+        ));
+        assert!(attendee(ParamFilter::new("RSVP").is_not_defined()));
+        assert!(!attendee(ParamFilter::new("CN").is_not_defined()));
+    });
+}
+
+#[test]
+fn all_conditions_apply_to_the_same_component() {
+    with_calendar(MEETING, |calendar| {
+        let filter = CompFilter::new("VCALENDAR").comp_filter(
+            CompFilter::new("VEVENT")
+                .prop_filter(PropFilter::new("CATEGORIES").text_match(TextMatch::new("work")))
+                .prop_filter(PropFilter::new("SUMMARY").text_match(TextMatch::new("trash"))),
+        );
+        assert!(!filter.matches(calendar));
+    });
+}
+
+#[test]
+fn unknown_collations_are_rejected() {
+    assert_eq!("i;octet".parse(), Ok(Collation::Octet));
+    assert_eq!(
+        "i;ascii-casemap".parse::<Collation>().map(|c| c.as_str()),
+        Ok("i;ascii-casemap")
+    );
+    assert_eq!(
+        "i;basic".parse::<Collation>(),
+        Err(FilterError::UnsupportedCollation("i;basic".into()))
+    );
+}
+
+#[test]
+fn calendar_data_selects_parts() {
+    with_calendar(MEETING, |calendar| {
+        let selector = CompSelector::new("VCALENDAR").prop("VERSION").comp(
+            CompSelector::new("VEVENT")
+                .prop("UID")
+                .prop("SUMMARY")
+                .prop_without_value("ATTENDEE"),
+        );
+        let partial = selector.select(calendar);
+
+        assert_eq!(partial.properties.len(), 1);
+        let [event] = partial.components.as_slice() else {
+            panic!("expected exactly one event");
+        };
+        let properties = event
+            .properties
+            .iter()
+            .map(|property| (property.name.as_str(), property.val.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            properties,
+            [
+                ("UID", "meeting@example.com"),
+                ("SUMMARY", "Straßenfest Planning"),
+                ("ATTENDEE", ""),
+            ]
+        );
+        let attendee = event.find_prop("ATTENDEE").unwrap();
+        assert_eq!(attendee.params.len(), 2, "parameters are kept");
+        assert!(event.components.is_empty(), "alarm was not requested");
+    });
+}
+
+#[test]
+fn calendar_data_all() {
+    with_calendar(MEETING, |calendar| {
+        let everything = CompSelector::new("VCALENDAR").all_props().all_comps();
+        assert_eq!(
+            everything.select(calendar).to_string(),
+            calendar.to_string()
+        );
+
+        let events_only = CompSelector::new("VCALENDAR")
+            .comp(CompSelector::new("VEVENT").all_props().all_comps())
+            .select(calendar);
+        assert!(events_only.properties.is_empty());
+        assert_eq!(
+            events_only.components, calendar.components,
+            "events are kept untouched"
+        );
+    });
+}
@@ -0,0 +1,143 @@
+#![cfg(feature = "parser")]
+use std::{
+    env, fs,
+    io::{self, BufReader, Read},
+    path::PathBuf,
+};
+
+use icalendar::{
+    Calendar, CalendarComponent, Component,
+    parser::{CalendarReader, ParseError, read_calendar, unfold},
+};
+use pretty_assertions::assert_eq;
+
+fn fixture(name: &str) -> String {
+    let path = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("fixtures")
+        .join(name);
+    fs::read_to_string(path).unwrap()
+}
+
+#[test]
+fn matches_read_calendar() {
+    for name in [
+        "event_five_attendees.ics",
+        "icalendar-rb/event.ics",
+        "icalendar-rb/timezone.ics",
+        "icalendar-rb/two_events.ics",
+        "icalendar-rb/custom_component.ics",
+    ] {
+        let input = fixture(name);
+        let expected = Calendar::from(read_calendar(&unfold(&input)).unwrap());
+
+        // a tiny buffer makes sure nothing relies on reading everything at once
+        let mut reader = CalendarReader::new(BufReader::with_capacity(16, input.as_bytes()));
+        let components = reader
+            .by_ref()
+            .collect::<Result<Vec<CalendarComponent>, _>>()
+            .unwrap();
+
+        assert_eq!(components, expected.components, "{name}");
+        assert_eq!(reader.properties(), expected.properties, "{name}");
+    }
+}
+
+const BROKEN: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+UID:first\r
+DESCRIPTION:folded over\r
+  two lines\r
+BROKEN LINE\r
+END:VEVENT\r
+BEGIN:VTODO\r
+UID:second\r
+END:VTODO\r
+BEGIN:VEVENT\r
+UID:third\r
+END:VTODO\r
+BEGIN:VJOURNAL\r
+UID:fourth\r
+";
+
+#[test]
+fn reports_errors_with_original_line_and_continues() {
+    let mut reader = CalendarReader::new(BROKEN.as_bytes());
+
+    let error = reader.next().unwrap().unwrap_err();
+    assert_eq!(error.location().unwrap().line, 7, "{error:?}");
+
+    let todo = reader.next().unwrap().unwrap();
+    assert_eq!(todo.as_todo().unwrap().get_uid(), Some("second"));
+
+    let error = reader.next().unwrap().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 14, column 5: BEGIN:VEVENT is closed by END:VTODO"
+    );
+
+    let error = reader.next().unwrap().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 15, column 1: BEGIN:VJOURNAL is never closed"
+    );
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn components_without_calendar() {
+    let input = "BEGIN:VEVENT\nUID:bare\nEND:VEVENT\n\nBEGIN:VTODO\nUID:todo\nEND:VTODO\n";
+    let uids = CalendarReader::new(input.as_bytes())
+        .map(|component| {
+            let component = component.unwrap();
+            match component {
+                CalendarComponent::Event(event) => event.get_uid().map(ToOwned::to_owned),
+                CalendarComponent::Todo(todo) => todo.get_uid().map(ToOwned::to_owned),
+                _ => None,
+            }
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(uids, [Some("bare".to_owned()), Some("todo".to_owned())]);
+
+    let mut reader = CalendarReader::new("UID:stray\n".as_bytes());
+    assert_eq!(
+        reader.next().unwrap().unwrap_err().to_string(),
+        "line 1, column 1: property outside of a component"
+    );
+}
+
+#[test]
+fn invalid_utf8() {
+    let input =
+        b"BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nSUMMARY:caf\xe9\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+    let mut reader = CalendarReader::new(&input[..]);
+    let ParseError::InvalidUtf8 { location } = reader.next().unwrap().unwrap_err() else {
+        panic!("expected invalid UTF-8");
+    };
+    assert_eq!((location.line, location.column), (3, 12));
+    assert_eq!(location.snippet, "SUMMARY:caf\u{fffd}");
+}
+
+#[derive(Debug)]
+struct FailingAfter<'a>(&'a [u8]);
+
+impl Read for FailingAfter<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.0.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::ConnectionReset, "gone"));
+        }
+        self.0.read(buf)
+    }
+}
+
+#[test]
+fn io_errors_end_the_iteration() {
+    let input = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:x\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\n";
+    let mut reader = CalendarReader::new(BufReader::new(FailingAfter(input.as_bytes())));
+
+    assert!(reader.next().unwrap().is_ok());
+    let error = reader.next().unwrap().unwrap_err();
+    assert!(matches!(&error, ParseError::Io(io) if io.kind() == io::ErrorKind::ConnectionReset));
+    assert!(std::error::Error::source(&error).is_some());
+    assert!(reader.next().is_none());
+}
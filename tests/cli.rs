@@ -0,0 +1,240 @@
+#![cfg(feature = "cli")]
+use std::{
+    fs,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+use icalendar::{Calendar, Component};
+use pretty_assertions::assert_eq;
+
+const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:test\r
+BEGIN:VTIMEZONE\r
+TZID:Europe/Berlin\r
+BEGIN:STANDARD\r
+DTSTART:19701025T030000\r
+TZOFFSETFROM:+0200\r
+TZOFFSETTO:+0100\r
+END:STANDARD\r
+END:VTIMEZONE\r
+BEGIN:VEVENT\r
+UID:standup\r
+DTSTAMP:20240301T100000Z\r
+DTSTART;TZID=Europe/Berlin:20240301T090000\r
+DTEND;TZID=Europe/Berlin:20240301T091500\r
+RRULE:FREQ=DAILY;COUNT=3\r
+SUMMARY:Standup\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:launch\r
+DTSTAMP:20240301T100000Z\r
+DTSTART:20240302T120000Z\r
+SUMMARY:Launch\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+/// What running the tool with `args` and `stdin` produced.
+#[derive(Debug)]
+struct Output {
+    code: i32,
+    stdout: String,
+    stderr: String,
+}
+
+fn icalendar(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_icalendar"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // commands that fail early don't read their input
+    let _ = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+    let output = child.wait_with_output().unwrap();
+    Output {
+        code: output.status.code().unwrap(),
+        stdout: String::from_utf8(output.stdout).unwrap(),
+        stderr: String::from_utf8(output.stderr).unwrap(),
+    }
+}
+
+/// Runs the tool and returns its error message, which must make it fail.
+fn error(args: &[&str]) -> String {
+    let output = icalendar(args, CALENDAR);
+    assert_eq!(output.code, 1, "{output:?}");
+    assert_eq!(output.stdout, "");
+    output.stderr
+}
+
+#[test]
+fn commands_are_checked() {
+    let output = icalendar(&[], "");
+    assert_eq!(output.code, 1);
+    assert!(output.stderr.starts_with("Usage: icalendar"));
+
+    let output = icalendar(&["help"], "");
+    assert_eq!(output.code, 0);
+    assert!(output.stdout.starts_with("Usage: icalendar"));
+
+    assert!(error(&["frobnicate"]).starts_with("icalendar: unknown command \"frobnicate\"\n"));
+}
+
+#[test]
+fn options_are_checked() {
+    assert_eq!(
+        error(&["validate", "--frobnicate"]),
+        "icalendar: unknown option --frobnicate\n"
+    );
+    assert_eq!(
+        error(&["fmt", "-w", "--uid", "x"]),
+        "icalendar: unknown option --uid\n"
+    );
+    assert_eq!(
+        error(&["expand", "--to", "20240302", "--from"]),
+        "icalendar: --from needs a value\n"
+    );
+    assert_eq!(
+        error(&["expand", "--from", "20240301"]),
+        "icalendar: expand needs --from and --to\n"
+    );
+    assert_eq!(
+        error(&["to-json", "a.ics", "b.ics"]),
+        "icalendar: expected at most one file\n"
+    );
+    assert_eq!(
+        error(&["merge", "--strategy", "mine"]),
+        "icalendar: unknown strategy \"mine\"\n"
+    );
+    assert!(error(&["validate", "missing.ics"]).starts_with("icalendar: missing.ics: "));
+}
+
+#[test]
+fn times_are_dates_utc_date_times_or_rfc_3339() {
+    let expand = |from: &str, to: &str| {
+        let output = icalendar(&["expand", "--from", from, "--to", to], CALENDAR);
+        assert_eq!(output.code, 0, "{output:?}");
+        output.stdout.lines().count()
+    };
+    assert_eq!(expand("20240301", "20240302"), 1);
+    assert_eq!(expand("20240301", "20240302T120001Z"), 3);
+    // after the second standup in Berlin
+    assert_eq!(expand("2024-03-02T09:30:00+01:00", "20240304"), 2);
+
+    for time in ["2024", "20240301T100000", "2024-03-01 10:00", "tomorrow"] {
+        assert_eq!(
+            error(&["expand", "--from", time, "--to", "20240302"]),
+            format!("icalendar: invalid time {time:?}\n")
+        );
+    }
+}
+
+#[test]
+fn validate_fails_on_errors_only() {
+    let output = icalendar(&["validate"], CALENDAR);
+    assert_eq!((output.code, output.stdout.as_str()), (0, ""));
+
+    let no_dtstamp = CALENDAR.replace("DTSTAMP:20240301T100000Z\r\nDTSTART:", "DTSTART:");
+    let output = icalendar(&["validate", "-"], &no_dtstamp);
+    assert_eq!(output.code, 0);
+    assert_eq!(
+        output.stdout,
+        "-: warning: VCALENDAR/VEVENT[2]: missing DTSTAMP\n"
+    );
+
+    let output = icalendar(&["validate"], &CALENDAR.replace("PRODID:test\r\n", ""));
+    assert_eq!(output.code, 1);
+    assert_eq!(output.stdout, "-: error: VCALENDAR: missing PRODID\n");
+}
+
+#[test]
+fn expand_prints_where_occurrences_are_in_time() {
+    let output = icalendar(
+        &["expand", "--from", "20240301", "--to", "20240303"],
+        CALENDAR,
+    );
+    assert_eq!(output.code, 0, "{output:?}");
+    assert_eq!(
+        output.stdout.lines().collect::<Vec<_>>(),
+        [
+            "TZID=Europe/Berlin:20240301T090000\tTZID=Europe/Berlin:20240301T091500\tstandup\tStandup",
+            "TZID=Europe/Berlin:20240302T090000\tTZID=Europe/Berlin:20240302T091500\tstandup\tStandup",
+            "20240302T120000Z\t-\tlaunch\tLaunch",
+        ]
+    );
+}
+
+#[test]
+fn query_keeps_time_zones() {
+    let output = icalendar(&["query", "--uid", "launch"], CALENDAR);
+    assert_eq!(output.code, 0, "{output:?}");
+    let calendar: Calendar = output.stdout.parse().unwrap();
+    assert_eq!(
+        calendar
+            .components
+            .iter()
+            .map(Component::component_kind)
+            .collect::<Vec<_>>(),
+        ["VTIMEZONE", "VEVENT"]
+    );
+    assert!(output.stdout.contains("UID:launch\r\n"));
+    assert!(!output.stdout.contains("UID:standup\r\n"));
+}
+
+#[test]
+fn split_writes_one_file_per_uid() {
+    let dir = std::env::temp_dir().join(format!("icalendar-cli-split-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+
+    let output = icalendar(&["split", "--dir", dir.to_str().unwrap()], CALENDAR);
+    assert_eq!(output.code, 0, "{output:?}");
+    let written = output.stdout.lines().map(PathBuf::from).collect::<Vec<_>>();
+    assert_eq!(written, [dir.join("standup.ics"), dir.join("launch.ics")]);
+    let standup = fs::read_to_string(&written[0]).unwrap();
+    assert!(standup.contains("TZID:Europe/Berlin\r\n"));
+    assert!(!standup.contains("UID:launch"));
+
+    // splitting again updates the files
+    let output = icalendar(&["split", "--dir", dir.to_str().unwrap()], CALENDAR);
+    assert_eq!(output.code, 0, "{output:?}");
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn fmt_is_stable_and_rewrites_files_whole() {
+    const UNTIDY: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:test\r
+BEGIN:VEVENT\r
+summary:No UID or DTSTAMP\r
+DTSTART:20240302T120000Z\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+    let output = icalendar(&["fmt"], UNTIDY);
+    assert_eq!(output.code, 0, "{output:?}");
+    assert!(output.stdout.contains("SUMMARY:No UID or DTSTAMP\r\n"));
+    assert!(!output.stdout.contains("UID:"), "{}", output.stdout);
+    assert!(!output.stdout.contains("DTSTAMP:"), "{}", output.stdout);
+    assert_eq!(icalendar(&["fmt"], UNTIDY).stdout, output.stdout);
+    assert_eq!(icalendar(&["fmt"], &output.stdout).stdout, output.stdout);
+
+    let dir = std::env::temp_dir().join(format!("icalendar-cli-fmt-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir(&dir).unwrap();
+    let file = dir.join("untidy.ics");
+    fs::write(&file, UNTIDY).unwrap();
+    for _ in 0..2 {
+        let written = icalendar(&["fmt", "-w", file.to_str().unwrap()], "");
+        assert_eq!(written.code, 0, "{written:?}");
+        assert_eq!(fs::read_to_string(&file).unwrap(), output.stdout);
+    }
+    // the temporary file is renamed onto the original
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+    fs::remove_dir_all(&dir).unwrap();
+}
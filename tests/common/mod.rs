@@ -0,0 +1,36 @@
+//! Fixtures shared by the integration tests, every test crate uses only some of them.
+#![allow(dead_code)]
+
+use std::fmt::Display;
+
+use icalendar::Calendar;
+
+/// A `VCALENDAR` around `body`, which holds complete content lines.
+pub fn calendar(body: &str) -> Calendar {
+    format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:test\r\n{body}END:VCALENDAR\r\n")
+        .parse()
+        .unwrap()
+}
+
+/// A component `kind` around `lines`, which are complete content lines.
+pub fn component(kind: &str, lines: &str) -> String {
+    format!("BEGIN:{kind}\r\n{lines}END:{kind}\r\n")
+}
+
+/// A calendar with a single `VEVENT` made of a `UID`, a `DTSTAMP` and `lines`.
+pub fn event(lines: &str) -> Calendar {
+    calendar(&component(
+        "VEVENT",
+        &format!("UID:1\r\nDTSTAMP:20240301T100000Z\r\n{lines}"),
+    ))
+}
+
+/// What each of `items` displays as, e.g. changes, violations or merge results.
+pub fn messages<T: Display>(items: &[T]) -> Vec<String> {
+    items.iter().map(ToString::to_string).collect()
+}
+
+/// The lines `value` displays as.
+pub fn lines(value: &impl Display) -> Vec<String> {
+    value.to_string().lines().map(ToOwned::to_owned).collect()
+}
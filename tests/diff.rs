@@ -0,0 +1,147 @@
+#![cfg(feature = "parser")]
+use icalendar::{
+    diff::{self, ComponentChange, PatchError, PropertyChange},
+    *,
+};
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::{calendar, lines};
+
+const OLD: &str = "BEGIN:VEVENT\r
+UID:meeting\r
+DTSTAMP:20240301T100000Z\r
+SUMMARY:Planning\\, first round\r
+ATTENDEE;ROLE=CHAIR;CN=Alice:mailto:alice@example.com\r
+ATTENDEE;PARTSTAT=NEEDS-ACTION:mailto:bob@example.com\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+TRIGGER:-PT15M\r
+DESCRIPTION:Reminder\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:lunch\r
+DTSTAMP:20240301T100000Z\r
+SUMMARY:Lunch\r
+END:VEVENT\r
+";
+
+#[test]
+fn cosmetic_differences_are_ignored() {
+    let reformatted = OLD
+        .replace(
+            "SUMMARY:Planning\\, first round",
+            "SUMMARY:Planning\\, fir\r\n st round",
+        )
+        .replace("ROLE=CHAIR;CN=Alice", "CN=\"Alice\";role=CHAIR");
+    let patch = diff::diff(&calendar(OLD), &calendar(&reformatted));
+    assert!(patch.is_empty(), "{patch}");
+}
+
+#[test]
+fn properties_and_components_are_compared() {
+    let new = OLD
+        .replace("SUMMARY:Planning\\, first round", "SUMMARY:Planning")
+        .replace("PARTSTAT=NEEDS-ACTION", "PARTSTAT=ACCEPTED")
+        .replace("TRIGGER:-PT15M", "TRIGGER:-PT30M")
+        .replace("UID:meeting\r\n", "UID:meeting\r\nCATEGORIES:work\r\n")
+        .replace("BEGIN:VEVENT\r\nUID:lunch", "BEGIN:VTODO\r\nUID:lunch")
+        .replace("SUMMARY:Lunch\r\nEND:VEVENT", "SUMMARY:Lunch\r\nEND:VTODO");
+    let mut new = calendar(&new);
+    new.append_property(("X-WR-CALNAME", "Work"));
+
+    let patch = diff::diff(&calendar(OLD), &new);
+    assert_eq!(
+        lines(&patch),
+        [
+            "VCALENDAR: added X-WR-CALNAME:Work",
+            "VEVENT meeting: changed ATTENDEE;PARTSTAT=NEEDS-ACTION:mailto:bob@example.com to ATTENDEE;PARTSTAT=ACCEPTED:mailto:bob@example.com",
+            "VEVENT meeting: added CATEGORIES:work",
+            "VEVENT meeting: changed SUMMARY:Planning\\, first round to SUMMARY:Planning",
+            "VEVENT meeting: replaced nested components",
+            "VEVENT lunch: removed",
+            "VTODO lunch: added",
+        ]
+    );
+    let Some(ComponentChange::Modified { properties, .. }) = patch.components.first() else {
+        panic!("{patch:?}")
+    };
+    let PropertyChange::Changed { old, new } = &properties[0] else {
+        panic!("{properties:?}")
+    };
+    assert_eq!(old.params()["PARTSTAT"].value(), "NEEDS-ACTION");
+    assert_eq!(new.params()["PARTSTAT"].value(), "ACCEPTED");
+}
+
+#[test]
+fn overrides_are_told_apart() {
+    let master = "BEGIN:VEVENT\r\nUID:series\r\nDTSTAMP:20240301T100000Z\r\nRRULE:FREQ=DAILY\r\nEND:VEVENT\r\n";
+    let instance = |day: u8, summary: &str| {
+        format!(
+            "BEGIN:VEVENT\r\nUID:series\r\nDTSTAMP:20240301T100000Z\r\nRECURRENCE-ID:202403{day:02}T100000Z\r\nSUMMARY:{summary}\r\nEND:VEVENT\r\n"
+        )
+    };
+    let old = calendar(&format!("{master}{}", instance(2, "moved")));
+    let new = calendar(&format!(
+        "{master}{}{}",
+        instance(2, "moved again"),
+        instance(3, "cancelled")
+    ));
+    assert_eq!(
+        lines(&diff::diff(&old, &new)),
+        [
+            "VEVENT series at 20240302T100000Z: changed SUMMARY:moved to SUMMARY:moved again",
+            "VEVENT series at 20240303T100000Z: added",
+        ]
+    );
+}
+
+#[test]
+fn patches_apply_to_the_old_version() {
+    let old = calendar(OLD);
+    let mut new = old.clone();
+    for event in new.events_mut() {
+        event
+            .summary("Renamed")
+            .append_multi_property(("COMMENT", "added"));
+    }
+    new.components
+        .retain(|component| component.get_uid() != Some("lunch"));
+    new.push(Event::with_uid("new").summary("New").done());
+
+    let patch = diff::diff(&old, &new);
+    let mut patched = old.clone();
+    diff::apply(&mut patched, &patch).unwrap();
+    assert!(diff::diff(&patched, &new).is_empty());
+
+    // applied twice, the summary it expects is gone
+    assert_eq!(
+        diff::apply(&mut patched, &patch),
+        Err(PatchError::MissingProperty {
+            component: "VEVENT meeting".into(),
+            property: "SUMMARY:Planning\\, first round".into()
+        })
+    );
+    let added = diff::Patch {
+        components: vec![ComponentChange::Added(new.components[1].clone())],
+        ..Default::default()
+    };
+    assert!(matches!(
+        diff::apply(&mut patched, &added),
+        Err(PatchError::ExistingComponent(_))
+    ));
+}
+
+#[test]
+fn conflicting_patches_change_nothing() {
+    let old = calendar(OLD);
+    let new = calendar(&OLD.replace("SUMMARY:Lunch", "SUMMARY:Brunch"));
+    let patch = diff::diff(&old, &new);
+
+    let mut concurrent = calendar(&OLD.replace("SUMMARY:Lunch", "SUMMARY:Dinner"));
+    let before = concurrent.clone();
+    let error = diff::apply(&mut concurrent, &patch).unwrap_err();
+    assert_eq!(error.to_string(), "VEVENT lunch has no SUMMARY:Lunch");
+    assert_eq!(concurrent, before);
+}
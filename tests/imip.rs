@@ -0,0 +1,218 @@
+use chrono::{TimeZone, Utc};
+use icalendar::{
+    Attendee, Calendar, Component, Event, EventLike, Method, PartStat,
+    imip::{self, ImipError, Mailbox, MimeMessage},
+    itip,
+};
+
+const ORGANIZER: &str = "mailto:bob@example.com";
+const ALICE: &str = "mailto:alice@example.com";
+
+fn meeting() -> Event {
+    Event::with_uid("meeting@example.com")
+        .summary("Planning")
+        .location("Room 1")
+        .starts(Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap())
+        .ends(Utc.with_ymd_and_hms(2026, 3, 2, 10, 0, 0).unwrap())
+        .organizer(ORGANIZER)
+        .attendee(Attendee::new(ALICE.into()).cn("Alice".into()).rsvp(true))
+        .attendee(Attendee::new(ORGANIZER.into()).partstat(PartStat::Accepted))
+        .attendee(Attendee::new("urn:uuid:room-1".into()))
+        .done()
+}
+
+fn rendered(message: &Calendar) -> String {
+    String::from_utf8(MimeMessage::new(message).unwrap().to_bytes()).unwrap()
+}
+
+#[test]
+fn request_goes_from_organizer_to_attendees() {
+    let email = MimeMessage::new(&itip::request(&meeting()).unwrap()).unwrap();
+    assert_eq!(email.method(), Method::Request);
+    assert_eq!(email.from, Some(Mailbox::new("bob@example.com")));
+    assert_eq!(
+        email.to,
+        [Mailbox::new("alice@example.com").name("Alice")],
+        "neither the organizer nor non-mailto attendees receive the invitation"
+    );
+    assert_eq!(email.subject, "Invitation: Planning");
+    assert!(email.text.contains("When: 2026-03-02 09:00 UTC"));
+    assert!(email.text.contains("Where: Room 1"));
+}
+
+#[test]
+fn reply_goes_to_organizer() {
+    let reply = itip::reply(&meeting(), ALICE, PartStat::Declined).unwrap();
+    let email = MimeMessage::new(&reply).unwrap();
+    assert_eq!(
+        email.from,
+        Some(Mailbox::new("alice@example.com").name("Alice"))
+    );
+    assert_eq!(email.to, [Mailbox::new("bob@example.com")]);
+    assert_eq!(email.subject, "Declined");
+    assert!(email.text.starts_with("Alice replied: Declined"));
+}
+
+#[test]
+fn renders_multipart_alternative() {
+    let raw = rendered(&itip::request(&meeting()).unwrap());
+    let (head, body) = raw.split_once("\r\n\r\n").unwrap();
+
+    assert!(head.contains("From: bob@example.com\r\n"));
+    assert!(head.contains("To: Alice <alice@example.com>\r\n"));
+    assert!(head.contains("Subject: Invitation: Planning\r\n"));
+    assert!(head.contains("MIME-Version: 1.0\r\n"));
+    assert!(head.contains("Message-ID: <"));
+    assert!(head.contains("Content-Type: multipart/alternative;\r\n boundary="));
+
+    let plain = body
+        .find("Content-Type: text/plain; charset=UTF-8")
+        .unwrap();
+    let calendar = body
+        .find("Content-Type: text/calendar; method=REQUEST; charset=UTF-8")
+        .unwrap();
+    assert!(plain < calendar, "the preferred alternative comes last");
+    assert!(body.contains("Content-Transfer-Encoding: 7bit\r\n"));
+    assert!(body.contains("BEGIN:VCALENDAR\r\n"));
+    assert!(raw.split("\r\n").all(|line| line.len() <= 78));
+}
+
+#[test]
+fn non_ascii_is_encoded() {
+    let event = meeting()
+        .summary("Straßenfest")
+        .remove_multi_property("ATTENDEE")
+        .attendee(Attendee::new(ALICE.into()).cn("Zoë".into()))
+        .done();
+    let raw = rendered(&itip::request(&event).unwrap());
+
+    assert!(raw.is_ascii());
+    assert!(raw.contains("Subject: =?UTF-8?B?SW52aXRhdGlvbjogU3RyYcOfZW5mZXN0?=\r\n"));
+    assert!(raw.contains("To: =?UTF-8?B?Wm/Dqw==?= <alice@example.com>\r\n"));
+    assert!(raw.contains("Content-Transfer-Encoding: base64\r\n"));
+
+    let part = imip::extract(raw.as_bytes()).unwrap();
+    assert!(part.content.contains("SUMMARY:Straßenfest\r\n"));
+}
+
+#[test]
+fn line_breaks_cannot_inject_headers() {
+    let event = meeting()
+        .summary("Hi\r\nBcc: evil@example.com")
+        .remove_multi_property("ATTENDEE")
+        .attendee(Attendee::new(ALICE.into()).cn("Alice\r\nCc: evil@example.com".into()))
+        .done();
+    let mut email = MimeMessage::new(&itip::request(&event).unwrap()).unwrap();
+    email
+        .to
+        .push(Mailbox::new("carol@example.com\r\nX-Evil: yes"));
+    let raw = String::from_utf8(email.to_bytes()).unwrap();
+    let (head, _) = raw.split_once("\r\n\r\n").unwrap();
+
+    for line in head.split("\r\n") {
+        assert!(
+            line.starts_with(' ')
+                || [
+                    "From:",
+                    "To:",
+                    "Subject:",
+                    "Date:",
+                    "Message-ID:",
+                    "MIME-Version:",
+                    "Content-Type:"
+                ]
+                .iter()
+                .any(|name| line.starts_with(name)),
+            "unexpected header line {line:?}"
+        );
+    }
+    assert!(!head.contains("evil@example.com"));
+    assert!(head.contains("Subject: =?UTF-8?B?"));
+}
+
+#[test]
+fn extract_round_trip() {
+    let request = itip::request(&meeting()).unwrap();
+    let raw = MimeMessage::new(&request).unwrap().to_bytes();
+
+    let part = imip::extract(&raw).unwrap();
+    assert_eq!(part.method, Some(Method::Request));
+    assert_eq!(part.content, request.to_string());
+}
+
+#[test]
+fn extract_from_nested_quoted_printable() {
+    let raw = concat!(
+        "From: Bob <bob@example.com>\n",
+        "Subject: Invitation\n",
+        "Content-Type: multipart/mixed;\n",
+        "  boundary=\"outer\"\n",
+        "\n",
+        "--outer\n",
+        "Content-Type: multipart/alternative; boundary=inner\n",
+        "\n",
+        "--inner\n",
+        "Content-Type: text/plain\n",
+        "\n",
+        "You are invited.\n",
+        "--inner\n",
+        "Content-Type: text/calendar; charset=\"utf-8\"; method=\"reply\"\n",
+        "Content-Transfer-Encoding: quoted-printable\n",
+        "\n",
+        "BEGIN:VCALENDAR\n",
+        "METHOD:REPLY\n",
+        "SUMMARY:Stra=C3=9Fenfest with a line that is=\n",
+        " long\n",
+        "END:VCALENDAR\n",
+        "--inner--\n",
+        "--outer--\n",
+    );
+    let part = imip::extract(raw.as_bytes()).unwrap();
+    assert_eq!(part.method, Some(Method::Reply));
+    assert_eq!(
+        part.content,
+        "BEGIN:VCALENDAR\nMETHOD:REPLY\nSUMMARY:Straßenfest with a line that is long\nEND:VCALENDAR"
+    );
+}
+
+#[test]
+fn extract_errors() {
+    let plain = "Subject: hi\r\nContent-Type: text/plain\r\n\r\nhello\r\n";
+    assert_eq!(
+        imip::extract(plain.as_bytes()),
+        Err(ImipError::NoCalendarPart)
+    );
+
+    let utf16 = "Content-Type: text/calendar; charset=utf-16\r\n\r\nBEGIN:VCALENDAR\r\n";
+    assert_eq!(
+        imip::extract(utf16.as_bytes()),
+        Err(ImipError::UnsupportedCharset("utf-16".into()))
+    );
+
+    assert_eq!(
+        MimeMessage::new(&Calendar::new().push(meeting()).done()).unwrap_err(),
+        ImipError::MissingMethod
+    );
+}
+
+#[cfg(feature = "parser")]
+#[test]
+fn parse_checks_method() {
+    let request = itip::request(&meeting()).unwrap();
+    let raw = rendered(&request);
+
+    let parsed = imip::parse(raw.as_bytes()).unwrap();
+    assert_eq!(parsed.get_method(), Some(Method::Request));
+    let event = parsed.events().next().unwrap();
+    assert_eq!(event.get_uid(), Some("meeting@example.com"));
+    assert_eq!(event.get_location(), Some("Room 1"));
+
+    let lying = raw.replace("method=REQUEST", "method=CANCEL");
+    assert_eq!(
+        imip::parse(lying.as_bytes()).unwrap_err(),
+        ImipError::MethodMismatch {
+            content_type: "CANCEL".into(),
+            calendar: "REQUEST".into()
+        }
+    );
+}
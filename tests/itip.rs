@@ -0,0 +1,463 @@
+use chrono::{Duration, TimeZone, Utc};
+use icalendar::{
+    Alarm, Attendee, Calendar, Component, Event, EventLike, EventStatus, Method, PartStat, Trigger,
+    itip::{self, Conflict, ItipError, Outcome},
+};
+
+const ORGANIZER: &str = "mailto:bob@example.com";
+const ALICE: &str = "mailto:alice@example.com";
+const CAROL: &str = "mailto:carol@example.com";
+
+fn meeting() -> Event {
+    Event::with_uid("meeting@example.com")
+        .summary("Planning")
+        .starts(Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap())
+        .ends(Utc.with_ymd_and_hms(2026, 3, 2, 10, 0, 0).unwrap())
+        .organizer(ORGANIZER)
+        .attendee(
+            Attendee::new(ALICE.into())
+                .cn("Alice".into())
+                .partstat(PartStat::NeedsAction)
+                .rsvp(true),
+        )
+        .attendee(Attendee::new(CAROL.into()).partstat(PartStat::NeedsAction))
+        .sequence(2)
+        .alarm(Alarm::display("soon", -chrono::Duration::minutes(10)))
+        .done()
+}
+
+fn only_event(message: &Calendar) -> &Event {
+    let mut events = message.events();
+    let event = events.next().expect("message contains an event");
+    assert!(events.next().is_none());
+    event
+}
+
+#[test]
+fn request() {
+    let message = itip::request(&meeting()).unwrap();
+    assert_eq!(message.get_method(), Some(Method::Request));
+    assert!(message.to_string().contains("METHOD:REQUEST\r\n"));
+
+    let event = only_event(&message);
+    assert_eq!(event.get_attendees().len(), 2);
+    assert_eq!(event.get_organizer(), Some(ORGANIZER));
+    assert!(event.get_timestamp().is_some());
+    assert_eq!(event.components().len(), 1, "alarms may be sent along");
+}
+
+#[test]
+fn request_needs_organizer_and_attendees() {
+    let without_organizer = meeting().remove_organizer().done();
+    assert_eq!(
+        itip::request(&without_organizer),
+        Err(ItipError::MissingProperty {
+            method: Method::Request,
+            property: "ORGANIZER"
+        })
+    );
+
+    let without_attendees = Event::with_uid("lonely@example.com")
+        .summary("Focus time")
+        .starts(Utc::now())
+        .organizer(ORGANIZER)
+        .done();
+    assert_eq!(
+        itip::request(&without_attendees),
+        Err(ItipError::MissingProperty {
+            method: Method::Request,
+            property: "ATTENDEE"
+        })
+    );
+}
+
+#[test]
+fn reply_contains_only_the_replying_attendee() {
+    let message = itip::reply(&meeting(), "MAILTO:alice@example.com", PartStat::Accepted).unwrap();
+    assert_eq!(message.get_method(), Some(Method::Reply));
+
+    let event = only_event(&message);
+    let attendees = event.get_attendees();
+    let [alice] = attendees.as_slice() else {
+        panic!("expected a single attendee, got {attendees:?}");
+    };
+    assert_eq!(alice.cal_address, ALICE);
+    assert_eq!(alice.part_stat, Some(PartStat::Accepted));
+    assert_eq!(alice.cn.as_deref(), Some("Alice"));
+    assert_eq!(alice.rsvp, None);
+
+    assert_eq!(event.get_uid(), Some("meeting@example.com"));
+    assert_eq!(event.get_sequence(), Some(2));
+    assert_eq!(event.get_summary(), None);
+    assert!(event.components().is_empty());
+}
+
+#[test]
+fn reply_from_stranger() {
+    assert_eq!(
+        itip::reply(&meeting(), "mailto:eve@example.com", PartStat::Accepted),
+        Err(ItipError::UnknownAttendee("mailto:eve@example.com".into()))
+    );
+}
+
+#[test]
+fn cancel_bumps_sequence() {
+    let message = itip::cancel(&meeting()).unwrap();
+    assert_eq!(message.get_method(), Some(Method::Cancel));
+
+    let event = only_event(&message);
+    assert_eq!(event.get_sequence(), Some(3));
+    assert_eq!(event.get_status(), Some(EventStatus::Cancelled));
+    assert_eq!(event.get_attendees().len(), 2);
+    assert!(event.components().is_empty(), "alarms are dropped");
+}
+
+#[test]
+fn add_rejects_recurrence() {
+    let mut instance = meeting();
+    instance.add_property("RRULE", "FREQ=WEEKLY;COUNT=3");
+    assert_eq!(
+        itip::add(&instance),
+        Err(ItipError::ForbiddenProperty {
+            method: Method::Add,
+            property: "RRULE"
+        })
+    );
+
+    let message = itip::add(&meeting()).unwrap();
+    assert_eq!(message.get_method(), Some(Method::Add));
+}
+
+#[test]
+fn refresh_only_identifies_the_event() {
+    let message = itip::refresh(&meeting(), CAROL).unwrap();
+    let event = only_event(&message);
+
+    assert_eq!(event.get_uid(), Some("meeting@example.com"));
+    assert_eq!(event.get_organizer(), Some(ORGANIZER));
+    assert_eq!(event.get_start(), None);
+    assert_eq!(event.get_sequence(), None);
+    assert_eq!(event.get_attendees().len(), 1);
+}
+
+#[test]
+fn counter_and_decline() {
+    let proposal = meeting()
+        .starts(Utc.with_ymd_and_hms(2026, 3, 2, 14, 0, 0).unwrap())
+        .ends(Utc.with_ymd_and_hms(2026, 3, 2, 15, 0, 0).unwrap())
+        .done();
+    let counter = itip::counter(&proposal).unwrap();
+    assert_eq!(counter.get_method(), Some(Method::Counter));
+
+    let decline = itip::decline_counter(only_event(&counter), ALICE).unwrap();
+    assert_eq!(decline.get_method(), Some(Method::DeclineCounter));
+    let event = only_event(&decline);
+    assert_eq!(event.get_sequence(), Some(2));
+    assert_eq!(event.get_start(), None);
+    assert_eq!(event.get_attendees().len(), 1);
+}
+
+#[test]
+fn validate_checks_messages() {
+    assert_eq!(
+        itip::validate(&Calendar::new().push(meeting()).done()),
+        Err(ItipError::MissingMethod)
+    );
+    assert_eq!(
+        itip::validate(&Calendar::new().method(Method::Request).done()),
+        Err(ItipError::NoEvent)
+    );
+
+    let reply_with_alarm = Calendar::new()
+        .method(Method::Reply)
+        .push(
+            Event::with_uid("meeting@example.com")
+                .organizer(ORGANIZER)
+                .attendee(Attendee::new(ALICE.into()))
+                .timestamp(Utc::now())
+                .alarm(Alarm::display(
+                    "soon",
+                    Trigger::before_start(chrono::Duration::minutes(5)),
+                ))
+                .done(),
+        )
+        .done();
+    assert_eq!(
+        itip::validate(&reply_with_alarm),
+        Err(ItipError::ForbiddenAlarm {
+            method: Method::Reply
+        })
+    );
+
+    let confirmed_cancel = Calendar::new()
+        .method(Method::Cancel)
+        .push(
+            Event::with_uid("meeting@example.com")
+                .organizer(ORGANIZER)
+                .sequence(1)
+                .timestamp(Utc::now())
+                .status(EventStatus::Confirmed)
+                .done(),
+        )
+        .done();
+    assert_eq!(
+        itip::validate(&confirmed_cancel),
+        Err(ItipError::InvalidStatus {
+            method: Method::Cancel,
+            status: "CONFIRMED".into()
+        })
+    );
+
+    let mixed = Calendar::new()
+        .method(Method::Publish)
+        .push(Event::with_uid("a").done())
+        .push(Event::with_uid("b").done())
+        .done();
+    assert_eq!(itip::validate(&mixed), Err(ItipError::MixedUids));
+}
+
+#[test]
+fn method_is_replaced() {
+    let calendar = Calendar::new()
+        .method(Method::Request)
+        .method(Method::Cancel)
+        .done();
+    assert_eq!(calendar.get_method(), Some(Method::Cancel));
+    assert_eq!(
+        calendar
+            .properties
+            .iter()
+            .filter(|property| property.key() == "METHOD")
+            .count(),
+        1
+    );
+}
+
+fn stored() -> Calendar {
+    Calendar::new().push(meeting()).done()
+}
+
+fn weekly_meeting() -> Calendar {
+    let mut meeting = meeting();
+    meeting.add_property("RRULE", "FREQ=WEEKLY;COUNT=4");
+    Calendar::new().push(meeting).done()
+}
+
+fn alice_in(event: &Event) -> Attendee {
+    event
+        .get_attendees()
+        .into_iter()
+        .find(|attendee| attendee.cal_address == ALICE)
+        .expect("alice attends")
+}
+
+#[test]
+fn apply_reply_updates_part_stat() {
+    let mut calendar = stored();
+    let reply = itip::reply(&meeting(), ALICE, PartStat::Accepted).unwrap();
+
+    assert_eq!(
+        itip::apply(&mut calendar, &reply),
+        Ok(vec![Outcome::AttendeeUpdated {
+            uid: "meeting@example.com".into(),
+            recurrence_id: None,
+            attendee: ALICE.into(),
+            part_stat: PartStat::Accepted,
+        }])
+    );
+    let event = only_event(&calendar);
+    let alice = alice_in(event);
+    assert_eq!(alice.part_stat, Some(PartStat::Accepted));
+    assert_eq!(alice.rsvp, None, "the reply answered the RSVP");
+    assert_eq!(alice.cn.as_deref(), Some("Alice"));
+    assert_eq!(event.get_attendees().len(), 2);
+    assert_eq!(event.get_summary(), Some("Planning"));
+}
+
+#[test]
+fn apply_reply_ignores_stale_messages() {
+    let mut calendar = stored();
+    let outdated = meeting().sequence(1).done();
+    let reply = itip::reply(&outdated, ALICE, PartStat::Declined).unwrap();
+    assert!(matches!(
+        itip::apply(&mut calendar, &reply).unwrap().as_slice(),
+        [Outcome::Stale { .. }]
+    ));
+    assert_eq!(
+        alice_in(only_event(&calendar)).part_stat,
+        Some(PartStat::NeedsAction)
+    );
+
+    // replies overtaking each other: the one sent later wins
+    let sent_at = |minute| {
+        let mut reply = itip::reply(&meeting(), ALICE, PartStat::Accepted).unwrap();
+        if let Some(icalendar::CalendarComponent::Event(event)) = reply.components.first_mut() {
+            event.timestamp(Utc.with_ymd_and_hms(2026, 3, 1, 12, minute, 0).unwrap());
+        }
+        reply
+    };
+    let mut later = sent_at(30);
+    if let Some(icalendar::CalendarComponent::Event(event)) = later.components.first_mut() {
+        let tentative = Attendee::new(ALICE.into()).partstat(PartStat::Tentative);
+        event.remove_multi_property("ATTENDEE").attendee(tentative);
+    }
+    assert!(matches!(
+        itip::apply(&mut calendar, &later).unwrap().as_slice(),
+        [Outcome::AttendeeUpdated { .. }]
+    ));
+    assert!(matches!(
+        itip::apply(&mut calendar, &sent_at(10)).unwrap().as_slice(),
+        [Outcome::Stale { .. }]
+    ));
+    assert_eq!(
+        alice_in(only_event(&calendar)).part_stat,
+        Some(PartStat::Tentative)
+    );
+}
+
+#[test]
+fn apply_reply_for_one_instance_creates_override() {
+    let mut calendar = weekly_meeting();
+    let second = Utc.with_ymd_and_hms(2026, 3, 9, 9, 0, 0).unwrap();
+    let instance = meeting().recurrence_id(second).done();
+    let reply = itip::reply(&instance, ALICE, PartStat::Declined).unwrap();
+
+    assert_eq!(
+        itip::apply(&mut calendar, &reply),
+        Ok(vec![Outcome::AttendeeUpdated {
+            uid: "meeting@example.com".into(),
+            recurrence_id: Some(second.into()),
+            attendee: ALICE.into(),
+            part_stat: PartStat::Declined,
+        }])
+    );
+
+    let events = calendar.events().collect::<Vec<_>>();
+    let [master, instance] = events.as_slice() else {
+        panic!("expected master and override, got {events:?}");
+    };
+    assert_eq!(
+        alice_in(master).part_stat,
+        Some(PartStat::NeedsAction),
+        "other instances are untouched"
+    );
+    assert_eq!(alice_in(instance).part_stat, Some(PartStat::Declined));
+    assert_eq!(instance.get_recurrence_id(), Some(second.into()));
+    assert_eq!(instance.get_start(), Some(second.into()));
+    assert_eq!(
+        instance.get_end(),
+        Some((second + Duration::hours(1)).into())
+    );
+    assert_eq!(instance.property_value("RRULE"), None);
+    assert_eq!(instance.get_summary(), Some("Planning"));
+
+    // a second reply for the same instance reuses the override
+    let reply = itip::reply(instance, ALICE, PartStat::Accepted).unwrap();
+    itip::apply(&mut calendar, &reply).unwrap();
+    assert_eq!(calendar.events().count(), 2);
+}
+
+#[test]
+fn rejected_reply_for_one_instance_creates_no_override() {
+    let mut calendar = weekly_meeting();
+    let second = Utc.with_ymd_and_hms(2026, 3, 9, 9, 0, 0).unwrap();
+
+    let crasher = meeting()
+        .recurrence_id(second)
+        .attendee(Attendee::new("mailto:eve@example.com".into()))
+        .done();
+    let reply = itip::reply(&crasher, "mailto:eve@example.com", PartStat::Accepted).unwrap();
+    assert!(matches!(
+        itip::apply(&mut calendar, &reply).unwrap().as_slice(),
+        [Outcome::Conflict {
+            conflict: Conflict::UnknownAttendee(_),
+            ..
+        }]
+    ));
+    assert_eq!(calendar.components.len(), 1);
+
+    let outdated = meeting().recurrence_id(second).sequence(1).done();
+    let reply = itip::reply(&outdated, ALICE, PartStat::Declined).unwrap();
+    assert!(matches!(
+        itip::apply(&mut calendar, &reply).unwrap().as_slice(),
+        [Outcome::Stale { .. }]
+    ));
+    assert_eq!(calendar.components.len(), 1);
+}
+
+#[test]
+fn apply_reply_conflicts() {
+    let mut calendar = stored();
+    let mut stranger = meeting();
+    stranger.add_property("UID", "other@example.com");
+    let reply = itip::reply(&stranger, ALICE, PartStat::Accepted).unwrap();
+    assert_eq!(
+        itip::apply(&mut calendar, &reply),
+        Ok(vec![Outcome::Conflict {
+            uid: "other@example.com".into(),
+            recurrence_id: None,
+            conflict: Conflict::UnknownEvent,
+        }])
+    );
+
+    let crasher = meeting()
+        .attendee(Attendee::new("mailto:eve@example.com".into()))
+        .done();
+    let reply = itip::reply(&crasher, "mailto:eve@example.com", PartStat::Accepted).unwrap();
+    assert!(matches!(
+        itip::apply(&mut calendar, &reply).unwrap().as_slice(),
+        [Outcome::Conflict {
+            conflict: Conflict::UnknownAttendee(_),
+            ..
+        }]
+    ));
+
+    assert_eq!(
+        itip::apply(&mut calendar, &itip::request(&meeting()).unwrap()),
+        Err(ItipError::UnsupportedMethod(Method::Request))
+    );
+}
+
+#[test]
+fn apply_cancel_whole_event() {
+    let mut calendar = stored();
+    let cancellation = itip::cancel(&meeting()).unwrap();
+
+    assert_eq!(
+        itip::apply(&mut calendar, &cancellation),
+        Ok(vec![Outcome::Cancelled {
+            uid: "meeting@example.com".into(),
+            recurrence_id: None,
+        }])
+    );
+    let event = only_event(&calendar);
+    assert_eq!(event.get_status(), Some(EventStatus::Cancelled));
+    assert_eq!(event.get_sequence(), Some(3));
+
+    // the same cancellation again is not newer than what is stored
+    let outdated = itip::cancel(&meeting().sequence(1).done()).unwrap();
+    assert!(matches!(
+        itip::apply(&mut calendar, &outdated).unwrap().as_slice(),
+        [Outcome::Stale { .. }]
+    ));
+}
+
+#[test]
+fn apply_cancel_one_instance() {
+    let mut calendar = weekly_meeting();
+    let third = Utc.with_ymd_and_hms(2026, 3, 16, 9, 0, 0).unwrap();
+    let cancellation = itip::cancel(&meeting().recurrence_id(third).done()).unwrap();
+
+    assert!(matches!(
+        itip::apply(&mut calendar, &cancellation)
+            .unwrap()
+            .as_slice(),
+        [Outcome::Cancelled {
+            recurrence_id: Some(_),
+            ..
+        }]
+    ));
+    let master = only_event(&calendar);
+    assert_eq!(master.get_status(), None);
+    assert!(master.to_string().contains("EXDATE:20260316T090000Z"));
+}
@@ -0,0 +1,196 @@
+#![cfg(feature = "jcal")]
+use icalendar::{
+    Calendar, Component,
+    jcal::{self, JcalError},
+    parser::{read_calendar, unfold},
+};
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+const SAMPLE: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//test//test//EN\r
+BEGIN:VTIMEZONE\r
+TZID:Europe/Berlin\r
+BEGIN:STANDARD\r
+DTSTART:19701025T030000\r
+TZOFFSETFROM:+0200\r
+TZOFFSETTO:+0100\r
+END:STANDARD\r
+END:VTIMEZONE\r
+BEGIN:VEVENT\r
+UID:weekly@example.com\r
+DTSTAMP:20260301T120000Z\r
+DTSTART;TZID=Europe/Berlin:20260302T090000\r
+DURATION:PT1H\r
+RRULE:FREQ=WEEKLY;UNTIL=20260330T080000Z;BYDAY=MO,WE\r
+EXDATE;TZID=Europe/Berlin:20260309T090000,20260311T090000\r
+RDATE;VALUE=PERIOD:20260401T070000Z/PT2H\r
+SUMMARY:Standup\\, daily-ish\r
+CATEGORIES:WORK,TEAM\r
+GEO:52.52;13.405\r
+PRIORITY:5\r
+ATTENDEE;CN=Alice;PARTSTAT=ACCEPTED:mailto:alice@example.com\r
+X-ANSWER:42\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+DESCRIPTION:Reminder\r
+TRIGGER;VALUE=DATE-TIME:20260302T074500Z\r
+END:VALARM\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+fn encoded() -> serde_json::Value {
+    jcal::encode(&read_calendar(&unfold(SAMPLE)).unwrap())
+}
+
+#[test]
+fn encodes_components_and_typed_values() {
+    let json = encoded();
+    assert_eq!(
+        json[1],
+        json!([
+            ["version", {}, "text", "2.0"],
+            ["prodid", {}, "text", "-//test//test//EN"],
+        ])
+    );
+
+    let timezone = &json[2][0];
+    assert_eq!(timezone[0], "vtimezone");
+    assert_eq!(
+        timezone[2][0][1],
+        json!([
+            ["dtstart", {}, "date-time", "1970-10-25T03:00:00"],
+            ["tzoffsetfrom", {}, "utc-offset", "+02:00"],
+            ["tzoffsetto", {}, "utc-offset", "+01:00"],
+        ])
+    );
+
+    let event = &json[2][1];
+    assert_eq!(
+        event[1],
+        json!([
+            ["uid", {}, "text", "weekly@example.com"],
+            ["dtstamp", {}, "date-time", "2026-03-01T12:00:00Z"],
+            ["dtstart", {"tzid": "Europe/Berlin"}, "date-time", "2026-03-02T09:00:00"],
+            ["duration", {}, "duration", "PT1H"],
+            ["rrule", {}, "recur", {
+                "freq": "WEEKLY",
+                "until": "2026-03-30T08:00:00Z",
+                "byday": ["MO", "WE"],
+            }],
+            ["exdate", {"tzid": "Europe/Berlin"}, "date-time",
+                "2026-03-09T09:00:00", "2026-03-11T09:00:00"],
+            ["rdate", {}, "period", "2026-04-01T07:00:00Z/PT2H"],
+            ["summary", {}, "text", "Standup, daily-ish"],
+            ["categories", {}, "text", "WORK", "TEAM"],
+            ["geo", {}, "float", [52.52, 13.405]],
+            ["priority", {}, "integer", 5],
+            ["attendee", {"cn": "Alice", "partstat": "ACCEPTED"}, "cal-address",
+                "mailto:alice@example.com"],
+            ["x-answer", {}, "unknown", "42"],
+        ])
+    );
+    assert_eq!(
+        event[2][0][1][2],
+        json!(["trigger", {}, "date-time", "2026-03-02T07:45:00Z"])
+    );
+}
+
+#[test]
+fn decodes_back_to_text() {
+    let decoded = jcal::decode(&encoded()).unwrap();
+    let unfolded = unfold(SAMPLE);
+    let original = read_calendar(&unfolded).unwrap();
+    assert_eq!(decoded.to_string(), original.to_string());
+}
+
+#[test]
+fn builder_round_trip() {
+    let calendar: Calendar = SAMPLE.parse().unwrap();
+    let json = jcal::to_string(&calendar);
+    let decoded = jcal::from_str(&json).unwrap();
+    assert_eq!(decoded, calendar);
+}
+
+#[test]
+fn parameter_lists_and_escapes_round_trip() {
+    const ATTENDEE: &str = "ATTENDEE;MEMBER=\"mailto:a@example.com\",\"mailto:b@example.com\";DELEGATED-TO=\"mailto:c@example.com\";X-TAG=one;X-TAG=two;X-ADDRESS=\"1 Infinite Loop^nCupertino, CA\":mailto:jane@example.com";
+    let calendar: Calendar = format!(
+        "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:params\r\nDTSTAMP:20260301T120000Z\r\n{ATTENDEE}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n"
+    )
+    .parse()
+    .unwrap();
+
+    let json = jcal::to_value(&calendar);
+    assert_eq!(
+        json[2][0][1][2][1],
+        json!({
+            "member": ["mailto:a@example.com", "mailto:b@example.com"],
+            "delegated-to": "mailto:c@example.com",
+            "x-tag": ["one", "two"],
+            "x-address": "1 Infinite Loop\nCupertino, CA",
+        })
+    );
+
+    let decoded = jcal::from_value(&json).unwrap();
+    let attendee = &decoded.components[0].multi_properties()["ATTENDEE"][0];
+    assert_eq!(
+        attendee.param_values("MEMBER"),
+        ["mailto:a@example.com", "mailto:b@example.com"]
+    );
+    assert_eq!(
+        attendee.param_values("DELEGATED-TO"),
+        ["mailto:c@example.com"]
+    );
+    assert_eq!(attendee.param_values("X-TAG"), ["one", "two"]);
+    assert_eq!(
+        attendee.params()["X-ADDRESS"].value(),
+        "1 Infinite Loop\nCupertino, CA"
+    );
+    assert_eq!(jcal::to_value(&decoded), json);
+}
+
+#[test]
+fn type_other_than_default_becomes_value_parameter() {
+    let json = json!(["vcalendar", [], [
+        ["vevent", [
+            ["dtstart", {}, "date", "2026-03-02"],
+            ["x-flag", {}, "boolean", true],
+            ["rrule", {}, "recur", {"count": 3, "freq": "DAILY", "byhour": [9, 17]}],
+        ], []],
+    ]]);
+    let calendar = jcal::decode(&json).unwrap();
+    assert_eq!(
+        calendar.to_string(),
+        "BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+DTSTART;VALUE=DATE:20260302\r
+X-FLAG;VALUE=BOOLEAN:TRUE\r
+RRULE:FREQ=DAILY;COUNT=3;BYHOUR=9,17\r
+END:VEVENT\r
+END:VCALENDAR\r
+"
+    );
+}
+
+#[test]
+fn rejects_malformed_input() {
+    assert!(matches!(
+        jcal::from_str("[\"vcalendar\""),
+        Err(JcalError::Json(_))
+    ));
+    assert_eq!(
+        jcal::decode(&json!(["vevent", [], []])).unwrap_err(),
+        JcalError::Malformed("the outermost component must be vcalendar")
+    );
+    assert!(matches!(
+        jcal::decode(&json!(["vcalendar", [["version", {}, "text"]], []])),
+        Err(JcalError::Malformed(_))
+    ));
+    assert!(matches!(
+        jcal::decode(&json!(["vcalendar", {}, []])),
+        Err(JcalError::Malformed(_))
+    ));
+}
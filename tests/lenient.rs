@@ -0,0 +1,137 @@
+#![cfg(feature = "parser")]
+use std::{env, fs, path::PathBuf};
+
+use icalendar::{
+    Calendar, Component,
+    parser::{WarningKind, read_calendar_lenient},
+};
+use pretty_assertions::assert_eq;
+
+fn fixture(name: &str) -> String {
+    let path = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("fixtures")
+        .join(name);
+    fs::read_to_string(path).unwrap()
+}
+
+fn messages(input: &str) -> Vec<String> {
+    let (_, warnings) = read_calendar_lenient(input);
+    warnings.iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn property_without_value() {
+    let (calendar, warnings) =
+        read_calendar_lenient(&fixture("icalendar-rb/single_event_bad_line.ics"));
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(
+        warnings[0].kind,
+        WarningKind::MissingValue {
+            name: "X-NO-VALUE".into()
+        }
+    );
+    assert_eq!(warnings[0].location.line, 20);
+
+    let calendar = Calendar::from(calendar);
+    let event = calendar.components[0].as_event().unwrap();
+    assert_eq!(event.property_value("X-NO-VALUE"), Some(""));
+    assert_eq!(event.get_uid(), Some("bsuidfortestabc123"));
+}
+
+#[test]
+fn date_without_value_date() {
+    let (calendar, warnings) =
+        read_calendar_lenient(&fixture("icalendar-rb/single_event_bad_dtstart.ics"));
+    assert_eq!(
+        warnings
+            .iter()
+            .map(|warning| &warning.kind)
+            .collect::<Vec<_>>(),
+        [&WarningKind::AddedValueDate {
+            name: "DTSTART".into()
+        }]
+    );
+    assert_eq!(warnings[0].location.line, 8);
+
+    let calendar = Calendar::from(calendar);
+    let event = calendar.components[0].as_event().unwrap();
+    assert_eq!(
+        event.get_start(),
+        Some(chrono::NaiveDate::from_ymd_opt(2005, 1, 20).unwrap().into())
+    );
+}
+
+#[test]
+fn well_formed_input_has_no_warnings() {
+    for name in [
+        "event_five_attendees.ics",
+        "icalendar-rb/event.ics",
+        "icalendar-rb/timezone.ics",
+        "icalendar-rb/two_events.ics",
+    ] {
+        let input = fixture(name);
+        let (calendar, warnings) = read_calendar_lenient(&input);
+        assert_eq!(warnings, [], "{name}");
+        assert_eq!(
+            Calendar::from(calendar),
+            input.parse::<Calendar>().unwrap(),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn drops_broken_lines_and_keeps_going() {
+    let input = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+UID:first\r
+DESCRIPTION:folded over\r
+  two lines\r
+BROKEN LINE\r
+END:VEVENT\r
+END:VTODO\r
+BEGIN:VEVENT\r
+UID:second\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+END:VEVENT\r
+BEGIN:VJOURNAL\r
+UID:third\r
+";
+    let (calendar, _) = read_calendar_lenient(input);
+    assert_eq!(calendar.properties.len(), 1);
+    assert_eq!(calendar.components.len(), 3);
+    assert_eq!(calendar.components[0].properties.len(), 2);
+    assert_eq!(calendar.components[1].components[0].name, "VALARM");
+    assert_eq!(calendar.components[2].name, "VJOURNAL");
+
+    assert_eq!(
+        messages(input),
+        [
+            "line 7, column 1: dropped unreadable line",
+            "line 9, column 1: dropped END:VTODO without BEGIN:VTODO",
+            "line 12, column 1: closed BEGIN:VALARM",
+            "line 15, column 1: closed BEGIN:VJOURNAL",
+            "line 1, column 1: closed BEGIN:VCALENDAR",
+        ]
+    );
+}
+
+#[test]
+fn properties_outside_components() {
+    assert_eq!(
+        messages("UID:stray\r\nBEGIN:VEVENT\r\nEND:VEVENT\r\n"),
+        ["line 1, column 1: dropped property outside of a component"]
+    );
+}
+
+#[test]
+fn bare_components() {
+    let (calendar, warnings) = read_calendar_lenient(
+        "BEGIN:VEVENT\nUID:bare\nEND:VEVENT\nBEGIN:VTODO\nUID:todo\nEND:VTODO\n",
+    );
+    assert_eq!(warnings, []);
+    assert!(calendar.properties.is_empty());
+    assert_eq!(calendar.components.len(), 2);
+}
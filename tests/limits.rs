@@ -0,0 +1,173 @@
+#![cfg(feature = "parser")]
+use std::io::{self, BufReader, Read};
+
+use icalendar::parser::{
+    CalendarReader, Limit, Limits, ParseError, read_calendar, read_calendar_with_limits, unfold,
+};
+use pretty_assertions::assert_eq;
+
+const EVENT: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+UID:limits\r
+ATTENDEE;CN=\"Doe; Jane\";ROLE=CHAIR:mailto:jane@example.com\r
+DESCRIPTION:folded over\r
+  two lines\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+#[track_caller]
+fn exceeded(input: &str, limits: Limits) -> (Limit, Option<usize>) {
+    match read_calendar_with_limits(input, &limits) {
+        Err(ParseError::LimitExceeded { location, limit }) => {
+            (limit, location.map(|location| location.line))
+        }
+        other => panic!("expected an exceeded limit, got {other:?}"),
+    }
+}
+
+#[test]
+fn within_limits() {
+    let unfolded = unfold(EVENT);
+    let tight = Limits::default()
+        .max_input_size(unfolded.len())
+        .max_depth(2)
+        .max_components(2)
+        .max_properties(4)
+        .max_parameters(2)
+        .max_line_length(58);
+    let limited = read_calendar_with_limits(&unfolded, &tight).unwrap();
+    assert_eq!(
+        limited.to_string(),
+        read_calendar(&unfolded).unwrap().to_string()
+    );
+}
+
+#[test]
+fn each_limit() {
+    let unfolded = unfold(EVENT);
+    let limits = Limits::none();
+    assert_eq!(
+        exceeded(&unfolded, limits.max_input_size(100)),
+        (Limit::InputSize(100), None)
+    );
+    assert_eq!(
+        exceeded(&unfolded, limits.max_depth(1)),
+        (Limit::Depth(1), Some(3))
+    );
+    assert_eq!(
+        exceeded(&unfolded, limits.max_components(1)),
+        (Limit::Components(1), Some(3))
+    );
+    assert_eq!(
+        exceeded(&unfolded, limits.max_properties(3)),
+        (Limit::Properties(3), Some(6))
+    );
+    // the quoted `;` is not a separator
+    assert_eq!(
+        exceeded(&unfolded, limits.max_parameters(1)),
+        (Limit::Parameters(1), Some(5))
+    );
+    assert_eq!(
+        exceeded(&unfolded, limits.max_line_length(57)),
+        (Limit::LineLength(57), Some(5))
+    );
+}
+
+#[test]
+fn deep_nesting_is_refused_before_parsing() {
+    let depth = 100_000;
+    let input = "BEGIN:X\n".repeat(depth) + &"END:X\n".repeat(depth);
+    let error = read_calendar_with_limits(&input, &Limits::default()).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 17, column 1: components are nested deeper than 16 levels"
+    );
+
+    let mut reader = CalendarReader::with_limits(input.as_bytes(), Limits::default());
+    assert!(matches!(
+        reader.next(),
+        Some(Err(ParseError::LimitExceeded {
+            limit: Limit::Depth(16),
+            ..
+        }))
+    ));
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn reader_counts_across_components() {
+    let limits = Limits::none().max_properties(2);
+    let mut reader = CalendarReader::with_limits(EVENT.as_bytes(), limits);
+    let error = reader.next().unwrap().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 5, column 1: more than 2 properties"
+    );
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn reader_locates_in_folded_input() {
+    let limits = Limits::none().max_line_length(20);
+    let error = CalendarReader::with_limits(EVENT.as_bytes(), limits)
+        .find_map(Result::err)
+        .unwrap();
+    assert_eq!(
+        error.location().map(|location| location.line),
+        Some(5),
+        "{error}"
+    );
+
+    // each physical line fits, the unfolded one doesn't
+    let limits = Limits::none().max_line_length(30);
+    let error = CalendarReader::with_limits(EVENT.as_bytes(), limits)
+        .next()
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(error.location().unwrap().line, 5);
+    let input = EVENT.replace(
+        "ATTENDEE;CN=\"Doe; Jane\";ROLE=CHAIR:mailto:jane@example.com\r\n",
+        "",
+    );
+    let error = CalendarReader::with_limits(input.as_bytes(), limits)
+        .next()
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 5, column 1: line is longer than 30 bytes"
+    );
+}
+
+/// Endless input without a single line break.
+struct Endless;
+
+impl Read for Endless {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        buf.fill(b'X');
+        Ok(buf.len())
+    }
+}
+
+#[test]
+fn reader_does_not_buffer_overlong_lines() {
+    let limits = Limits::default().max_line_length(1000);
+    let mut reader = CalendarReader::with_limits(BufReader::new(Endless), limits);
+    let Some(Err(ParseError::LimitExceeded { location, limit })) = reader.next() else {
+        panic!("expected an exceeded limit");
+    };
+    assert_eq!(limit, Limit::LineLength(1000));
+    assert_eq!(location.unwrap().snippet.len(), 1002);
+}
+
+#[test]
+fn reader_input_size() {
+    let limits = Limits::none().max_input_size(50);
+    let error = CalendarReader::with_limits(EVENT.as_bytes(), limits)
+        .find_map(Result::err)
+        .unwrap();
+    assert_eq!(error.to_string(), "input is larger than 50 bytes");
+    assert_eq!(error.location(), None);
+}
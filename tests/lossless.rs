@@ -0,0 +1,120 @@
+#![cfg(feature = "parser")]
+use icalendar::*;
+use pretty_assertions::assert_eq;
+
+const INPUT: &str = "BEGIN:VCALENDAR\r
+PRODID:-//server//EN\r
+VERSION:2.0\r
+X-WR-CALNAME;x-unknown=\"quoted\":Work\r
+BEGIN:VEVENT\r
+UID:lossless@example.com\r
+SUMMARY:Planning\r
+DTSTART;TZID=Europe/Berlin:20240301T100000\r
+ATTENDEE;CN=\"Doe, Jane\";PARTSTAT=ACCEPTED:mailto:jane@example.com\r
+DTSTAMP:20240301T090000Z\r
+CATEGORIES:work\r
+DESCRIPTION:a description that the server folded somewhere in the mid\r
+ dle\\nwith an escaped line break\r
+ATTENDEE;CN=Bob:mailto:bob@example.com\r
+CATEGORIES:meeting\r
+BEGIN:VALARM\r
+TRIGGER;RELATED=START:-PT15M\r
+ACTION:DISPLAY\r
+DESCRIPTION:Reminder\r
+END:VALARM\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+fn event(calendar: &mut Calendar) -> &mut Event {
+    match calendar.components.first_mut() {
+        Some(CalendarComponent::Event(event)) => event,
+        _ => panic!("no event"),
+    }
+}
+
+#[test]
+fn unchanged_calendars_are_written_as_read() {
+    let calendar = Calendar::from_str_lossless(INPUT).unwrap();
+    assert_eq!(calendar.to_string(), INPUT);
+    // what was read is the same as with `str::parse`
+    assert_eq!(calendar, INPUT.parse::<Calendar>().unwrap());
+}
+
+#[test]
+fn modified_properties_stay_in_place() {
+    let mut calendar = Calendar::from_str_lossless(INPUT).unwrap();
+    event(&mut calendar)
+        .summary("Planning (moved)")
+        .append_property(("X-SYNC", "1"));
+    let description = event(&mut calendar).get_description().unwrap().to_owned();
+    assert_eq!(
+        description,
+        "a description that the server folded somewhere in the middle\nwith an escaped line break"
+    );
+
+    let expected = INPUT
+        .replace("SUMMARY:Planning\r", "SUMMARY:Planning (moved)\r")
+        .replace(
+            "CATEGORIES:meeting\r\n",
+            "CATEGORIES:meeting\r\nX-SYNC:1\r\n",
+        );
+    assert_eq!(calendar.to_string(), expected);
+
+    // setting an equal value changes nothing
+    let mut calendar = Calendar::from_str_lossless(INPUT).unwrap();
+    event(&mut calendar).description(&description);
+    assert_eq!(calendar.to_string(), INPUT);
+}
+
+#[test]
+fn modified_parameters_are_rewritten() {
+    let mut calendar = Calendar::from_str_lossless(INPUT).unwrap();
+    calendar
+        .properties
+        .iter_mut()
+        .find(|property| property.key() == "X-WR-CALNAME")
+        .unwrap()
+        .add_parameter("X-COLOR", "red");
+    assert_eq!(
+        calendar.to_string(),
+        INPUT.replace(
+            "X-WR-CALNAME;x-unknown=\"quoted\":Work",
+            "X-WR-CALNAME;x-unknown=quoted;X-COLOR=red:Work"
+        )
+    );
+}
+
+#[test]
+fn repeated_single_properties_are_kept() {
+    let input = INPUT.replace(
+        "CATEGORIES:meeting\r\n",
+        "CATEGORIES:meeting\r\nSUMMARY:Planning again\r\n",
+    );
+    let mut calendar = Calendar::from_str_lossless(&input).unwrap();
+    assert_eq!(event(&mut calendar).get_summary(), Some("Planning"));
+    assert_eq!(
+        event(&mut calendar).multi_properties()["SUMMARY"][0].value(),
+        "Planning again"
+    );
+    assert_eq!(calendar.to_string(), input);
+
+    // `str::parse` keeps only the last one
+    let parsed = input.parse::<Calendar>().unwrap();
+    assert_eq!(
+        parsed.components[0].as_event().unwrap().get_summary(),
+        Some("Planning again")
+    );
+}
+
+#[test]
+fn removed_properties_are_dropped() {
+    let mut calendar = Calendar::from_str_lossless(INPUT).unwrap();
+    event(&mut calendar).remove_multi_property("CATEGORIES");
+    assert_eq!(
+        calendar.to_string(),
+        INPUT
+            .replace("CATEGORIES:work\r\n", "")
+            .replace("CATEGORIES:meeting\r\n", "")
+    );
+}
@@ -0,0 +1,188 @@
+#![cfg(feature = "parser")]
+use icalendar::*;
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::{calendar, component, messages};
+
+const TIMEZONE: &str = "BEGIN:VTIMEZONE\r
+TZID:Europe/Berlin\r
+BEGIN:STANDARD\r
+DTSTART:19701025T030000\r
+TZOFFSETFROM:+0200\r
+TZOFFSETTO:+0100\r
+END:STANDARD\r
+END:VTIMEZONE\r
+";
+
+fn summaries(calendar: &Calendar) -> Vec<&str> {
+    calendar
+        .components
+        .iter()
+        .filter_map(|component| component.property_value("SUMMARY"))
+        .collect()
+}
+
+#[test]
+fn newest_version_wins() {
+    let mut ours = calendar(&format!(
+        "{}{}{}",
+        component(
+            "VEVENT",
+            "UID:sequence\r\nSEQUENCE:2\r\nDTSTAMP:20240301T100000Z\r\nSUMMARY:ours\r\n"
+        ),
+        component(
+            "VEVENT",
+            "UID:modified\r\nLAST-MODIFIED:20240301T100000Z\r\nDTSTAMP:20240305T100000Z\r\nSUMMARY:ours\r\n"
+        ),
+        component(
+            "VEVENT",
+            "UID:stamped\r\nDTSTAMP:20240301T100000Z\r\nSUMMARY:ours\r\n"
+        ),
+    ));
+    let theirs = calendar(&format!(
+        "{}{}{}{}",
+        // a lower sequence loses despite the later DTSTAMP
+        component(
+            "VEVENT",
+            "UID:sequence\r\nSEQUENCE:1\r\nDTSTAMP:20240309T100000Z\r\nSUMMARY:theirs\r\n"
+        ),
+        // LAST-MODIFIED counts before DTSTAMP
+        component(
+            "VEVENT",
+            "UID:modified\r\nLAST-MODIFIED:20240302T100000Z\r\nDTSTAMP:20240302T100000Z\r\nSUMMARY:theirs\r\n"
+        ),
+        component(
+            "VEVENT",
+            "UID:stamped\r\nDTSTAMP:20240302T100000Z\r\nSUMMARY:theirs\r\n"
+        ),
+        component(
+            "VEVENT",
+            "UID:new\r\nDTSTAMP:20240302T100000Z\r\nSUMMARY:theirs\r\n"
+        ),
+    ));
+
+    let merged = ours.merge(theirs, &MergeStrategy::Newest);
+    assert_eq!(
+        messages(&merged),
+        [
+            "VEVENT sequence: unchanged",
+            "VEVENT modified: replaced",
+            "VEVENT stamped: replaced",
+            "VEVENT new: added",
+        ]
+    );
+    assert_eq!(summaries(&ours), ["ours", "theirs", "theirs", "theirs"]);
+}
+
+#[test]
+fn overrides_are_matched_by_recurrence_id() {
+    let master = component(
+        "VEVENT",
+        "UID:series\r\nDTSTAMP:20240301T100000Z\r\nRRULE:FREQ=DAILY\r\nSUMMARY:master\r\n",
+    );
+    let mut ours = calendar(&format!(
+        "{master}{}",
+        component(
+            "VEVENT",
+            "UID:series\r\nDTSTAMP:20240301T100000Z\r\nRECURRENCE-ID:20240302T100000Z\r\nSUMMARY:ours\r\n"
+        ),
+    ));
+    let theirs = calendar(&format!(
+        "{master}{}{}",
+        component(
+            "VEVENT",
+            "UID:series\r\nDTSTAMP:20240301T100000Z\r\nSEQUENCE:1\r\nRECURRENCE-ID:20240302T100000Z\r\nSUMMARY:theirs\r\n"
+        ),
+        component(
+            "VEVENT",
+            "UID:series\r\nDTSTAMP:20240301T100000Z\r\nRECURRENCE-ID:20240303T100000Z\r\nSUMMARY:theirs\r\n"
+        ),
+    ));
+
+    let merged = ours.merge(theirs, &MergeStrategy::default());
+    assert_eq!(
+        messages(&merged),
+        [
+            "VEVENT series: unchanged",
+            "VEVENT series at 20240302T100000Z: replaced",
+            "VEVENT series at 20240303T100000Z: added",
+        ]
+    );
+    assert_eq!(merged[1].recurrence_id.as_deref(), Some("20240302T100000Z"));
+    assert_eq!(summaries(&ours), ["master", "theirs", "theirs"]);
+}
+
+#[cfg(feature = "chrono-tz")]
+#[test]
+fn recurrence_ids_match_across_time_zones() {
+    let mut ours = calendar(&component(
+        "VEVENT",
+        "UID:series\r\nDTSTAMP:20240301T100000Z\r\nRECURRENCE-ID;TZID=Europe/Berlin:20260302T100000\r\nSUMMARY:ours\r\n",
+    ));
+    let theirs = calendar(&component(
+        "VEVENT",
+        "UID:series\r\nDTSTAMP:20240301T100000Z\r\nSEQUENCE:1\r\nRECURRENCE-ID:20260302T090000Z\r\nSUMMARY:theirs\r\n",
+    ));
+
+    let merged = ours.merge(theirs, &MergeStrategy::default());
+    assert_eq!(
+        messages(&merged),
+        ["VEVENT series at 20260302T090000Z: replaced"]
+    );
+    assert_eq!(summaries(&ours), ["theirs"]);
+}
+
+#[test]
+fn timezones_are_deduplicated() {
+    let mut ours = calendar(TIMEZONE);
+    let theirs = calendar(&format!("{}{TIMEZONE}", TIMEZONE.replace("+0100", "+0000")));
+    assert_eq!(
+        messages(&ours.merge(theirs, &MergeStrategy::Theirs)),
+        [
+            "VTIMEZONE Europe/Berlin: unchanged",
+            "VTIMEZONE Europe/Berlin: unchanged",
+        ]
+    );
+    assert_eq!(ours.components.len(), 1);
+    assert_eq!(
+        ours.components[0].components()[0].property_value("TZOFFSETTO"),
+        Some("+0100")
+    );
+}
+
+#[test]
+fn custom_strategies_decide() {
+    let mut ours = calendar(&component(
+        "VEVENT",
+        "UID:custom\r\nDTSTAMP:20240301T100000Z\r\nSUMMARY:ours\r\nLOCATION:here\r\n",
+    ));
+    let theirs = calendar(&component(
+        "VEVENT",
+        "UID:custom\r\nDTSTAMP:20240302T100000Z\r\nSUMMARY:theirs\r\n",
+    ));
+
+    // never lose a location
+    let strategy = MergeStrategy::custom(|ours, theirs| {
+        if ours.property_value("LOCATION").is_some() && theirs.property_value("LOCATION").is_none()
+        {
+            Side::Ours
+        } else {
+            Side::Theirs
+        }
+    });
+    assert_eq!(
+        messages(&ours.clone().merge(theirs.clone(), &strategy)),
+        ["VEVENT custom: unchanged"]
+    );
+
+    assert_eq!(
+        messages(&ours.merge(theirs.clone(), &MergeStrategy::Theirs)),
+        ["VEVENT custom: replaced"]
+    );
+    // merging the same version again changes nothing
+    assert_eq!(
+        messages(&ours.merge(theirs, &MergeStrategy::Theirs)),
+        ["VEVENT custom: unchanged"]
+    );
+}
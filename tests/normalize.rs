@@ -0,0 +1,326 @@
+#![cfg(feature = "parser")]
+use chrono::{TimeZone, Utc};
+use icalendar::*;
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::{calendar, component, event, messages};
+
+#[test]
+fn repairs_what_validation_reports() {
+    let mut calendar = Calendar::empty();
+    calendar.push(
+        Event::new()
+            .starts(Utc.with_ymd_and_hms(2024, 3, 1, 10, 0, 0).unwrap())
+            .append_multi_property(("SUMMARY", "one"))
+            .append_multi_property(("SUMMARY", "two"))
+            .append_property(
+                Property::new("location", "here")
+                    .add_parameter("language", "en")
+                    .done(),
+            )
+            .add_property("DTEND", "20240301T110000")
+            .add_property("DURATION", "PT1H")
+            .done(),
+    );
+    assert!(!calendar.validate().is_empty());
+
+    let changes = calendar.normalize();
+    let kinds = changes
+        .iter()
+        .map(|change| &change.kind)
+        .filter(|kind| !matches!(kind, ChangeKind::AddedProperty { .. }))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            // the generated VERSION and PRODID are appended
+            &ChangeKind::ReorderedProperties,
+            &ChangeKind::RenamedProperty {
+                from: "location".into(),
+                to: "LOCATION".into()
+            },
+            &ChangeKind::RenamedParameter {
+                property: "LOCATION".into(),
+                from: "language".into(),
+                to: "LANGUAGE".into()
+            },
+            &ChangeKind::RemovedProperty {
+                name: "SUMMARY".into(),
+                value: "two".into()
+            },
+            &ChangeKind::RemovedProperty {
+                name: "DURATION".into(),
+                value: "PT1H".into()
+            },
+        ]
+    );
+    let added = changes
+        .iter()
+        .filter_map(|change| match &change.kind {
+            ChangeKind::AddedProperty { name, .. } => Some((change.path.as_str(), name.as_str())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        added,
+        [
+            ("VCALENDAR", "PRODID"),
+            ("VCALENDAR", "VERSION"),
+            ("VCALENDAR/VEVENT[0]", "DTSTAMP"),
+            ("VCALENDAR/VEVENT[0]", "UID"),
+        ]
+    );
+
+    assert_eq!(calendar.validate(), []);
+    let event = calendar.components[0].as_event().unwrap();
+    assert_eq!(event.get_summary(), Some("one"));
+    assert_eq!(event.get_location(), Some("here"));
+    assert!(
+        event.properties()["LOCATION"]
+            .params()
+            .contains_key("LANGUAGE")
+    );
+    assert_eq!(calendar.normalize(), []);
+}
+
+#[test]
+fn durations_become_ends() {
+    let options = Normalization::new().ends(EndStyle::End);
+    let mut calendar = calendar(
+        "BEGIN:VEVENT\r
+UID:1\r
+DTSTAMP:20240301T100000Z\r
+DTSTART;VALUE=DATE:20240301\r
+DURATION:P2D\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:2\r
+DTSTAMP:20240301T100000Z\r
+DTSTART;VALUE=DATE:20240301\r
+DURATION:PT1H\r
+END:VEVENT\r
+BEGIN:VTODO\r
+UID:3\r
+DTSTAMP:20240301T100000Z\r
+DTSTART;TZID=Europe/Berlin:20240301T230000\r
+DURATION:PT2H\r
+END:VTODO\r
+",
+    );
+    assert_eq!(
+        messages(&calendar.normalize_with(&options)),
+        [
+            "VCALENDAR/VEVENT[0]: replaced DURATION with DTEND:20240303",
+            "VCALENDAR/VTODO[2]: replaced DURATION with DUE:20240302T010000",
+        ]
+    );
+    let end = calendar.components[0].properties()["DTEND"].clone();
+    assert_eq!(end.value_type(), Some(ValueType::Date));
+    // a date can't end within the day
+    assert!(calendar.components[1].properties().contains_key("DURATION"));
+    let due = &calendar.components[2].properties()["DUE"];
+    assert_eq!(due.params()["TZID"].value(), "Europe/Berlin");
+}
+
+#[test]
+fn ends_become_durations() {
+    let options = Normalization::new().ends(EndStyle::Duration);
+    let mut calendar = event(
+        "DTSTART:20240301T100000Z\r
+DTEND:20240301T113000Z\r
+",
+    );
+    assert_eq!(
+        messages(&calendar.normalize_with(&options)),
+        ["VCALENDAR/VEVENT[0]: replaced DTEND with DURATION:PT5400S"]
+    );
+    assert_eq!(calendar.components[0].property_value("DTEND"), None);
+    assert_eq!(calendar.validate(), []);
+
+    // different time zones can't be subtracted without knowing them
+    let mut calendar = event(
+        "DTSTART;TZID=Europe/Berlin:20240301T100000\r
+DTEND;TZID=Europe/London:20240301T100000\r
+",
+    );
+    assert_eq!(calendar.normalize_with(&options), []);
+}
+
+#[test]
+fn timezones_are_canonicalized() {
+    let mut calendar = calendar(
+        "X-WR-TIMEZONE:Eastern Standard Time\r
+BEGIN:VTIMEZONE\r
+TZID:/mozilla.org/20050126_1/Europe/Berlin\r
+BEGIN:STANDARD\r
+DTSTART:19701025T030000\r
+TZOFFSETFROM:+0200\r
+TZOFFSETTO:+0100\r
+END:STANDARD\r
+END:VTIMEZONE\r
+BEGIN:VEVENT\r
+UID:1\r
+DTSTAMP:20240301T100000Z\r
+DTSTART;TZID=/mozilla.org/20050126_1/Europe/Berlin:20240301T100000\r
+DTEND;TZID=Eastern Standard Time:20240301T100000\r
+EXDATE;TZID=W. Europe Standard Time:20240308T100000\r
+END:VEVENT\r
+",
+    );
+    assert_eq!(
+        messages(&calendar.normalize()),
+        [
+            "VCALENDAR: renamed time zone \"/mozilla.org/20050126_1/Europe/Berlin\" to \"Europe/Berlin\"",
+            "VCALENDAR: renamed time zone \"Eastern Standard Time\" to \"America/New_York\"",
+        ]
+    );
+    assert_eq!(calendar.get_timezone(), Some("America/New_York"));
+    assert_eq!(
+        calendar.components[0].property_value("TZID"),
+        Some("Europe/Berlin")
+    );
+    let event = &calendar.components[1];
+    let tzid = |name: &str| event.properties()[name].params()["TZID"].value().to_owned();
+    assert_eq!(tzid("DTSTART"), "Europe/Berlin");
+    assert_eq!(tzid("DTEND"), "America/New_York");
+    // renaming it as well would merge two distinct VTIMEZONEs
+    assert_eq!(
+        event.multi_properties()["EXDATE"][0].params()["TZID"].value(),
+        "W. Europe Standard Time"
+    );
+}
+
+#[test]
+fn floating_times_get_a_timezone() {
+    let options = Normalization::new().floating_timezone("Europe/Berlin");
+    let mut calendar = calendar(
+        "BEGIN:VTIMEZONE\r
+TZID:Europe/Berlin\r
+BEGIN:STANDARD\r
+DTSTART:19701025T030000\r
+TZOFFSETFROM:+0200\r
+TZOFFSETTO:+0100\r
+END:STANDARD\r
+END:VTIMEZONE\r
+BEGIN:VEVENT\r
+UID:1\r
+DTSTAMP:20240301T100000Z\r
+DTSTART:20240301T100000\r
+DTEND:20240301T110000Z\r
+EXDATE:20240308T100000,20240315T100000\r
+RDATE;VALUE=DATE:20240322\r
+END:VEVENT\r
+",
+    );
+    assert_eq!(
+        messages(&calendar.normalize_with(&options)),
+        [
+            "VCALENDAR/VEVENT[1]: placed floating DTSTART in time zone \"Europe/Berlin\"",
+            "VCALENDAR/VEVENT[1]: placed floating EXDATE in time zone \"Europe/Berlin\"",
+        ]
+    );
+    assert_eq!(calendar.validate(), []);
+    let standard = &calendar.components[0].components()[0];
+    assert!(standard.properties()["DTSTART"].params().is_empty());
+}
+
+#[cfg(feature = "chrono-tz")]
+#[test]
+fn missing_timezone_is_generated() {
+    let options = Normalization::new().floating_timezone("Europe/Berlin");
+    let mut calendar = event("DTSTART:20240301T100000\r\nRRULE:FREQ=WEEKLY\r\n");
+    assert_eq!(
+        messages(&calendar.normalize_with(&options)),
+        [
+            "VCALENDAR/VEVENT[0]: placed floating DTSTART in time zone \"Europe/Berlin\"",
+            "VCALENDAR/VTIMEZONE[1]: added VTIMEZONE \"Europe/Berlin\"",
+        ]
+    );
+    assert_eq!(calendar.validate(), []);
+
+    let timezone = &calendar.components[1];
+    assert_eq!(timezone.property_value("TZID"), Some("Europe/Berlin"));
+    let observances = timezone.components();
+    // the start of 2024 and two changes a year until the end of 2034
+    assert_eq!(observances.len(), 23);
+    let summer = &observances[1];
+    assert_eq!(summer.component_kind(), "DAYLIGHT");
+    assert_eq!(summer.property_value("DTSTART"), Some("20240331T020000"));
+    assert_eq!(summer.property_value("TZOFFSETFROM"), Some("+0100"));
+    assert_eq!(summer.property_value("TZOFFSETTO"), Some("+0200"));
+    assert_eq!(summer.property_value("TZNAME"), Some("CEST"));
+    assert_eq!(
+        observances[2].property_value("DTSTART"),
+        Some("20241027T030000")
+    );
+}
+
+#[test]
+fn unknown_timezone_leaves_floating_times() {
+    let options = Normalization::new().floating_timezone("Nowhere/Special");
+    let mut calendar = event("DTSTART:20240301T100000\r\n");
+    assert_eq!(
+        messages(&calendar.normalize_with(&options)),
+        [] as [String; 0]
+    );
+    assert_eq!(calendar.validate(), []);
+    assert!(
+        calendar.components[0].properties()["DTSTART"]
+            .params()
+            .is_empty()
+    );
+}
+
+#[test]
+fn properties_are_ordered() {
+    let mut calendar = Calendar::empty();
+    calendar
+        .append_property(("X-WR-CALNAME", "ordered"))
+        .append_property(("METHOD", "PUBLISH"))
+        .append_property(("PRODID", "normalize"))
+        .append_property(("VERSION", "2.0"));
+    calendar.push(
+        Event::new()
+            .uid("1")
+            .add_property("DTSTAMP", "20240301T100000Z")
+            .add_property("DTSTART", "20240301T100000Z")
+            .append_multi_property(("CATEGORIES", "work"))
+            .append_multi_property(("CATEGORIES", "meeting"))
+            .done(),
+    );
+    assert_eq!(
+        messages(&calendar.normalize()),
+        [
+            "VCALENDAR: reordered properties",
+            "VCALENDAR/VEVENT[0]: reordered properties",
+        ]
+    );
+    assert_eq!(
+        calendar
+            .properties
+            .iter()
+            .map(Property::key)
+            .collect::<Vec<_>>(),
+        ["VERSION", "PRODID", "METHOD", "X-WR-CALNAME"]
+    );
+    assert_eq!(
+        calendar.components[0].multi_properties()["CATEGORIES"]
+            .iter()
+            .map(Property::value)
+            .collect::<Vec<_>>(),
+        ["meeting", "work"]
+    );
+}
+
+#[test]
+fn stable_normalization_generates_no_ids() {
+    let mut calendar = calendar(&component("VEVENT", "SUMMARY:No UID or DTSTAMP\r\n"));
+    let mut again = calendar.clone();
+    let options = Normalization::new().stable();
+    assert_eq!(calendar.normalize_with(&options), []);
+    assert_eq!(again.normalize_with(&options), []);
+    assert_eq!(calendar, again);
+    assert!(!calendar.components[0].properties().contains_key("UID"));
+    assert!(!calendar.components[0].properties().contains_key("DTSTAMP"));
+}
@@ -0,0 +1,181 @@
+#![cfg(feature = "parser")]
+use icalendar::{Calendar, Component, ordering};
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::{calendar, component};
+
+fn uids(calendar: &Calendar) -> Vec<&str> {
+    calendar
+        .components
+        .iter()
+        .map(|component| component.get_uid().unwrap_or("-"))
+        .collect()
+}
+
+const BERLIN: &str = "BEGIN:VTIMEZONE\r\nTZID:Europe/Berlin\r\nBEGIN:STANDARD\r\nDTSTART:19701025T030000\r\nTZOFFSETFROM:+0200\r\nTZOFFSETTO:+0100\r\nEND:STANDARD\r\nBEGIN:DAYLIGHT\r\nDTSTART:19700329T020000\r\nTZOFFSETFROM:+0100\r\nTZOFFSETTO:+0200\r\nEND:DAYLIGHT\r\nEND:VTIMEZONE\r\n";
+
+#[cfg(feature = "chrono-tz")]
+fn mixed() -> String {
+    [
+        component("VTODO", "UID:created\r\nCREATED:20260302T070000Z\r\n"),
+        component("VEVENT", "UID:utc\r\nDTSTART:20260302T083000Z\r\n"),
+        component("VJOURNAL", "UID:undated\r\n"),
+        component(
+            "VEVENT",
+            "UID:zoned\r\nDTSTART;TZID=Europe/Berlin:20260302T090000\r\n",
+        ),
+        component("VEVENT", "UID:floating\r\nDTSTART:20260302T090000\r\n"),
+        component("VTODO", "UID:due\r\nDUE;VALUE=DATE:20260303\r\n"),
+        component("VEVENT", "UID:all-day\r\nDTSTART;VALUE=DATE:20260302\r\n"),
+        BERLIN.to_owned(),
+    ]
+    .concat()
+}
+
+#[cfg(feature = "chrono-tz")]
+#[test]
+fn sorts_by_effective_start() {
+    let mut calendar = calendar(&mixed());
+    calendar.sort_by_start();
+    assert_eq!(
+        uids(&calendar),
+        [
+            "-", "all-day", "created", "zoned", "utc", "floating", "due", "undated"
+        ]
+    );
+}
+
+#[cfg(feature = "chrono-tz")]
+#[test]
+fn floating_values_use_the_reference_timezone() {
+    let mut calendar = calendar(&mixed());
+    calendar.append_property(("X-WR-TIMEZONE", "Asia/Tokyo"));
+    calendar.sort_by_start();
+    // midnight and 9:00 in Tokyo are 15:00 and 00:00 UTC
+    assert_eq!(
+        uids(&calendar),
+        [
+            "-", "all-day", "floating", "created", "zoned", "utc", "due", "undated"
+        ]
+    );
+}
+
+#[cfg(feature = "chrono-tz")]
+#[test]
+fn dates_come_before_date_times_at_the_same_instant() {
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use icalendar::{DatePerhapsTime, ordering::ByStart};
+    use std::cmp::Ordering;
+
+    let date = DatePerhapsTime::from(NaiveDate::from_ymd_opt(2026, 3, 2).unwrap());
+    let midnight = DatePerhapsTime::from(Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap());
+    let order = ByStart::new();
+    assert_eq!(order.compare_values(&date, &midnight), Ordering::Less);
+    assert_eq!(order.compare_values(&midnight, &date), Ordering::Greater);
+    assert_eq!(order.compare_values(&date, &date), Ordering::Equal);
+
+    let tokyo = order.with_timezone(chrono_tz::Asia::Tokyo);
+    assert_eq!(tokyo.compare_values(&date, &midnight), Ordering::Less);
+    assert_eq!(
+        tokyo.compare_values(
+            &midnight,
+            &DatePerhapsTime::from(
+                NaiveDate::from_ymd_opt(2026, 3, 1)
+                    .unwrap()
+                    .and_hms_opt(23, 0, 0)
+                    .unwrap()
+            )
+        ),
+        Ordering::Greater
+    );
+}
+
+#[test]
+fn canonical_order_does_not_depend_on_input_order() {
+    let alarms = [
+        component(
+            "VALARM",
+            "ACTION:DISPLAY\r\nTRIGGER:-PT5M\r\nDESCRIPTION:b\r\n",
+        ),
+        component(
+            "VALARM",
+            "ACTION:DISPLAY\r\nTRIGGER:-PT5M\r\nDESCRIPTION:a\r\n",
+        ),
+    ];
+    let master = |alarms: &[String]| {
+        let lines = format!("UID:a\r\nDTSTAMP:20260301T000000Z\r\n{}", alarms.concat());
+        component("VEVENT", &lines)
+    };
+    let override_ = component(
+        "VEVENT",
+        "UID:a\r\nDTSTAMP:20260301T000000Z\r\nRECURRENCE-ID:20260302T000000Z\r\n",
+    );
+    let todo = component("VTODO", "UID:0\r\nDTSTAMP:20260301T000000Z\r\n");
+    let other = component("VEVENT", "UID:b\r\nDTSTAMP:20260301T000000Z\r\n");
+
+    let mut forward = calendar(
+        &[
+            master(&alarms),
+            override_.clone(),
+            todo.clone(),
+            other.clone(),
+            BERLIN.to_owned(),
+        ]
+        .concat(),
+    );
+    let reversed_alarms = [alarms[1].clone(), alarms[0].clone()];
+    let mut backward = calendar(
+        &[
+            BERLIN.to_owned(),
+            other,
+            todo,
+            override_,
+            master(&reversed_alarms),
+        ]
+        .concat(),
+    );
+    assert_ne!(forward, backward);
+
+    forward.sort_canonical();
+    backward.sort_canonical();
+    assert_eq!(forward, backward);
+    assert_eq!(uids(&forward), ["-", "a", "a", "b", "0"]);
+    assert_eq!(
+        forward.components[2].property_value("RECURRENCE-ID"),
+        Some("20260302T000000Z")
+    );
+    let alarm = &forward.components[1].components()[0];
+    assert_eq!(alarm.property_value("DESCRIPTION"), Some("a"));
+
+    let mut components = backward.components.clone();
+    components.reverse();
+    components.sort_by(ordering::canonical);
+    assert_eq!(components, backward.components);
+}
+
+#[test]
+fn canonical_order_sorts_repeated_properties_and_parameters() {
+    let event = |lines: &str| {
+        calendar(&component(
+            "VEVENT",
+            &format!("UID:a\r\nDTSTAMP:20260301T000000Z\r\n{lines}"),
+        ))
+    };
+    let mut forward = event(
+        "ATTENDEE;ROLE=CHAIR;CN=Bob:mailto:bob@example.com\r\nATTENDEE:mailto:alice@example.com\r\nCATEGORIES:work\r\nCATEGORIES:meeting\r\n",
+    );
+    let mut backward = event(
+        "CATEGORIES:meeting\r\nATTENDEE:mailto:alice@example.com\r\nCATEGORIES:work\r\nATTENDEE;CN=Bob;ROLE=CHAIR:mailto:bob@example.com\r\n",
+    );
+    assert_ne!(forward.to_string(), backward.to_string());
+
+    forward.sort_canonical();
+    backward.sort_canonical();
+    assert_eq!(forward.to_string(), backward.to_string());
+    assert!(
+        forward
+            .to_string()
+            .contains("ATTENDEE;CN=Bob;ROLE=CHAIR:mailto:bob@example.com\r\n")
+    );
+}
@@ -0,0 +1,205 @@
+#![cfg(feature = "parser")]
+use icalendar::{Attendee, Calendar, Component, Parameter, Property, parser::unfold};
+use pretty_assertions::assert_eq;
+
+const ATTENDEE: &str = "ATTENDEE;X-TAG=one;MEMBER=\"mailto:a@example.com\",\"mailto:b@example.com\";X-TAG=two,three;CN=\"Doe, Jane\";MEMBER=\"mailto:c@example.com\":mailto:jane@example.com";
+
+fn event(line: &str) -> String {
+    format!(
+        "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:params\r\nDTSTAMP:20240301T100000Z\r\n{line}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n"
+    )
+}
+
+#[test]
+fn repeated_and_listed_parameters_survive_a_round_trip() {
+    let calendar: Calendar = event(ATTENDEE).parse().unwrap();
+    let attendee = &calendar.components[0].multi_properties()["ATTENDEE"][0];
+    assert_eq!(
+        attendee
+            .params()
+            .iter()
+            .map(|param| param.key())
+            .collect::<Vec<_>>(),
+        ["X-TAG", "MEMBER", "X-TAG", "CN", "MEMBER"]
+    );
+    assert_eq!(attendee.param_values("X-TAG"), ["one", "two", "three"]);
+    assert_eq!(
+        attendee.param_values("MEMBER"),
+        [
+            "mailto:a@example.com",
+            "mailto:b@example.com",
+            "mailto:c@example.com"
+        ]
+    );
+    assert_eq!(attendee.param_values("CN"), ["Doe, Jane"]);
+    assert_eq!(attendee.params()["MEMBER"].values().len(), 2);
+
+    let written = calendar.to_string();
+    assert!(
+        unfold(&written).lines().any(|line| line == ATTENDEE),
+        "{written}"
+    );
+    assert_eq!(written.parse::<Calendar>().unwrap(), calendar);
+}
+
+#[test]
+fn append_replaces_and_append_multi_repeats() {
+    let property = Property::new("X-PROP", "value")
+        .add_parameter("A", "1")
+        .add_parameter("B", "2")
+        .append_multi_parameter(("A", "3"))
+        .done();
+    assert_eq!(property.param_values("A"), ["1", "3"]);
+    assert_eq!(property.params().get("A").unwrap().value(), "1");
+
+    // replacing keeps the position of the first one
+    let mut property = property;
+    property.add_parameter("A", "4");
+    assert_eq!(
+        property
+            .params()
+            .iter()
+            .map(|param| (param.key(), param.value()))
+            .collect::<Vec<_>>(),
+        [("A", "4"), ("B", "2")]
+    );
+}
+
+#[test]
+fn attendee_collects_all_members() {
+    let calendar: Calendar = event(ATTENDEE).parse().unwrap();
+    let property = &calendar.components[0].multi_properties()["ATTENDEE"][0];
+    let attendee = Attendee::try_from(property).unwrap();
+    assert_eq!(
+        attendee.member,
+        [
+            "mailto:a@example.com",
+            "mailto:b@example.com",
+            "mailto:c@example.com"
+        ]
+    );
+    assert_eq!(attendee.cn.as_deref(), Some("Doe, Jane"));
+}
+
+#[test]
+fn caret_encoding_round_trips() {
+    const LINE: &str = "ATTENDEE;CN=^'Bob^' ^^_^;X-ADDRESS=\"1 Infinite Loop^nCupertino, CA\":mailto:bob@example.com";
+    let calendar: Calendar = event(LINE).parse().unwrap();
+    let attendee = &calendar.components[0].multi_properties()["ATTENDEE"][0];
+    assert_eq!(attendee.params()["CN"].value(), "\"Bob\" ^_^");
+    assert_eq!(
+        attendee.params()["X-ADDRESS"].value(),
+        "1 Infinite Loop\nCupertino, CA"
+    );
+    assert_eq!(
+        attendee.param_values("X-ADDRESS"),
+        ["1 Infinite Loop\nCupertino, CA"]
+    );
+
+    let written = calendar.to_string();
+    assert!(
+        unfold(&written).lines().any(|line| line
+            == "ATTENDEE;CN=^'Bob^' ^^_^^;X-ADDRESS=\"1 Infinite Loop^nCupertino, CA\":mailto:bob@example.com"),
+        "{written}"
+    );
+    assert_eq!(written.parse::<Calendar>().unwrap(), calendar);
+}
+
+#[test]
+fn builder_values_are_encoded() {
+    let property = Property::new("ATTENDEE", "mailto:bob@example.com")
+        .add_parameter("CN", "Line one\r\nLine \"two\"")
+        .done();
+    let mut written = Vec::new();
+    property.write_to(&mut written).unwrap();
+    assert_eq!(
+        String::from_utf8(written).unwrap(),
+        "ATTENDEE;CN=Line one^nLine ^'two^':mailto:bob@example.com\r\n"
+    );
+}
+
+#[test]
+fn quoted_comma_is_not_a_list_separator() {
+    const LINE: &str = "ATTENDEE;X-NOTE=\"Smith, John\":mailto:john@example.com";
+    let calendar: Calendar = event(LINE).parse().unwrap();
+    let attendee = &calendar.components[0].multi_properties()["ATTENDEE"][0];
+    assert_eq!(attendee.params()["X-NOTE"].value(), "Smith, John");
+    assert_eq!(attendee.param_values("X-NOTE"), ["Smith, John"]);
+
+    let written = calendar.to_string();
+    assert!(
+        unfold(&written).lines().any(|line| line == LINE),
+        "{written}"
+    );
+    let reparsed = written.parse::<Calendar>().unwrap();
+    assert_eq!(reparsed, calendar);
+    assert_eq!(
+        reparsed.components[0].multi_properties()["ATTENDEE"][0].param_values("X-NOTE"),
+        ["Smith, John"]
+    );
+}
+
+#[test]
+fn list_elements_are_decoded_and_encoded_one_by_one() {
+    const LINE: &str = "ATTENDEE;X-L=\"a^'b\",c^^d:mailto:bob@example.com";
+    let calendar: Calendar = event(LINE).parse().unwrap();
+    let attendee = &calendar.components[0].multi_properties()["ATTENDEE"][0];
+    assert_eq!(attendee.param_values("X-L"), ["a\"b", "c^d"]);
+    let written = calendar.to_string();
+    assert!(
+        unfold(&written).lines().any(|line| line == LINE),
+        "{written}"
+    );
+
+    let property = Property::new("ATTENDEE", "mailto:bob@example.com")
+        .append_parameter(Parameter::list("X-L", ["line\none", "two\r\nlines"]))
+        .done();
+    let mut written = Vec::new();
+    property.write_to(&mut written).unwrap();
+    assert_eq!(
+        String::from_utf8(written).unwrap(),
+        "ATTENDEE;X-L=\"line^none\",\"two^nlines\":mailto:bob@example.com\r\n"
+    );
+    // a line break is written as ^n, whichever it was
+    assert_eq!(property.param_values("X-L"), ["line\none", "two\nlines"]);
+}
+
+#[test]
+fn single_values_with_a_comma_are_quoted() {
+    let property = Property::new("ATTENDEE", "mailto:bob@example.com")
+        .add_parameter("X-ADDRESS", "1 Infinite Loop\nCupertino, CA")
+        .done();
+    let line = "ATTENDEE;X-ADDRESS=\"1 Infinite Loop^nCupertino, CA\":mailto:bob@example.com";
+    let mut written = Vec::new();
+    property.write_to(&mut written).unwrap();
+    assert_eq!(String::from_utf8(written).unwrap(), format!("{line}\r\n"));
+
+    let calendar: Calendar = event(line).parse().unwrap();
+    let attendee = &calendar.components[0].multi_properties()["ATTENDEE"][0];
+    assert_eq!(
+        attendee.params()["X-ADDRESS"],
+        property.params()["X-ADDRESS"]
+    );
+    assert_eq!(
+        attendee.param_values("X-ADDRESS"),
+        ["1 Infinite Loop\nCupertino, CA"]
+    );
+}
+
+#[test]
+fn apple_structured_location_address_is_one_value() {
+    const LINE: &str = "X-APPLE-STRUCTURED-LOCATION;VALUE=URI;X-ADDRESS=\"Apple Inc.^n1 Infinite Loop^nCupertino, CA 95014\";X-TITLE=Apple:geo:37.331741,-122.030333";
+    let calendar: Calendar = event(LINE).parse().unwrap();
+    let location = &calendar.components[0].properties()["X-APPLE-STRUCTURED-LOCATION"];
+    assert_eq!(
+        location.params()["X-ADDRESS"].value(),
+        "Apple Inc.\n1 Infinite Loop\nCupertino, CA 95014"
+    );
+    assert!(!location.params()["X-ADDRESS"].is_list());
+
+    let written = calendar.to_string();
+    assert!(
+        unfold(&written).lines().any(|line| line == LINE),
+        "{written}"
+    );
+}
@@ -0,0 +1,128 @@
+#![cfg(feature = "parser")]
+use icalendar::{
+    Calendar, CalendarComponent,
+    parser::{Location, ParseError, read_calendar, unfold},
+};
+use pretty_assertions::assert_eq;
+
+fn calendar_error(input: &str) -> ParseError {
+    input.parse::<Calendar>().unwrap_err()
+}
+
+fn location(line: usize, column: usize, snippet: &str) -> Location {
+    Location {
+        line,
+        column,
+        snippet: snippet.into(),
+    }
+}
+
+const FOLDED_PREFIX: &str = "BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+DESCRIPTION:this description is long enough to be folded across a couple of\r
+  lines so that the unfolded line numbers would\r
+  be off by two\r
+";
+
+#[test]
+fn invalid_property_key_in_folded_input() {
+    let error = calendar_error(&format!(
+        "{FOLDED_PREFIX}SUMM@RY:hello\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n"
+    ));
+    assert!(matches!(
+        &error,
+        ParseError::InvalidPropertyKey { location: found } if *found == location(6, 5, "SUMM@RY:hello")
+    ));
+    assert_eq!(error.to_string(), "line 6, column 5: invalid property name");
+}
+
+#[test]
+fn invalid_property_key_on_continuation_line() {
+    let error = calendar_error(
+        "BEGIN:VEVENT\r\nX-VERY-LONG-PROPERTY-NAME-\r\n THAT-IS-FOLDED@AND-BROKEN:x\r\nEND:VEVENT\r\n",
+    );
+    assert_eq!(
+        error.location(),
+        Some(&location(3, 16, " THAT-IS-FOLDED@AND-BROKEN:x"))
+    );
+}
+
+#[test]
+fn invalid_parameter() {
+    let error = calendar_error(&format!(
+        "{FOLDED_PREFIX}ATTENDEE;CN=\"Jane\"Doe:mailto:jane@example.com\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n"
+    ));
+    assert!(matches!(
+        &error,
+        ParseError::InvalidParameter { location: found } if (found.line, found.column) == (6, 9)
+    ));
+}
+
+#[test]
+fn mismatched_end() {
+    let error = calendar_error(&format!("{FOLDED_PREFIX}END:VTODO\r\nEND:VCALENDAR\r\n"));
+    let ParseError::MismatchedEnd {
+        location: found,
+        begin,
+        end,
+    } = error
+    else {
+        panic!("expected a mismatched END, got {error:?}");
+    };
+    assert_eq!(found, location(6, 5, "END:VTODO"));
+    assert_eq!((begin.as_str(), end.as_str()), ("VEVENT", "VTODO"));
+}
+
+#[test]
+fn unexpected_end() {
+    let error = calendar_error("BEGIN:VEVENT\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n");
+    assert!(matches!(
+        &error,
+        ParseError::UnexpectedEnd { location: found, name } if found.line == 3 && name == "VCALENDAR"
+    ));
+    assert_eq!(
+        error.to_string(),
+        "line 3, column 1: END:VCALENDAR without BEGIN:VCALENDAR"
+    );
+}
+
+#[test]
+fn unterminated() {
+    let error = calendar_error(&format!("{FOLDED_PREFIX}UID:x\r\n"));
+    assert!(matches!(
+        &error,
+        ParseError::Unterminated { location: found, name } if *found == location(2, 1, "BEGIN:VEVENT") && name == "VEVENT"
+    ));
+}
+
+#[test]
+fn not_a_calendar() {
+    let error = calendar_error("hello world\r\n");
+    assert_eq!(error.to_string(), "line 1, column 1: expected BEGIN");
+    assert_eq!(error.location().unwrap().snippet, "hello world");
+}
+
+#[test]
+fn component_from_str() {
+    let error = "BEGIN:VEVENT\r\nSUMMARY:a\r\n b\r\nBAD LINE\r\nEND:VEVENT\r\n"
+        .parse::<CalendarComponent>()
+        .unwrap_err();
+    assert_eq!(error.location(), Some(&location(4, 4, "BAD LINE")));
+}
+
+#[test]
+fn read_calendar_locates_in_given_text() {
+    let input = format!("{FOLDED_PREFIX}BAD LINE\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n");
+    let unfolded = unfold(&input);
+    let error = read_calendar(&unfolded).unwrap_err();
+    // the unfolded text has two lines less
+    assert_eq!(error.location(), Some(&location(4, 4, "BAD LINE")));
+}
+
+#[test]
+fn is_an_error() {
+    fn parse(input: &str) -> Result<Calendar, Box<dyn std::error::Error>> {
+        Ok(input.parse()?)
+    }
+    assert!(parse("BEGIN:VCALENDAR\r\n").is_err());
+}
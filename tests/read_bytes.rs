@@ -0,0 +1,156 @@
+#![cfg(feature = "parser")]
+use std::{env, fs, path::PathBuf};
+
+use icalendar::{
+    Calendar, Component, EventLike,
+    parser::{WarningKind, read_calendar_bytes},
+};
+use pretty_assertions::assert_eq;
+
+fn fixture(name: &str) -> Vec<u8> {
+    let path = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("fixtures")
+        .join(name);
+    fs::read(path).unwrap()
+}
+
+fn event_with(lines: &[u8]) -> Vec<u8> {
+    [
+        b"BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:bytes\r\n".as_slice(),
+        lines,
+        b"END:VEVENT\r\nEND:VCALENDAR\r\n",
+    ]
+    .concat()
+}
+
+#[test]
+fn multi_byte_character_split_by_folding() {
+    let (calendar, warnings) =
+        read_calendar_bytes(&fixture("icalendar-rb-bad-utf8/bad_wrapping.ics")).unwrap();
+    assert_eq!(warnings, []);
+
+    let calendar = Calendar::from(calendar);
+    let event = calendar.components[0].as_event().unwrap();
+    assert_eq!(
+        event.get_description(),
+        Some("Event description that puts a UTF-8 multi-octet sequence right\u{a0}here.")
+    );
+    assert_eq!(event.get_summary(), Some("UTF-8 multi-octet sequence test"));
+}
+
+#[test]
+fn matches_str_parsing_for_valid_input() {
+    for name in [
+        "event_five_attendees.ics",
+        "icalendar-rb/event.ics",
+        "icalendar-rb/timezone.ics",
+        "icalendar-rb/two_events.ics",
+    ] {
+        let input = fixture(name);
+        let (calendar, warnings) = read_calendar_bytes(&input).unwrap();
+        assert_eq!(warnings, [], "{name}");
+        assert_eq!(
+            Calendar::from(calendar),
+            String::from_utf8(input)
+                .unwrap()
+                .parse::<Calendar>()
+                .unwrap(),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn byte_order_marks() {
+    let input = event_with("SUMMARY:Grüße\r\n".as_bytes());
+    let read = |input: &[u8]| {
+        let (calendar, warnings) = read_calendar_bytes(input).unwrap();
+        assert_eq!(warnings, []);
+        Calendar::from(calendar)
+    };
+    let expected = read(&input);
+
+    let utf8 = [b"\xef\xbb\xbf".as_slice(), &input].concat();
+    assert_eq!(read(&utf8), expected);
+
+    let text = String::from_utf8(input).unwrap();
+    let utf16le = [0xff, 0xfe]
+        .into_iter()
+        .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+        .collect::<Vec<_>>();
+    assert_eq!(read(&utf16le), expected);
+
+    let utf16be = [0xfe, 0xff]
+        .into_iter()
+        .chain(text.encode_utf16().flat_map(u16::to_be_bytes))
+        .collect::<Vec<_>>();
+    assert_eq!(read(&utf16be), expected);
+}
+
+#[test]
+fn charset_parameter() {
+    let input = event_with(
+        b"SUMMARY;CHARSET=windows-1252:\x93quoted\x94 \x80 5\r\nLOCATION;LANGUAGE=de;CHARSET=\"ISO-8859-1\":K\xf6ln\r\n",
+    );
+    let (calendar, warnings) = read_calendar_bytes(&input).unwrap();
+    assert_eq!(warnings, []);
+
+    let calendar = Calendar::from(calendar);
+    let event = calendar.components[0].as_event().unwrap();
+    assert_eq!(event.get_summary(), Some("“quoted” € 5"));
+    assert_eq!(event.get_location(), Some("Köln"));
+    let location = event.properties().get("LOCATION").unwrap();
+    assert_eq!(location.params().len(), 1);
+    assert!(location.params().contains_key("LANGUAGE"));
+}
+
+#[test]
+fn unsupported_charset() {
+    let input = event_with(b"SUMMARY;CHARSET=KOI8-R:\xf0\xd2\xc9\r\n");
+    let (calendar, warnings) = read_calendar_bytes(&input).unwrap();
+    assert_eq!(
+        warnings[0].kind,
+        WarningKind::UnsupportedCharset {
+            charset: "KOI8-R".into()
+        }
+    );
+    // each byte is an invalid sequence of its own
+    assert_eq!(warnings.len(), 4);
+    assert!(warnings.iter().skip(1).all(|warning| warning.kind
+        == WarningKind::InvalidEncoding {
+            charset: "UTF-8".into()
+        }));
+    assert_eq!(warnings[0].location.line, 4);
+    assert_eq!(
+        calendar.components[0].properties[1].val,
+        "\u{fffd}\u{fffd}\u{fffd}"
+    );
+}
+
+#[test]
+fn repairs_invalid_utf8_with_locations() {
+    let input = event_with(b"DESCRIPTION:first line is fi\xffne\r\n  and so is t\xc3he second\r\n");
+    let (calendar, warnings) = read_calendar_bytes(&input).unwrap();
+    assert_eq!(
+        calendar.components[0].properties[1].val,
+        "first line is fi\u{fffd}ne and so is t\u{fffd}he second"
+    );
+    assert_eq!(
+        warnings.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        [
+            "line 4, column 29: replaced bytes that are not valid UTF-8",
+            "line 5, column 14: replaced bytes that are not valid UTF-8",
+        ]
+    );
+    assert_eq!(
+        warnings[1].location.snippet,
+        "  and so is t\u{fffd}he second"
+    );
+}
+
+#[test]
+fn syntax_errors_are_located_in_the_input() {
+    let input = event_with(b"SUMMARY:caf\xc3\r\n \xa9\r\nBROKEN LINE\r\n");
+    let error = read_calendar_bytes(&input).unwrap_err();
+    assert_eq!(error.to_string(), "line 6, column 7: invalid property name");
+}
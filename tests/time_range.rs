@@ -0,0 +1,269 @@
+#![cfg(all(feature = "recurrence", feature = "parser"))]
+use chrono::{DateTime, TimeZone, Utc};
+use icalendar::{Calendar, CalendarComponent, Component, DatePerhapsTime, TimeRange};
+
+fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+}
+
+fn parse(components: &str) -> Calendar {
+    format!("BEGIN:VCALENDAR\nVERSION:2.0\nPRODID:-//test//test//EN\n{components}END:VCALENDAR\n")
+        .parse()
+        .unwrap()
+}
+
+fn first(calendar: &Calendar) -> &CalendarComponent {
+    calendar.components.first().unwrap()
+}
+
+fn uids(calendar: &Calendar, range: &TimeRange) -> Vec<String> {
+    calendar
+        .components_in_range(range)
+        .iter()
+        .filter_map(|component| component.get_uid().map(ToOwned::to_owned))
+        .collect()
+}
+
+#[test]
+fn event_with_dtend_overlaps_exclusively() {
+    let calendar = parse(
+        "BEGIN:VEVENT\nUID:a\nDTSTART:20260302T090000Z\nDTEND:20260302T100000Z\nEND:VEVENT\n",
+    );
+    let event = first(&calendar);
+
+    assert!(TimeRange::new(utc(2026, 3, 2, 9, 30), utc(2026, 3, 2, 11, 0)).matches(event));
+    // the end of the event is not part of it
+    assert!(!TimeRange::new(utc(2026, 3, 2, 10, 0), utc(2026, 3, 2, 11, 0)).matches(event));
+    // neither is the end of the range
+    assert!(!TimeRange::new(utc(2026, 3, 2, 8, 0), utc(2026, 3, 2, 9, 0)).matches(event));
+    assert!(TimeRange::starting_at(utc(2026, 3, 2, 9, 59)).matches(event));
+    assert!(TimeRange::ending_at(utc(2026, 3, 2, 9, 1)).matches(event));
+}
+
+#[test]
+fn event_without_end() {
+    let calendar = parse(
+        "BEGIN:VEVENT\nUID:instant\nDTSTART:20260302T090000Z\nEND:VEVENT\n\
+         BEGIN:VEVENT\nUID:zero\nDTSTART:20260302T090000Z\nDURATION:PT0S\nEND:VEVENT\n\
+         BEGIN:VEVENT\nUID:day\nDTSTART;VALUE=DATE:20260302\nEND:VEVENT\n",
+    );
+
+    // a zero-length event occupies its start
+    let at_start = TimeRange::new(utc(2026, 3, 2, 9, 0), utc(2026, 3, 2, 9, 1));
+    assert_eq!(uids(&calendar, &at_start), ["instant", "zero", "day"]);
+
+    // an all-day event without DTEND lasts one day
+    let late_evening = TimeRange::new(utc(2026, 3, 2, 23, 0), utc(2026, 3, 3, 1, 0));
+    assert_eq!(uids(&calendar, &late_evening), ["day"]);
+    let next_day = TimeRange::new(utc(2026, 3, 3, 0, 0), utc(2026, 3, 3, 1, 0));
+    assert!(uids(&calendar, &next_day).is_empty());
+}
+
+#[test]
+fn event_with_duration() {
+    let calendar =
+        parse("BEGIN:VEVENT\nUID:a\nDTSTART:20260302T090000Z\nDURATION:PT2H\nEND:VEVENT\n");
+    let event = first(&calendar);
+
+    assert!(TimeRange::new(utc(2026, 3, 2, 10, 59), utc(2026, 3, 2, 12, 0)).matches(event));
+    assert!(!TimeRange::new(utc(2026, 3, 2, 11, 0), utc(2026, 3, 2, 12, 0)).matches(event));
+}
+
+#[test]
+fn floating_and_date_values_use_reference_timezone() {
+    let calendar =
+        parse("BEGIN:VEVENT\nUID:a\nDTSTART:20260302T090000\nDTEND:20260302T100000\nEND:VEVENT\n");
+    let event = first(&calendar);
+    let range = TimeRange::new(utc(2026, 3, 2, 8, 0), utc(2026, 3, 2, 8, 30));
+
+    assert!(!range.matches(event));
+    // 09:00 in Berlin is 08:00 UTC
+    assert!(
+        range
+            .with_timezone(chrono_tz::Europe::Berlin)
+            .matches(event)
+    );
+}
+
+#[test]
+fn calendar_timezone_anchors_floating_values() {
+    let calendar = parse(
+        "X-WR-TIMEZONE:Europe/Berlin\n\
+         BEGIN:VEVENT\nUID:a\nDTSTART:20260302T090000\nDTEND:20260302T100000\nEND:VEVENT\n",
+    );
+    let range = TimeRange::new(utc(2026, 3, 2, 8, 0), utc(2026, 3, 2, 8, 30));
+
+    assert_eq!(uids(&calendar, &range), ["a"]);
+}
+
+#[test]
+fn tzid_values_are_resolved() {
+    let calendar = parse(
+        "BEGIN:VEVENT\nUID:a\nDTSTART;TZID=America/New_York:20260302T090000\n\
+         DTEND;TZID=America/New_York:20260302T100000\nEND:VEVENT\n",
+    );
+    let event = first(&calendar);
+
+    assert!(TimeRange::new(utc(2026, 3, 2, 14, 0), utc(2026, 3, 2, 14, 30)).matches(event));
+    assert!(!TimeRange::new(utc(2026, 3, 2, 9, 0), utc(2026, 3, 2, 10, 0)).matches(event));
+}
+
+#[test]
+fn todo_rules() {
+    let calendar = parse(
+        "BEGIN:VTODO\nUID:start-duration\nDTSTART:20260302T090000Z\nDURATION:PT1H\nEND:VTODO\n\
+         BEGIN:VTODO\nUID:start-due\nDTSTART:20260302T090000Z\nDUE:20260302T120000Z\nEND:VTODO\n\
+         BEGIN:VTODO\nUID:start\nDTSTART:20260302T090000Z\nEND:VTODO\n\
+         BEGIN:VTODO\nUID:due\nDUE:20260302T120000Z\nEND:VTODO\n\
+         BEGIN:VTODO\nUID:completed-created\nCREATED:20260301T090000Z\nCOMPLETED:20260303T090000Z\nEND:VTODO\n\
+         BEGIN:VTODO\nUID:completed\nCOMPLETED:20260303T090000Z\nEND:VTODO\n\
+         BEGIN:VTODO\nUID:created\nCREATED:20260301T090000Z\nEND:VTODO\n\
+         BEGIN:VTODO\nUID:nothing\nEND:VTODO\n",
+    );
+
+    let morning = TimeRange::new(utc(2026, 3, 2, 9, 0), utc(2026, 3, 2, 9, 30));
+    assert_eq!(
+        uids(&calendar, &morning),
+        [
+            "start-duration",
+            "start-due",
+            "start",
+            "completed-created",
+            "created",
+            "nothing"
+        ]
+    );
+
+    let around_due = TimeRange::new(utc(2026, 3, 2, 11, 0), utc(2026, 3, 2, 12, 0));
+    assert_eq!(
+        uids(&calendar, &around_due),
+        [
+            "start-due",
+            "due",
+            "completed-created",
+            "created",
+            "nothing"
+        ]
+    );
+
+    let completion = TimeRange::new(utc(2026, 3, 3, 9, 0), utc(2026, 3, 3, 10, 0));
+    assert_eq!(
+        uids(&calendar, &completion),
+        ["completed-created", "completed", "created", "nothing"]
+    );
+
+    let before_creation = TimeRange::ending_at(utc(2026, 3, 1, 8, 0));
+    assert_eq!(uids(&calendar, &before_creation), ["nothing"]);
+}
+
+#[test]
+fn journal_rules() {
+    let calendar = parse(
+        "BEGIN:VJOURNAL\nUID:date-time\nDTSTART:20260302T090000Z\nEND:VJOURNAL\n\
+         BEGIN:VJOURNAL\nUID:date\nDTSTART;VALUE=DATE:20260302\nEND:VJOURNAL\n\
+         BEGIN:VJOURNAL\nUID:undated\nEND:VJOURNAL\n",
+    );
+
+    let afternoon = TimeRange::new(utc(2026, 3, 2, 13, 0), utc(2026, 3, 2, 14, 0));
+    assert_eq!(uids(&calendar, &afternoon), ["date"]);
+    let everything = TimeRange::starting_at(utc(2000, 1, 1, 0, 0));
+    assert_eq!(uids(&calendar, &everything), ["date-time", "date"]);
+}
+
+#[test]
+fn free_busy_rules() {
+    let calendar = parse(
+        "BEGIN:VFREEBUSY\nUID:bounded\nDTSTART:20260302T000000Z\nDTEND:20260303T000000Z\nEND:VFREEBUSY\n\
+         BEGIN:VFREEBUSY\nUID:periods\nFREEBUSY:20260302T090000Z/PT1H,20260302T140000Z/20260302T150000Z\nEND:VFREEBUSY\n",
+    );
+
+    let lunch = TimeRange::new(utc(2026, 3, 2, 12, 0), utc(2026, 3, 2, 13, 0));
+    assert_eq!(uids(&calendar, &lunch), ["bounded"]);
+    let afternoon = TimeRange::new(utc(2026, 3, 2, 14, 30), utc(2026, 3, 2, 16, 0));
+    assert_eq!(uids(&calendar, &afternoon), ["bounded", "periods"]);
+}
+
+#[test]
+fn alarm_rules() {
+    let calendar = parse(
+        "BEGIN:VEVENT\nUID:a\nDTSTART:20260302T090000Z\nDTEND:20260302T100000Z\n\
+         BEGIN:VALARM\nACTION:DISPLAY\nDESCRIPTION:soon\nTRIGGER:-PT15M\nREPEAT:2\nDURATION:PT5M\nEND:VALARM\n\
+         BEGIN:VALARM\nACTION:DISPLAY\nDESCRIPTION:over\nTRIGGER;RELATED=END:PT0S\nEND:VALARM\n\
+         END:VEVENT\n",
+    );
+    let event = first(&calendar);
+    let [before_start, at_end] = event.components() else {
+        panic!("expected two alarms");
+    };
+
+    let repetition = TimeRange::new(utc(2026, 3, 2, 8, 54), utc(2026, 3, 2, 8, 56));
+    assert!(repetition.matches_alarm(before_start, event));
+    assert!(!repetition.matches_alarm(at_end, event));
+
+    let after_repetitions = TimeRange::new(utc(2026, 3, 2, 8, 56), utc(2026, 3, 2, 9, 0));
+    assert!(!after_repetitions.matches_alarm(before_start, event));
+
+    let end = TimeRange::new(utc(2026, 3, 2, 10, 0), utc(2026, 3, 2, 10, 1));
+    assert!(end.matches_alarm(at_end, event));
+}
+
+#[test]
+fn recurring_events_are_expanded() {
+    let calendar = parse(
+        "BEGIN:VEVENT\nUID:daily\nDTSTART;TZID=Europe/Berlin:20260302T090000\n\
+         DTEND;TZID=Europe/Berlin:20260302T093000\nRRULE:FREQ=DAILY;COUNT=10\n\
+         EXDATE;TZID=Europe/Berlin:20260304T090000\nEND:VEVENT\n\
+         BEGIN:VEVENT\nUID:daily\nRECURRENCE-ID;TZID=Europe/Berlin:20260303T090000\n\
+         DTSTART;TZID=Europe/Berlin:20260303T150000\nDTEND;TZID=Europe/Berlin:20260303T153000\n\
+         SUMMARY:moved\nEND:VEVENT\n",
+    );
+    let week = TimeRange::new(utc(2026, 3, 2, 0, 0), utc(2026, 3, 6, 0, 0));
+    let occurrences = calendar.occurrences_in_range(&week);
+
+    let starts = occurrences
+        .iter()
+        .map(|occurrence| match &occurrence.start {
+            Some(DatePerhapsTime::DateTime(dt)) => dt.try_into_utc().unwrap(),
+            other => panic!("unexpected start {other:?}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        starts,
+        [
+            utc(2026, 3, 2, 8, 0),
+            utc(2026, 3, 3, 14, 0),
+            utc(2026, 3, 5, 8, 0)
+        ]
+    );
+
+    let moved = occurrences.get(1).unwrap();
+    assert_eq!(moved.component.get_summary(), Some("moved"));
+    assert_eq!(
+        moved.recurrence_id,
+        moved.component.get_recurrence_id(),
+        "override keeps its own RECURRENCE-ID"
+    );
+
+    let first = occurrences.first().unwrap();
+    assert_eq!(first.recurrence_id, first.start);
+    assert!(first.end.is_some());
+}
+
+#[test]
+fn long_instances_starting_before_the_range_are_found() {
+    let calendar = parse(
+        "BEGIN:VEVENT\nUID:weekly\nDTSTART;VALUE=DATE:20260302\nDTEND;VALUE=DATE:20260306\n\
+         RRULE:FREQ=WEEKLY\nEND:VEVENT\n",
+    );
+
+    let friday = TimeRange::new(utc(2026, 3, 12, 12, 0), utc(2026, 3, 12, 13, 0));
+    let occurrences = calendar.occurrences_in_range(&friday);
+
+    assert_eq!(occurrences.len(), 1);
+    assert_eq!(
+        occurrences.first().unwrap().start,
+        Some(DatePerhapsTime::Date(
+            chrono::NaiveDate::from_ymd_opt(2026, 3, 9).unwrap()
+        ))
+    );
+}
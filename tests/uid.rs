@@ -0,0 +1,207 @@
+#![cfg(feature = "parser")]
+use icalendar::*;
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::calendar;
+
+fn component(name: &str, lines: &str) -> String {
+    common::component(name, &format!("DTSTAMP:20240301T100000Z\r\n{lines}"))
+}
+
+fn uids<'c>(groups: &[UidGroup<'c>]) -> Vec<&'c str> {
+    groups.iter().map(|group| group.uid).collect()
+}
+
+#[test]
+fn overrides_are_grouped_with_their_master() {
+    let calendar = calendar(
+        &[
+            component(
+                "VEVENT",
+                "UID:series\r\nRECURRENCE-ID:20240302T100000Z\r\nSUMMARY:moved\r\n",
+            ),
+            component("VTIMEZONE", "TZID:Europe/Berlin\r\n"),
+            component(
+                "VEVENT",
+                "UID:series\r\nRRULE:FREQ=DAILY\r\nSUMMARY:daily\r\n",
+            ),
+            component("VTODO", "UID:task\r\n"),
+            component(
+                "VEVENT",
+                "UID:series\r\nRECURRENCE-ID:20240303T100000Z\r\nSUMMARY:cancelled\r\n",
+            ),
+        ]
+        .concat(),
+    );
+
+    let groups = calendar.group_by_uid();
+    assert_eq!(uids(&groups), ["series", "task"]);
+    let series = &groups[0];
+    assert_eq!(
+        series.master().unwrap().property_value("SUMMARY"),
+        Some("daily")
+    );
+    assert_eq!(
+        series
+            .components()
+            .map(|component| component.property_value("SUMMARY").unwrap())
+            .collect::<Vec<_>>(),
+        ["daily", "moved", "cancelled"]
+    );
+    assert_eq!(
+        series
+            .instance("20240303T100000Z")
+            .and_then(|component| component.property_value("SUMMARY")),
+        Some("cancelled")
+    );
+    assert!(series.instance("20240304T100000Z").is_none());
+    assert_eq!(calendar.duplicate_uids(), []);
+
+    assert_eq!(calendar.find_by_uid("series").count(), 3);
+    assert_eq!(calendar.find_by_uid("unknown").count(), 0);
+}
+
+#[test]
+fn duplicates_are_detected() {
+    let calendar = calendar(
+        &[
+            // two masters
+            component("VEVENT", "UID:twice\r\n"),
+            component("VEVENT", "UID:twice\r\n"),
+            // the same occurrence overridden twice
+            component("VEVENT", "UID:override\r\nRRULE:FREQ=DAILY\r\n"),
+            component(
+                "VEVENT",
+                "UID:override\r\nRECURRENCE-ID:20240302T100000Z\r\n",
+            ),
+            component(
+                "VEVENT",
+                "UID:override\r\nRECURRENCE-ID:20240302T100000Z\r\n",
+            ),
+            // an event and a to-do
+            component("VEVENT", "UID:mixed\r\nRRULE:FREQ=DAILY\r\n"),
+            component("VTODO", "UID:mixed\r\nRECURRENCE-ID:20240302T100000Z\r\n"),
+            component("VEVENT", "UID:unique\r\n"),
+        ]
+        .concat(),
+    );
+    assert_eq!(
+        uids(&calendar.duplicate_uids()),
+        ["twice", "override", "mixed"]
+    );
+}
+
+#[test]
+fn components_are_changed_and_removed_by_uid() {
+    let mut calendar = calendar(
+        &[
+            component("VEVENT", "UID:keep\r\n"),
+            component("VTODO", "UID:drop\r\n"),
+            component("VEVENT", "UID:drop\r\nRECURRENCE-ID:20240302T100000Z\r\n"),
+        ]
+        .concat(),
+    );
+
+    for component in calendar.get_mut_by_uid("drop") {
+        component.append_property(("STATUS", "CANCELLED"));
+    }
+    let removed = calendar.remove_by_uid("drop");
+    assert_eq!(removed.len(), 2);
+    assert!(
+        removed
+            .iter()
+            .all(|component| component.property_value("STATUS") == Some("CANCELLED"))
+    );
+    assert_eq!(uids(&calendar.group_by_uid()), ["keep"]);
+    assert_eq!(calendar.remove_by_uid("drop"), []);
+}
+
+fn timezone(tzid: &str) -> String {
+    format!(
+        "BEGIN:VTIMEZONE\r\nTZID:{tzid}\r\nBEGIN:STANDARD\r\nDTSTART:19701025T030000\r\nTZOFFSETFROM:+0200\r\nTZOFFSETTO:+0100\r\nEND:STANDARD\r\nEND:VTIMEZONE\r\n"
+    )
+}
+
+fn tzids(calendar: &Calendar) -> Vec<&str> {
+    calendar
+        .components
+        .iter()
+        .filter_map(|component| component.property_value("TZID"))
+        .collect()
+}
+
+#[test]
+fn resources_carry_what_they_need() {
+    let calendar = calendar(&[
+        "METHOD:PUBLISH\r\nX-WR-CALNAME:Work\r\n".to_owned(),
+        timezone("Europe/Berlin"),
+        timezone("Europe/London"),
+        timezone("America/New_York"),
+        component(
+            "VEVENT",
+            "UID:berlin\r\nDTSTART;TZID=Europe/Berlin:20240301T100000\r\nRRULE:FREQ=DAILY\r\n",
+        ),
+        component(
+            "VTODO",
+            "UID:london\r\nDUE;TZID=Europe/London:20240301T100000\r\n",
+        ),
+        component(
+            "VEVENT",
+            "UID:berlin\r\nRECURRENCE-ID;TZID=Europe/Berlin:20240302T100000\r\nEXDATE;TZID=Europe/London:20240303T100000\r\n",
+        ),
+        component("VJOURNAL", "SUMMARY:no uid\r\n"),
+    ]
+    .concat());
+
+    let resources = calendar.split_by_uid();
+    assert_eq!(resources.len(), 3);
+    for resource in &resources {
+        assert_eq!(
+            resource
+                .properties
+                .iter()
+                .map(|property| (property.key(), property.value()))
+                .collect::<Vec<_>>(),
+            [
+                ("VERSION", "2.0"),
+                ("PRODID", "test"),
+                ("CALSCALE", "GREGORIAN")
+            ]
+        );
+    }
+    assert_eq!(tzids(&resources[0]), ["Europe/Berlin", "Europe/London"]);
+    assert_eq!(uids(&resources[0].group_by_uid()), ["berlin"]);
+    assert_eq!(resources[0].group_by_uid()[0].overrides.len(), 1);
+    assert_eq!(tzids(&resources[1]), ["Europe/London"]);
+    assert_eq!(resources[2].components.len(), 1);
+}
+
+#[test]
+fn joining_deduplicates_timezones() {
+    let calendar = calendar(
+        &[
+            timezone("Europe/Berlin"),
+            component(
+                "VEVENT",
+                "UID:one\r\nDTSTART;TZID=Europe/Berlin:20240301T100000\r\n",
+            ),
+            component(
+                "VEVENT",
+                "UID:two\r\nDTSTART;TZID=Europe/Berlin:20240302T100000\r\n",
+            ),
+        ]
+        .concat(),
+    );
+    let resources = calendar.split_by_uid();
+    assert!(
+        resources
+            .iter()
+            .all(|resource| tzids(resource) == ["Europe/Berlin"])
+    );
+
+    let joined = Calendar::join(resources);
+    assert_eq!(tzids(&joined), ["Europe/Berlin"]);
+    assert_eq!(joined.components, calendar.components);
+    assert_eq!(Calendar::join([]), Calendar::empty());
+}
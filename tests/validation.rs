@@ -0,0 +1,301 @@
+#![cfg(feature = "parser")]
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+use icalendar::*;
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::{calendar, component, event, messages};
+
+#[test]
+fn builders_produce_valid_components() {
+    let event = Event::new()
+        .uid("builder")
+        .timestamp(Utc.with_ymd_and_hms(2024, 3, 1, 10, 0, 0).unwrap())
+        .starts(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap())
+        .ends(NaiveDate::from_ymd_opt(2024, 3, 2).unwrap())
+        .alarm(
+            Alarm::display("wake up", -Duration::minutes(15))
+                .duration_and_repeat(Duration::minutes(5), 2),
+        )
+        .done();
+    assert_eq!(event.validate(), []);
+
+    let mut calendar = Calendar::new();
+    calendar.push(event);
+    assert_eq!(calendar.validate(), []);
+}
+
+#[test]
+fn generated_properties_are_warnings() {
+    let violations = Event::new().summary("bare").done().validate();
+    assert_eq!(
+        messages(&violations),
+        ["VEVENT: missing DTSTAMP", "VEVENT: missing UID"]
+    );
+    assert!(
+        violations
+            .iter()
+            .all(|violation| violation.severity == Severity::Warning)
+    );
+
+    let violations = Calendar::empty().validate();
+    assert_eq!(
+        messages(&violations),
+        ["VCALENDAR: missing PRODID", "VCALENDAR: missing VERSION"]
+    );
+    assert!(
+        violations
+            .iter()
+            .all(|violation| violation.severity == Severity::Error)
+    );
+}
+
+#[test]
+fn start_required_without_method() {
+    let violations = event("SUMMARY:no start\r\n").validate();
+    assert_eq!(
+        violations
+            .iter()
+            .map(|violation| &violation.kind)
+            .collect::<Vec<_>>(),
+        [&ViolationKind::MissingProperty {
+            name: "DTSTART".into()
+        }]
+    );
+
+    let mut with_method = event("SUMMARY:no start\r\n");
+    with_method.method(Method::Request);
+    assert_eq!(with_method.validate(), []);
+}
+
+#[test]
+fn alarms() {
+    let calendar = event(
+        "DTSTART:20240301T100000Z\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+TRIGGER:-PT15M\r
+REPEAT:2\r
+END:VALARM\r
+BEGIN:VALARM\r
+ACTION:EMAIL\r
+TRIGGER;VALUE=DATE-TIME:20240301T090000Z\r
+DESCRIPTION:mail\r
+END:VALARM\r
+BEGIN:VALARM\r
+ACTION:AUDIO\r
+TRIGGER:in a bit\r
+END:VALARM\r
+",
+    );
+    assert_eq!(
+        messages(&calendar.validate()),
+        [
+            "VCALENDAR/VEVENT[0]/VALARM[0]: missing DESCRIPTION",
+            "VCALENDAR/VEVENT[0]/VALARM[0]: REPEAT requires DURATION",
+            "VCALENDAR/VEVENT[0]/VALARM[1]: missing SUMMARY",
+            "VCALENDAR/VEVENT[0]/VALARM[1]: missing ATTENDEE",
+            "VCALENDAR/VEVENT[0]/VALARM[2]: TRIGGER is not a valid DURATION",
+        ]
+    );
+}
+
+#[test]
+fn cardinality() {
+    let mut calendar = event("DTSTART:20240301T100000Z\r\n");
+    calendar.append_property(("VERSION", "2.0"));
+    calendar.components[0]
+        .append_multi_property(("SUMMARY", "one"))
+        .append_multi_property(("SUMMARY", "two"))
+        .append_property(("SUMMARY", "three"));
+
+    let violations = calendar.validate();
+    assert_eq!(
+        messages(&violations),
+        [
+            "VCALENDAR: VERSION occurs 2 times but is allowed once",
+            "VCALENDAR/VEVENT[0]: SUMMARY occurs 3 times but is allowed once",
+        ]
+    );
+}
+
+#[test]
+fn repeated_properties_are_found_in_text_read_losslessly() {
+    let text = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+VERSION:2.0\r
+PRODID:test\r
+BEGIN:VEVENT\r
+UID:1\r
+DTSTAMP:20240301T100000Z\r
+DTSTART:20240301T100000Z\r
+SUMMARY:one\r
+SUMMARY:two\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+    let expected = [
+        "VCALENDAR: VERSION occurs 2 times but is allowed once",
+        "VCALENDAR/VEVENT[0]: SUMMARY occurs 2 times but is allowed once",
+    ];
+    let lossless = Calendar::from_str_lossless(text).unwrap();
+    assert_eq!(messages(&lossless.validate()), expected);
+
+    // parsing keeps one of them only
+    let parsed: Calendar = text.parse().unwrap();
+    assert_eq!(messages(&parsed.validate()), expected[..1]);
+}
+
+#[test]
+fn missing_ids_are_errors_where_nothing_is_generated() {
+    let text = format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:test\r\n{}END:VCALENDAR\r\n",
+        component("VEVENT", "DTSTART:20240301T100000Z\r\n")
+    );
+    let severities = |calendar: Calendar| {
+        calendar
+            .validate()
+            .iter()
+            .map(|violation| (violation.to_string(), violation.severity))
+            .collect::<Vec<_>>()
+    };
+
+    let parsed: Calendar = text.parse().unwrap();
+    assert_eq!(
+        severities(parsed),
+        [
+            (
+                "VCALENDAR/VEVENT[0]: missing DTSTAMP".into(),
+                Severity::Warning
+            ),
+            ("VCALENDAR/VEVENT[0]: missing UID".into(), Severity::Warning),
+        ]
+    );
+    // the properties of a calendar read losslessly are written as they are
+    let lossless = Calendar::from_str_lossless(&text).unwrap();
+    assert_eq!(
+        severities(lossless),
+        [
+            (
+                "VCALENDAR/VEVENT[0]: missing DTSTAMP".into(),
+                Severity::Error
+            ),
+            ("VCALENDAR/VEVENT[0]: missing UID".into(), Severity::Error),
+        ]
+    );
+}
+
+#[test]
+fn exclusive_and_paired_properties() {
+    let calendar = calendar(
+        "BEGIN:VEVENT\r
+UID:1\r
+DTSTAMP:20240301T100000Z\r
+DTSTART:20240301T100000Z\r
+DTEND:20240301T110000Z\r
+DURATION:PT1H\r
+END:VEVENT\r
+BEGIN:VTODO\r
+UID:2\r
+DTSTAMP:20240301T100000Z\r
+DUE:20240301T100000Z\r
+DURATION:PT1H\r
+END:VTODO\r
+",
+    );
+    assert_eq!(
+        messages(&calendar.validate()),
+        [
+            "VCALENDAR/VEVENT[0]: DTEND and DURATION exclude each other",
+            "VCALENDAR/VTODO[1]: DUE and DURATION exclude each other",
+            "VCALENDAR/VTODO[1]: DURATION requires DTSTART",
+        ]
+    );
+}
+
+#[test]
+fn value_types() {
+    let calendar = event(
+        "DTSTART;VALUE=DATE:20240301\r
+DTEND:20240302T000000\r
+RECURRENCE-ID;VALUE=TEXT:tomorrow\r
+EXDATE;VALUE=DATE:20240308,20240315T100000\r
+RDATE;VALUE=PERIOD:20240322T100000Z/PT1H,20240329T100000Z/20240329T110000Z\r
+PRIORITY:high\r
+SEQUENCE;VALUE=X-CUSTOM:whatever\r
+",
+    );
+    assert_eq!(
+        messages(&calendar.validate()),
+        [
+            "VCALENDAR/VEVENT[0]: DTEND must have the same value type as DTSTART",
+            "VCALENDAR/VEVENT[0]: PRIORITY is not a valid INTEGER",
+            "VCALENDAR/VEVENT[0]: RECURRENCE-ID can't be VALUE=TEXT",
+            "VCALENDAR/VEVENT[0]: EXDATE is not a valid DATE",
+        ]
+    );
+}
+
+#[test]
+fn timezone_references() {
+    const TIMEZONE: &str = "BEGIN:VTIMEZONE\r
+TZID:Europe/Berlin\r
+BEGIN:STANDARD\r
+DTSTART:19701025T030000\r
+TZOFFSETFROM:+0200\r
+TZOFFSETTO:+0100\r
+END:STANDARD\r
+END:VTIMEZONE\r
+";
+    const EVENT: &str = "BEGIN:VEVENT\r
+UID:1\r
+DTSTAMP:20240301T100000Z\r
+DTSTART;TZID=Europe/Berlin:20240301T100000\r
+DTEND;TZID=Europe/Paris:20240301T110000\r
+END:VEVENT\r
+";
+    let violations = calendar(EVENT).validate();
+    assert_eq!(
+        messages(&violations),
+        [
+            "VCALENDAR/VEVENT[0]: DTEND refers to missing VTIMEZONE \"Europe/Paris\"",
+            "VCALENDAR/VEVENT[0]: DTSTART refers to missing VTIMEZONE \"Europe/Berlin\"",
+        ]
+    );
+
+    let violations = calendar(&format!("{TIMEZONE}{EVENT}")).validate();
+    assert_eq!(
+        violations
+            .iter()
+            .map(|violation| &violation.kind)
+            .collect::<Vec<_>>(),
+        [&ViolationKind::UnknownTimezone {
+            name: "DTEND".into(),
+            tzid: "Europe/Paris".into()
+        }]
+    );
+
+    // a single component has nothing to refer to
+    assert_eq!(calendar(EVENT).components[0].validate(), []);
+}
+
+#[test]
+fn timezone_components() {
+    let calendar = calendar(
+        "BEGIN:VTIMEZONE\r
+TZID:Broken\r
+BEGIN:DAYLIGHT\r
+DTSTART:19700329T020000\r
+TZOFFSETFROM:+1\r
+END:DAYLIGHT\r
+END:VTIMEZONE\r
+",
+    );
+    assert_eq!(
+        messages(&calendar.validate()),
+        [
+            "VCALENDAR/VTIMEZONE[0]/DAYLIGHT[0]: missing TZOFFSETTO",
+            "VCALENDAR/VTIMEZONE[0]/DAYLIGHT[0]: TZOFFSETFROM is not a valid UTC-OFFSET",
+        ]
+    );
+}
@@ -0,0 +1,238 @@
+#![cfg(feature = "parser")]
+use std::{fs, path::PathBuf};
+
+use icalendar::{
+    Calendar, CalendarComponent, Component, Event,
+    vdir::{ItemChange, Vdir, VdirError},
+};
+use pretty_assertions::assert_eq;
+
+/// An empty directory that is removed when dropped.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("icalendar-vdir-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        TempDir(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn item(uid: &str, summary: &str) -> Calendar {
+    let mut calendar = Calendar::new();
+    calendar.push(
+        Event::with_uid(uid)
+            .summary(summary)
+            .timestamp(
+                chrono::TimeZone::with_ymd_and_hms(&chrono::Utc, 2024, 3, 1, 10, 0, 0).unwrap(),
+            )
+            .done(),
+    );
+    calendar
+}
+
+fn summaries(calendar: &Calendar) -> Vec<&str> {
+    let mut summaries = calendar
+        .events()
+        .filter_map(|event| event.get_summary())
+        .collect::<Vec<_>>();
+    summaries.sort();
+    summaries
+}
+
+#[test]
+fn loads_items_and_ignores_other_files() {
+    let dir = TempDir::new("load");
+    let vdir = Vdir::create(&dir.0).unwrap();
+    fs::write(dir.0.join("a.ics"), item("a", "First").to_string()).unwrap();
+    fs::write(dir.0.join("b.ics"), item("b", "Second").to_string()).unwrap();
+    fs::write(dir.0.join(".c.ics.tmp"), "garbage").unwrap();
+    fs::write(dir.0.join("notes.txt"), "garbage").unwrap();
+
+    let hrefs = vdir
+        .items()
+        .unwrap()
+        .into_iter()
+        .map(|item| item.href)
+        .collect::<Vec<_>>();
+    assert_eq!(hrefs, ["a.ics", "b.ics"]);
+    assert_eq!(summaries(&vdir.load().unwrap()), ["First", "Second"]);
+}
+
+#[test]
+fn create_update_and_delete_by_uid() {
+    let dir = TempDir::new("crud");
+    let vdir = Vdir::create(&dir.0).unwrap();
+
+    let created = vdir.create_item(&item("a/b c", "Draft")).unwrap();
+    assert_eq!(created.href, "a%2Fb%20c.ics");
+    assert!(matches!(
+        vdir.create_item(&item("a/b c", "Again")),
+        Err(VdirError::Exists(_))
+    ));
+
+    let updated = vdir
+        .update("a/b c", &item("a/b c", "Final"), &created.etag)
+        .unwrap();
+    assert_eq!(updated.href, created.href);
+    let (found, content) = vdir.get("a/b c").unwrap().unwrap();
+    assert_eq!(found, updated);
+    assert_eq!(summaries(&content), ["Final"]);
+
+    vdir.delete("a/b c", &updated.etag).unwrap();
+    assert_eq!(vdir.items().unwrap(), []);
+    assert!(matches!(
+        vdir.delete("a/b c", &updated.etag),
+        Err(VdirError::NotFound(_))
+    ));
+}
+
+#[test]
+fn concurrent_creates_never_replace_each_other() {
+    let dir = TempDir::new("race");
+    let vdir = Vdir::create(&dir.0).unwrap();
+
+    let results = std::thread::scope(|scope| {
+        let creates = (0..8)
+            .map(|n| {
+                let vdir = &vdir;
+                scope.spawn(move || vdir.create_item(&item("same", &format!("Draft {n}"))))
+            })
+            .collect::<Vec<_>>();
+        creates
+            .into_iter()
+            .map(|create| create.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 1);
+    assert!(
+        results
+            .iter()
+            .all(|result| matches!(result, Ok(_) | Err(VdirError::Exists(_))))
+    );
+    assert_eq!(fs::read_dir(&dir.0).unwrap().count(), 1);
+}
+
+#[test]
+fn stale_etags_conflict() {
+    let dir = TempDir::new("conflict");
+    let vdir = Vdir::create(&dir.0).unwrap();
+    let created = vdir.create_item(&item("a", "Draft")).unwrap();
+
+    // another client rewrites the file
+    fs::write(dir.0.join("a.ics"), item("a", "Theirs, longer").to_string()).unwrap();
+
+    assert!(matches!(
+        vdir.update("a", &item("a", "Ours"), &created.etag),
+        Err(VdirError::Conflict(_))
+    ));
+    assert!(matches!(
+        vdir.delete("a", &created.etag),
+        Err(VdirError::Conflict(_))
+    ));
+    assert_eq!(summaries(&vdir.load().unwrap()), ["Theirs, longer"]);
+}
+
+#[test]
+fn items_need_exactly_one_uid() {
+    let dir = TempDir::new("uids");
+    let vdir = Vdir::create(&dir.0).unwrap();
+
+    let mut two = item("a", "One");
+    two.push(Event::with_uid("b").done());
+    assert!(matches!(
+        vdir.create_item(&two),
+        Err(VdirError::MultipleUids)
+    ));
+
+    let mut without = Calendar::new();
+    without.push(CalendarComponent::Event(Event::new()));
+    without.components[0].remove_property("UID");
+    assert!(matches!(
+        vdir.create_item(&without),
+        Err(VdirError::MissingUid)
+    ));
+    assert_eq!(vdir.items().unwrap(), []);
+}
+
+#[test]
+fn store_writes_only_changes_and_reports_them() {
+    let dir = TempDir::new("store");
+    let vdir = Vdir::create(&dir.0).unwrap();
+
+    let calendar = Calendar::join([item("a", "One"), item("b", "Two")]);
+    let changes = vdir.store(&calendar).unwrap();
+    assert_eq!(changes.len(), 2);
+    assert!(
+        changes
+            .iter()
+            .all(|change| matches!(change, ItemChange::Added(_)))
+    );
+    let before = vdir.items().unwrap();
+
+    // nothing changed, nothing is written
+    assert_eq!(vdir.store(&calendar).unwrap(), []);
+    assert_eq!(vdir.changes(&before).unwrap(), []);
+
+    let calendar = Calendar::join([item("a", "One, edited"), item("c", "Three")]);
+    let changes = vdir.store(&calendar).unwrap();
+    let changed = changes
+        .iter()
+        .map(|change| match change {
+            ItemChange::Added(item) => format!("+{}", item.href),
+            ItemChange::Modified(item) => format!("~{}", item.href),
+            ItemChange::Removed(href) => format!("-{href}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(changed, ["~a.ics", "+c.ics", "-b.ics"]);
+
+    let mut seen = vdir.changes(&before).unwrap();
+    seen.sort_by_key(|change| format!("{change:?}"));
+    assert_eq!(seen.len(), 3);
+    assert_eq!(summaries(&vdir.load().unwrap()), ["One, edited", "Three"]);
+    // no temporary files are left behind
+    assert_eq!(fs::read_dir(&dir.0).unwrap().count(), 2);
+}
+
+#[test]
+fn store_splits_files_holding_several_items() {
+    let dir = TempDir::new("split");
+    let vdir = Vdir::create(&dir.0).unwrap();
+    let both = Calendar::join([item("a", "One"), item("b", "Two")]);
+    fs::write(dir.0.join("both.ics"), both.to_string()).unwrap();
+
+    let calendar = Calendar::join([item("a", "One, edited"), item("b", "Two")]);
+    let changes = vdir.store(&calendar).unwrap();
+    let changed = changes
+        .iter()
+        .map(|change| match change {
+            ItemChange::Added(item) => format!("+{}", item.href),
+            ItemChange::Modified(item) => format!("~{}", item.href),
+            ItemChange::Removed(href) => format!("-{href}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(changed, ["+a.ics", "+b.ics", "-both.ics"]);
+    assert_eq!(summaries(&vdir.load().unwrap()), ["One, edited", "Two"]);
+
+    // a file named after one of its items is split up as well
+    fs::remove_file(dir.0.join("b.ics")).unwrap();
+    fs::write(
+        dir.0.join("a.ics"),
+        Calendar::join([item("a", "One"), item("b", "Two")]).to_string(),
+    )
+    .unwrap();
+    let changes = vdir.store(&Calendar::join([item("b", "Two")])).unwrap();
+    assert!(
+        matches!(&changes[..], [ItemChange::Added(item), ItemChange::Removed(href)]
+        if item.href == "b.ics" && href == "a.ics")
+    );
+    assert_eq!(summaries(&vdir.load().unwrap()), ["Two"]);
+    assert_eq!(vdir.items().unwrap().len(), 1);
+}
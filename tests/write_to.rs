@@ -0,0 +1,82 @@
+use std::io;
+
+use chrono::*;
+use icalendar::*;
+use pretty_assertions::assert_eq;
+
+fn event(uid: &str) -> Event {
+    Event::with_uid(uid)
+        .timestamp(Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap())
+        .starts(NaiveDate::from_ymd_opt(2026, 3, 2).unwrap())
+        .summary("a summary that is long enough to be folded, or at least it should be by now")
+        .done()
+}
+
+fn calendar() -> Calendar {
+    Calendar::new()
+        .name("streamed")
+        .push(event("first"))
+        .push(event("second"))
+        .done()
+}
+
+#[test]
+fn write_to_matches_to_string() {
+    let calendar = calendar();
+    let mut out = Vec::new();
+    calendar.write_to(&mut out).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), calendar.to_string());
+
+    let event = event("single");
+    let mut out = Vec::new();
+    event.write_to(&mut out).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), event.to_string());
+}
+
+#[test]
+fn property_is_folded() {
+    let mut out = Vec::new();
+    Property::new("DESCRIPTION", "x".repeat(100))
+        .write_to(&mut out)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        format!("DESCRIPTION:{}\r\n {}\r\n", "x".repeat(63), "x".repeat(37))
+    );
+}
+
+#[test]
+fn incremental_writer_matches_calendar() {
+    let calendar = calendar();
+    let header = Calendar::new().name("streamed").done();
+
+    let mut writer = CalendarWriter::new(Vec::new(), &header).unwrap();
+    for component in calendar.iter() {
+        writer.write(component).unwrap();
+    }
+    let out = writer.finish().unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), calendar.to_string());
+}
+
+#[derive(Debug)]
+struct Failing;
+
+impl io::Write for Failing {
+    fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::StorageFull, "disk full"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn io_errors_are_passed_through() {
+    let error = calendar().write_to(&mut Failing).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::StorageFull);
+    assert_eq!(error.to_string(), "disk full");
+
+    let error = CalendarWriter::new(Failing, &Calendar::new()).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::StorageFull);
+}
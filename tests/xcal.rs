@@ -0,0 +1,152 @@
+#![cfg(feature = "xcal")]
+use icalendar::{
+    Calendar,
+    parser::{read_calendar, unfold},
+    xcal::{self, XcalError},
+};
+use pretty_assertions::assert_eq;
+
+const SAMPLE: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//test//test//EN\r
+BEGIN:VEVENT\r
+UID:weekly@example.com\r
+DTSTAMP:20260301T120000Z\r
+DTSTART;TZID=Europe/Berlin:20260302T090000\r
+DURATION:PT1H\r
+RRULE:FREQ=WEEKLY;UNTIL=20260330T080000Z;BYDAY=MO,WE\r
+EXDATE;TZID=Europe/Berlin:20260309T090000,20260311T090000\r
+RDATE;VALUE=PERIOD:20260401T070000Z/PT2H,20260402T070000Z/20260402T080000Z\r
+SUMMARY:Standup <daily> & more\r
+CATEGORIES:WORK,TEAM\r
+GEO:52.52;13.405\r
+PRIORITY:5\r
+ATTENDEE;CN=Alice;MEMBER=\"mailto:team@example.com\":mailto:alice@example.com\r
+X-ANSWER:42\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+DESCRIPTION:Reminder\r
+TRIGGER:-PT15M\r
+END:VALARM\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+fn encoded() -> String {
+    let unfolded = unfold(SAMPLE);
+    xcal::encode(&read_calendar(&unfolded).unwrap())
+}
+
+#[test]
+fn encodes_typed_value_elements() {
+    let xml = encoded();
+    assert!(xml.starts_with(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+         <icalendar xmlns=\"urn:ietf:params:xml:ns:icalendar-2.0\"><vcalendar><properties>\
+         <version><text>2.0</text></version>"
+    ));
+    for expected in [
+        "<dtstart><parameters><tzid><text>Europe/Berlin</text></tzid></parameters>\
+         <date-time>2026-03-02T09:00:00</date-time></dtstart>",
+        "<duration><duration>PT1H</duration></duration>",
+        "<rrule><recur><freq>WEEKLY</freq><until>2026-03-30T08:00:00Z</until>\
+         <byday>MO</byday><byday>WE</byday></recur></rrule>",
+        "<date-time>2026-03-09T09:00:00</date-time><date-time>2026-03-11T09:00:00</date-time>",
+        "<rdate><period><start>2026-04-01T07:00:00Z</start><duration>PT2H</duration></period>\
+         <period><start>2026-04-02T07:00:00Z</start><end>2026-04-02T08:00:00Z</end></period></rdate>",
+        "<summary><text>Standup &lt;daily&gt; &amp; more</text></summary>",
+        "<categories><text>WORK</text><text>TEAM</text></categories>",
+        "<geo><latitude>52.52</latitude><longitude>13.405</longitude></geo>",
+        "<priority><integer>5</integer></priority>",
+        "<member><cal-address>mailto:team@example.com</cal-address></member>",
+        "<x-answer><unknown>42</unknown></x-answer>",
+        "<components><valarm><properties>",
+        "<trigger><duration>-PT15M</duration></trigger>",
+    ] {
+        assert!(xml.contains(expected), "{expected} not in {xml}");
+    }
+}
+
+#[test]
+fn decodes_back_to_text() {
+    let unfolded = unfold(SAMPLE);
+    let original = read_calendar(&unfolded).unwrap();
+    let decoded = xcal::decode(&encoded()).unwrap();
+    assert_eq!(decoded.to_string(), original.to_string());
+}
+
+#[test]
+fn builder_round_trip() {
+    let calendar: Calendar = SAMPLE.parse().unwrap();
+    let decoded = xcal::from_str(&xcal::to_string(&calendar)).unwrap();
+    assert_eq!(decoded, calendar);
+}
+
+#[cfg(feature = "jcal")]
+#[test]
+fn agrees_with_jcal() {
+    let calendar: Calendar = SAMPLE.parse().unwrap();
+    let via_json = icalendar::jcal::from_str(&icalendar::jcal::to_string(&calendar)).unwrap();
+    let via_xml = xcal::from_str(&xcal::to_string(&calendar)).unwrap();
+    assert_eq!(via_json, via_xml);
+}
+
+#[test]
+fn namespaces_are_resolved() {
+    let xml = r#"<?xml version="1.0"?>
+<xc:icalendar xmlns:xc="urn:ietf:params:xml:ns:icalendar-2.0" xmlns:ext="urn:example:extension">
+  <xc:vcalendar>
+    <xc:properties>
+      <xc:prodid><xc:text>-//Example//EN</xc:text></xc:prodid>
+      <ext:note><xc:text>ignored</xc:text></ext:note>
+    </xc:properties>
+    <xc:components>
+      <xc:vevent>
+        <xc:properties>
+          <xc:uid><xc:text>ns@example.com</xc:text></xc:uid>
+          <xc:dtstart><xc:date>2026-03-02</xc:date></xc:dtstart>
+          <xc:summary><xc:text><![CDATA[Tea & cake]]> &#x2615;</xc:text></xc:summary>
+          <xc:x-flag><xc:boolean>true</xc:boolean></xc:x-flag>
+        </xc:properties>
+      </xc:vevent>
+    </xc:components>
+  </xc:vcalendar>
+</xc:icalendar>"#;
+    assert_eq!(
+        xcal::decode(xml).unwrap().to_string(),
+        "BEGIN:VCALENDAR\r
+PRODID:-//Example//EN\r
+BEGIN:VEVENT\r
+UID:ns@example.com\r
+DTSTART;VALUE=DATE:20260302\r
+SUMMARY:Tea & cake \u{2615}\r
+X-FLAG;VALUE=BOOLEAN:TRUE\r
+END:VEVENT\r
+END:VCALENDAR\r
+"
+    );
+}
+
+#[test]
+fn rejects_malformed_input() {
+    assert!(matches!(
+        xcal::decode("<icalendar xmlns=\"urn:ietf:params:xml:ns:icalendar-2.0\"><vcalendar>"),
+        Err(XcalError::Xml(_)) | Err(XcalError::Malformed(_))
+    ));
+    assert_eq!(
+        xcal::decode("<icalendar><vcalendar/></icalendar>").unwrap_err(),
+        XcalError::Malformed("no element of the xCal namespace")
+    );
+    assert_eq!(
+        xcal::decode("<icalendar xmlns=\"urn:ietf:params:xml:ns:icalendar-2.0\"/>").unwrap_err(),
+        XcalError::Malformed("icalendar contains no vcalendar")
+    );
+    assert_eq!(
+        xcal::decode(
+            "<icalendar xmlns=\"urn:ietf:params:xml:ns:icalendar-2.0\"><vcalendar><properties>\
+             <version></version></properties></vcalendar></icalendar>"
+        )
+        .unwrap_err(),
+        XcalError::Malformed("a property needs a value")
+    );
+}